@@ -0,0 +1,2 @@
+pub(crate) mod time_buckets;
+pub(crate) mod time_of_day;
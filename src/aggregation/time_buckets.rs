@@ -0,0 +1,229 @@
+use chrono::{DateTime, Datelike, Days, Months, NaiveDate, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BucketSize {
+    Day,
+    Week,
+    Month,
+}
+
+/**
+ * A half open interval [start, end) in UTC covering one local day, week or month
+ */
+#[derive(Clone, Copy)]
+pub struct TimeBucket {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+/**
+ * The UTC instant at which the given local date begins. Midnight doesn't exist on the days some timezones move their
+ * clocks forward at midnight, in which case the day begins at the first instant after the gap.
+ */
+pub fn start_of_local_day(date: NaiveDate, timezone: Tz) -> DateTime<Utc> {
+    let midnight = date.and_hms_opt(0, 0, 0).expect("midnight is a valid time");
+
+    (0..=3)
+        .find_map(|hours| {
+            timezone
+                .from_local_datetime(&(midnight + chrono::TimeDelta::hours(hours)))
+                .earliest()
+        })
+        .map(|local| local.with_timezone(&Utc))
+        .unwrap_or_else(|| Utc.from_utc_datetime(&midnight))
+}
+
+fn first_day_of_bucket(date: NaiveDate, size: BucketSize) -> NaiveDate {
+    match size {
+        BucketSize::Day => date,
+        BucketSize::Week => date - Days::new(date.weekday().num_days_from_monday() as u64),
+        BucketSize::Month => date.with_day(1).expect("every month has a first day"),
+    }
+}
+
+fn next_bucket_day(date: NaiveDate, size: BucketSize) -> NaiveDate {
+    match size {
+        BucketSize::Day => date + Days::new(1),
+        BucketSize::Week => date + Days::new(7),
+        BucketSize::Month => date + Months::new(1),
+    }
+}
+
+/**
+ * Splits the range into consecutive buckets according to the calendar in the given timezone. The first bucket starts
+ * at the beginning of the local day, week (starting on Monday) or month containing 'from', so it may begin before
+ * 'from'. Returns None if the range would need more than 'max_buckets' buckets.
+ */
+pub fn to_buckets(
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    size: BucketSize,
+    timezone: Tz,
+    max_buckets: usize,
+) -> Option<Vec<TimeBucket>> {
+    let mut buckets = vec![];
+    let mut day = first_day_of_bucket(from.with_timezone(&timezone).date_naive(), size);
+
+    loop {
+        let start = start_of_local_day(day, timezone);
+
+        if start > to {
+            return Some(buckets);
+        }
+
+        if buckets.len() == max_buckets {
+            return None;
+        }
+
+        day = next_bucket_day(day, size);
+
+        buckets.push(TimeBucket {
+            start,
+            end: start_of_local_day(day, timezone),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeDelta, TimeZone, Utc};
+    use chrono_tz::{America::New_York, Europe::London, Pacific::Auckland};
+
+    use super::*;
+
+    fn utc(year: i32, month: u32, day: u32, hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(year, month, day, hour, 0, 0).unwrap()
+    }
+
+    fn lengths(buckets: &[TimeBucket]) -> Vec<TimeDelta> {
+        buckets
+            .iter()
+            .map(|bucket| bucket.end - bucket.start)
+            .collect()
+    }
+
+    #[test]
+    fn day_is_23_hours_when_the_clocks_go_forward() {
+        let buckets = to_buckets(
+            utc(2025, 3, 29, 12),
+            utc(2025, 3, 31, 12),
+            BucketSize::Day,
+            London,
+            10,
+        )
+        .unwrap();
+
+        assert_eq!(buckets[0].start, utc(2025, 3, 29, 0));
+        // Midnight on 31 March is in BST, an hour before midnight UTC
+        assert_eq!(buckets[2].start, utc(2025, 3, 30, 23));
+        assert_eq!(
+            lengths(&buckets),
+            [
+                TimeDelta::hours(24),
+                TimeDelta::hours(23),
+                TimeDelta::hours(24)
+            ]
+        );
+    }
+
+    #[test]
+    fn day_is_25_hours_when_the_clocks_go_back() {
+        let buckets = to_buckets(
+            utc(2025, 10, 25, 12),
+            utc(2025, 10, 27, 12),
+            BucketSize::Day,
+            London,
+            10,
+        )
+        .unwrap();
+
+        assert_eq!(buckets[0].start, utc(2025, 10, 24, 23));
+        assert_eq!(buckets[2].start, utc(2025, 10, 27, 0));
+        assert_eq!(
+            lengths(&buckets),
+            [
+                TimeDelta::hours(24),
+                TimeDelta::hours(25),
+                TimeDelta::hours(24)
+            ]
+        );
+    }
+
+    #[test]
+    fn week_starts_on_the_local_monday() {
+        // Sunday 1 June in UTC, but already Monday 2 June in New Zealand
+        let from = utc(2025, 6, 1, 14);
+
+        let buckets = to_buckets(from, from, BucketSize::Week, Auckland, 10).unwrap();
+
+        assert_eq!(buckets.len(), 1);
+        assert_eq!(
+            buckets[0].start.with_timezone(&Auckland).date_naive(),
+            NaiveDate::from_ymd_opt(2025, 6, 2).unwrap()
+        );
+        assert_eq!(buckets[0].start, utc(2025, 6, 1, 12));
+        assert_eq!(buckets[0].end, utc(2025, 6, 8, 12));
+    }
+
+    #[test]
+    fn month_follows_the_local_calendar() {
+        // Still 28 February in New York
+        let from = utc(2025, 3, 1, 3);
+
+        let buckets =
+            to_buckets(from, utc(2025, 3, 15, 0), BucketSize::Month, New_York, 10).unwrap();
+
+        assert_eq!(buckets.len(), 2);
+        assert_eq!(buckets[0].start, utc(2025, 2, 1, 5));
+        assert_eq!(buckets[0].end, utc(2025, 3, 1, 5));
+        // April begins in daylight saving time, an hour earlier in UTC than March did
+        assert_eq!(buckets[1].start, utc(2025, 3, 1, 5));
+        assert_eq!(buckets[1].end, utc(2025, 4, 1, 4));
+    }
+
+    #[test]
+    fn range_starting_partway_through_a_bucket_includes_the_whole_bucket() {
+        // A Wednesday afternoon to the following Tuesday
+        let from = utc(2025, 1, 15, 15);
+        let to = utc(2025, 1, 21, 9);
+
+        let buckets = to_buckets(from, to, BucketSize::Week, London, 10).unwrap();
+
+        assert_eq!(buckets.len(), 2);
+        assert_eq!(buckets[0].start, utc(2025, 1, 13, 0));
+        assert!(buckets[0].start < from && from < buckets[0].end);
+        assert_eq!(buckets[1].start, utc(2025, 1, 20, 0));
+        assert!(buckets[1].start <= to && to < buckets[1].end);
+    }
+
+    #[test]
+    fn buckets_are_contiguous() {
+        let buckets = to_buckets(
+            utc(2025, 1, 1, 0),
+            utc(2025, 12, 31, 0),
+            BucketSize::Day,
+            London,
+            400,
+        )
+        .unwrap();
+
+        assert_eq!(buckets.len(), 365);
+        assert!(buckets.windows(2).all(|pair| pair[0].end == pair[1].start));
+    }
+
+    #[test]
+    fn range_needing_too_many_buckets_is_rejected() {
+        let result = to_buckets(
+            utc(2025, 1, 1, 0),
+            utc(2025, 1, 31, 0),
+            BucketSize::Day,
+            London,
+            30,
+        );
+
+        assert!(result.is_none());
+    }
+}
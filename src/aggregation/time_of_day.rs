@@ -0,0 +1,124 @@
+use chrono::NaiveTime;
+use chrono_tz::Tz;
+use serde::Serialize;
+
+use crate::repositories::blood_pressure_readings_repository::BloodPressureReadingEntity;
+
+/**
+ * A window of local time. The start is inclusive and the end exclusive. A window whose end is before its start
+ * wraps around midnight, e.g. 22:00 to 02:00.
+ */
+#[derive(Clone, Copy, Serialize)]
+pub struct TimeWindow {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl TimeWindow {
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        if self.start <= self.end {
+            time >= self.start && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+}
+
+/**
+ * How far apart the morning and evening averages have to be before the difference is considered clinically
+ * significant
+ */
+#[derive(Clone, Copy, Serialize)]
+pub struct DivergenceThresholds {
+    pub systolic: f64,
+    pub diastolic: f64,
+}
+
+#[derive(Serialize)]
+pub struct WindowAverages {
+    pub count: i64,
+    pub systolic_mean: Option<f64>,
+    pub diastolic_mean: Option<f64>,
+    pub pulse_mean: Option<f64>,
+}
+
+#[derive(Serialize)]
+pub struct MorningEveningSplit {
+    pub morning: WindowAverages,
+    pub evening: WindowAverages,
+    /**
+     * The morning average minus the evening average. None unless both windows contain readings
+     */
+    pub systolic_difference: Option<f64>,
+    pub diastolic_difference: Option<f64>,
+    pub diverges: bool,
+}
+
+fn mean(values: &[i32]) -> Option<f64> {
+    match values.len() {
+        0 => None,
+        length => Some(values.iter().map(|value| *value as f64).sum::<f64>() / length as f64),
+    }
+}
+
+fn to_window_averages(readings: &[&BloodPressureReadingEntity]) -> WindowAverages {
+    let systolic: Vec<i32> = readings.iter().map(|reading| reading.systolic).collect();
+    let diastolic: Vec<i32> = readings.iter().map(|reading| reading.diastolic).collect();
+    let pulse: Vec<i32> = readings.iter().map(|reading| reading.pulse).collect();
+
+    WindowAverages {
+        count: readings.len() as i64,
+        systolic_mean: mean(&systolic),
+        diastolic_mean: mean(&diastolic),
+        pulse_mean: mean(&pulse),
+    }
+}
+
+fn difference(morning: Option<f64>, evening: Option<f64>) -> Option<f64> {
+    match (morning, evening) {
+        (Some(morning), Some(evening)) => Some(morning - evening),
+        _ => None,
+    }
+}
+
+/**
+ * Splits readings into morning and evening by the local time of day they were taken, as home blood pressure
+ * monitoring protocols ask for. Readings outside both windows are left out.
+ */
+pub fn split_morning_evening(
+    readings: &[BloodPressureReadingEntity],
+    timezone: Tz,
+    morning_window: TimeWindow,
+    evening_window: TimeWindow,
+    thresholds: DivergenceThresholds,
+) -> MorningEveningSplit {
+    let local_time =
+        |reading: &BloodPressureReadingEntity| reading.taken.with_timezone(&timezone).time();
+
+    let morning: Vec<&BloodPressureReadingEntity> = readings
+        .iter()
+        .filter(|reading| morning_window.contains(local_time(reading)))
+        .collect();
+
+    let evening: Vec<&BloodPressureReadingEntity> = readings
+        .iter()
+        .filter(|reading| evening_window.contains(local_time(reading)))
+        .collect();
+
+    let morning = to_window_averages(&morning);
+    let evening = to_window_averages(&evening);
+
+    let systolic_difference = difference(morning.systolic_mean, evening.systolic_mean);
+    let diastolic_difference = difference(morning.diastolic_mean, evening.diastolic_mean);
+
+    let diverges = systolic_difference.is_some_and(|d| d.abs() >= thresholds.systolic)
+        || diastolic_difference.is_some_and(|d| d.abs() >= thresholds.diastolic);
+
+    MorningEveningSplit {
+        morning,
+        evening,
+        systolic_difference,
+        diastolic_difference,
+        diverges,
+    }
+}
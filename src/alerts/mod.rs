@@ -0,0 +1 @@
+pub(crate) mod rule_engine;
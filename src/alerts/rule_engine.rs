@@ -0,0 +1,462 @@
+use crate::repositories::{
+    alert_repository::{
+        AbsoluteThresholdRule, AlertKind, AlertRulesEntity, ConsecutiveHighRule, SuddenJumpRule,
+    },
+    blood_pressure_readings_repository::{
+        BloodPressureReadingEntity, BloodPressureReadingRepository, ReadingCursor,
+        ReadingPageQuery, RetrieveError,
+    },
+};
+
+pub struct TriggeredAlert {
+    pub kind: AlertKind,
+    pub message: String,
+}
+
+fn check_absolute_threshold(
+    rule: AbsoluteThresholdRule,
+    reading: &BloodPressureReadingEntity,
+) -> Option<TriggeredAlert> {
+    if reading.systolic <= rule.systolic && reading.diastolic <= rule.diastolic {
+        return None;
+    }
+
+    Some(TriggeredAlert {
+        kind: AlertKind::AbsoluteThreshold,
+        message: format!(
+            "Reading of {}/{} is above the alert threshold of {}/{}",
+            reading.systolic, reading.diastolic, rule.systolic, rule.diastolic
+        ),
+    })
+}
+
+/**
+ * `previous` holds the readings taken before the new one, most recent first
+ */
+fn check_consecutive_high(
+    rule: ConsecutiveHighRule,
+    reading: &BloodPressureReadingEntity,
+    previous: &[BloodPressureReadingEntity],
+) -> Option<TriggeredAlert> {
+    let needed_previous = (rule.count as usize).saturating_sub(1);
+
+    if rule.count == 0 || previous.len() < needed_previous {
+        return None;
+    }
+
+    let is_high = |reading: &BloodPressureReadingEntity| {
+        reading.systolic >= rule.systolic || reading.diastolic >= rule.diastolic
+    };
+
+    let all_high =
+        is_high(reading) && previous.iter().take(needed_previous).all(is_high);
+
+    match all_high {
+        false => None,
+        true => Some(TriggeredAlert {
+            kind: AlertKind::ConsecutiveHigh,
+            message: format!(
+                "The last {} readings were all at or above {}/{}",
+                rule.count, rule.systolic, rule.diastolic
+            ),
+        }),
+    }
+}
+
+/**
+ * Compares the new reading with the average of the readings before it. Nothing is reported until there are enough
+ * earlier readings to fill the window, since an average of one or two readings isn't a meaningful baseline.
+ */
+fn check_sudden_jump(
+    rule: SuddenJumpRule,
+    reading: &BloodPressureReadingEntity,
+    previous: &[BloodPressureReadingEntity],
+) -> Option<TriggeredAlert> {
+    let window = rule.window as usize;
+
+    if window == 0 || previous.len() < window {
+        return None;
+    }
+
+    let baseline = &previous[..window];
+    let mean = |value: fn(&BloodPressureReadingEntity) -> i32| {
+        baseline.iter().map(value).sum::<i32>() as f64 / window as f64
+    };
+
+    let mean_systolic = mean(|reading| reading.systolic);
+    let mean_diastolic = mean(|reading| reading.diastolic);
+
+    let systolic_jump = reading.systolic as f64 - mean_systolic;
+    let diastolic_jump = reading.diastolic as f64 - mean_diastolic;
+
+    if systolic_jump < rule.systolic_increase as f64
+        && diastolic_jump < rule.diastolic_increase as f64
+    {
+        return None;
+    }
+
+    Some(TriggeredAlert {
+        kind: AlertKind::SuddenJump,
+        message: format!(
+            "Reading of {}/{} is {:.0}/{:.0} above the average of the previous {} readings",
+            reading.systolic, reading.diastolic, systolic_jump, diastolic_jump, window
+        ),
+    })
+}
+
+/**
+ * Runs the user's alert rules against a newly saved reading. The readings it is compared against are fetched through
+ * the repository trait so the rules can be evaluated against any implementation of it.
+ */
+pub async fn evaluate_alert_rules<T: BloodPressureReadingRepository>(
+    reading_repository: &T,
+    rules: &AlertRulesEntity,
+    reading: &BloodPressureReadingEntity,
+) -> Result<Vec<TriggeredAlert>, RetrieveError> {
+    let needed_previous = [
+        rules
+            .consecutive_high
+            .map(|rule| rule.count.saturating_sub(1)),
+        rules.sudden_jump.map(|rule| rule.window),
+    ]
+    .into_iter()
+    .flatten()
+    .max()
+    .unwrap_or(0);
+
+    let previous = match needed_previous {
+        0 => vec![],
+        limit => {
+            let query = ReadingPageQuery {
+                from: None,
+                to: None,
+                tag: None,
+                after: Some(ReadingCursor {
+                    taken: reading.taken,
+                    reading_id: reading.reading_id.clone(),
+                }),
+                limit,
+            };
+
+            reading_repository
+                .list_page(reading.user_id.clone(), query)
+                .await?
+        }
+    };
+
+    let alerts = [
+        rules
+            .absolute_threshold
+            .and_then(|rule| check_absolute_threshold(rule, reading)),
+        rules
+            .consecutive_high
+            .and_then(|rule| check_consecutive_high(rule, reading, &previous)),
+        rules
+            .sudden_jump
+            .and_then(|rule| check_sudden_jump(rule, reading, &previous)),
+    ];
+
+    Ok(alerts.into_iter().flatten().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use chrono::{DateTime, TimeDelta, TimeZone, Utc};
+
+    use super::*;
+    use crate::{
+        aggregation::time_buckets::TimeBucket,
+        repositories::{
+            blood_pressure_readings_repository::{
+                BatchReadingEntity, BatchSaveOutcome, BucketAggregateEntity, DeleteError,
+                MeasurementSessionEntity, ReadingSummaryEntity, SaveError,
+            },
+            webhook_repository::WebhookEventEntity,
+        },
+    };
+
+    const USER_ID: &str = "user";
+
+    /**
+     * Holds readings in memory and pages through them the same way as the SQLite repository, which is all the rule
+     * engine needs
+     */
+    struct InMemoryReadingRepository {
+        readings: Vec<BloodPressureReadingEntity>,
+    }
+
+    impl BloodPressureReadingRepository for InMemoryReadingRepository {
+        async fn save(
+            &self,
+            _entity: BloodPressureReadingEntity,
+            _webhook_events: Vec<WebhookEventEntity>,
+        ) -> Result<(), SaveError> {
+            unimplemented!()
+        }
+
+        async fn save_batch(
+            &self,
+            _entities: Vec<BatchReadingEntity>,
+        ) -> Result<Vec<BatchSaveOutcome>, SaveError> {
+            unimplemented!()
+        }
+
+        async fn update(
+            &self,
+            _entity: BloodPressureReadingEntity,
+        ) -> Result<BloodPressureReadingEntity, SaveError> {
+            unimplemented!()
+        }
+
+        async fn delete(&self, _user_id: String, _reading_id: String) -> Result<(), DeleteError> {
+            unimplemented!()
+        }
+
+        async fn list(
+            &self,
+            _user_id: String,
+            _from: DateTime<Utc>,
+            _to: DateTime<Utc>,
+            _tag: Option<String>,
+        ) -> Result<Vec<BloodPressureReadingEntity>, RetrieveError> {
+            unimplemented!()
+        }
+
+        async fn list_page(
+            &self,
+            user_id: String,
+            query: ReadingPageQuery,
+        ) -> Result<Vec<BloodPressureReadingEntity>, RetrieveError> {
+            let mut readings: Vec<BloodPressureReadingEntity> = self
+                .readings
+                .iter()
+                .filter(|reading| reading.user_id == user_id)
+                .filter(|reading| match &query.after {
+                    None => true,
+                    Some(cursor) => {
+                        (reading.taken, &reading.reading_id) < (cursor.taken, &cursor.reading_id)
+                    }
+                })
+                .cloned()
+                .collect();
+
+            readings.sort_by(|a, b| (b.taken, &b.reading_id).cmp(&(a.taken, &a.reading_id)));
+            readings.truncate(query.limit as usize);
+
+            Ok(readings)
+        }
+
+        async fn list_tags(&self, _user_id: String) -> Result<Vec<String>, RetrieveError> {
+            unimplemented!()
+        }
+
+        async fn find_by_idempotency_keys(
+            &self,
+            _user_id: String,
+            _idempotency_keys: Vec<String>,
+        ) -> Result<HashMap<String, String>, RetrieveError> {
+            unimplemented!()
+        }
+
+        async fn find_measurement_session(
+            &self,
+            _user_id: String,
+            _from: DateTime<Utc>,
+            _to: DateTime<Utc>,
+        ) -> Result<Option<String>, RetrieveError> {
+            unimplemented!()
+        }
+
+        async fn list_measurement_sessions(
+            &self,
+            _user_id: String,
+            _from: DateTime<Utc>,
+            _to: DateTime<Utc>,
+            _discard_first: bool,
+        ) -> Result<Vec<MeasurementSessionEntity>, RetrieveError> {
+            unimplemented!()
+        }
+
+        async fn summarise(
+            &self,
+            _user_id: String,
+            _from: DateTime<Utc>,
+            _to: DateTime<Utc>,
+        ) -> Result<ReadingSummaryEntity, RetrieveError> {
+            unimplemented!()
+        }
+
+        async fn aggregate_by_bucket(
+            &self,
+            _user_id: String,
+            _buckets: &[TimeBucket],
+            _from: DateTime<Utc>,
+            _to: DateTime<Utc>,
+        ) -> Result<Vec<BucketAggregateEntity>, RetrieveError> {
+            unimplemented!()
+        }
+    }
+
+    /**
+     * A reading taken the given number of minutes after the start of the test's day
+     */
+    fn reading(minutes: i64, systolic: i32, diastolic: i32) -> BloodPressureReadingEntity {
+        let start = Utc.with_ymd_and_hms(2025, 3, 1, 8, 0, 0).unwrap();
+
+        BloodPressureReadingEntity {
+            reading_id: format!("reading-{:04}", minutes),
+            user_id: USER_ID.to_string(),
+            systolic,
+            diastolic,
+            pulse: 70,
+            weight_kilograms: None,
+            taken: start + TimeDelta::minutes(minutes),
+            measurement_session_id: None,
+            note: None,
+            arm: None,
+            body_position: None,
+            cuff_size: None,
+            tags: vec![],
+        }
+    }
+
+    fn rules(
+        absolute_threshold: Option<AbsoluteThresholdRule>,
+        consecutive_high: Option<ConsecutiveHighRule>,
+        sudden_jump: Option<SuddenJumpRule>,
+    ) -> AlertRulesEntity {
+        AlertRulesEntity {
+            user_id: USER_ID.to_string(),
+            absolute_threshold,
+            consecutive_high,
+            sudden_jump,
+        }
+    }
+
+    async fn evaluate(
+        previous: Vec<BloodPressureReadingEntity>,
+        rules: &AlertRulesEntity,
+        new_reading: &BloodPressureReadingEntity,
+    ) -> Vec<AlertKind> {
+        let repository = InMemoryReadingRepository { readings: previous };
+
+        evaluate_alert_rules(&repository, rules, new_reading)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|alert| alert.kind)
+            .collect()
+    }
+
+    const THRESHOLD: AbsoluteThresholdRule = AbsoluteThresholdRule {
+        systolic: 180,
+        diastolic: 120,
+    };
+
+    const CONSECUTIVE_HIGH: ConsecutiveHighRule = ConsecutiveHighRule {
+        count: 3,
+        systolic: 140,
+        diastolic: 90,
+    };
+
+    const SUDDEN_JUMP: SuddenJumpRule = SuddenJumpRule {
+        window: 3,
+        systolic_increase: 30,
+        diastolic_increase: 20,
+    };
+
+    #[tokio::test]
+    async fn reading_at_the_threshold_does_not_alert() {
+        let rules = rules(Some(THRESHOLD), None, None);
+
+        let alerts = evaluate(vec![], &rules, &reading(0, 180, 120)).await;
+
+        assert_eq!(alerts, vec![]);
+    }
+
+    #[tokio::test]
+    async fn reading_above_the_threshold_alerts() {
+        let rules = rules(Some(THRESHOLD), None, None);
+
+        let alerts = evaluate(vec![], &rules, &reading(0, 185, 125)).await;
+
+        assert_eq!(alerts, vec![AlertKind::AbsoluteThreshold]);
+    }
+
+    #[tokio::test]
+    async fn consecutive_high_needs_count_readings() {
+        let rules = rules(None, Some(CONSECUTIVE_HIGH), None);
+        let previous = vec![reading(0, 150, 95)];
+
+        let alerts = evaluate(previous, &rules, &reading(10, 150, 95)).await;
+
+        assert_eq!(alerts, vec![]);
+    }
+
+    #[tokio::test]
+    async fn consecutive_high_alerts_at_exactly_count_readings() {
+        let rules = rules(None, Some(CONSECUTIVE_HIGH), None);
+        let previous = vec![reading(0, 150, 95), reading(5, 145, 85)];
+
+        let alerts = evaluate(previous, &rules, &reading(10, 120, 92)).await;
+
+        assert_eq!(alerts, vec![AlertKind::ConsecutiveHigh]);
+    }
+
+    #[tokio::test]
+    async fn sudden_jump_needs_a_full_window() {
+        let rules = rules(None, None, Some(SUDDEN_JUMP));
+        let previous = vec![reading(0, 110, 70), reading(5, 110, 70)];
+
+        let alerts = evaluate(previous, &rules, &reading(10, 170, 100)).await;
+
+        assert_eq!(alerts, vec![]);
+    }
+
+    #[tokio::test]
+    async fn sudden_jump_alerts_above_the_window_average() {
+        let rules = rules(None, None, Some(SUDDEN_JUMP));
+        let previous = vec![
+            reading(0, 110, 70),
+            reading(5, 115, 75),
+            reading(10, 120, 80),
+        ];
+
+        let alerts = evaluate(previous, &rules, &reading(15, 150, 80)).await;
+
+        assert_eq!(alerts, vec![AlertKind::SuddenJump]);
+    }
+
+    #[tokio::test]
+    async fn previous_readings_are_the_newest_before_the_reading() {
+        let rules = rules(None, Some(CONSECUTIVE_HIGH), None);
+        // Only the two readings just before the new one count. The older low reading is outside the rule's count and
+        // the low reading taken afterwards comes after the cursor, so neither should stop the alert.
+        let previous = vec![
+            reading(20, 110, 70),
+            reading(0, 110, 70),
+            reading(10, 150, 95),
+            reading(5, 150, 95),
+        ];
+
+        let alerts = evaluate(previous, &rules, &reading(15, 150, 95)).await;
+
+        assert_eq!(alerts, vec![AlertKind::ConsecutiveHigh]);
+    }
+
+    #[tokio::test]
+    async fn older_low_reading_within_count_stops_consecutive_high() {
+        let rules = rules(None, Some(CONSECUTIVE_HIGH), None);
+        let previous = vec![
+            reading(0, 150, 95),
+            reading(5, 110, 70),
+            reading(10, 150, 95),
+        ];
+
+        let alerts = evaluate(previous, &rules, &reading(15, 150, 95)).await;
+
+        assert_eq!(alerts, vec![]);
+    }
+}
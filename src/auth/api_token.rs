@@ -0,0 +1,99 @@
+use std::sync::Arc;
+
+use axum::http::{HeaderMap, Method, header};
+use chrono::Utc;
+
+use crate::{
+    auth::secret_token::hash_secret_token,
+    controllers::api_error::ApiError,
+    repositories::api_token_repository::{ApiTokenRepository, ApiTokenScope},
+};
+
+const TOKEN_MANAGEMENT_PATH: &str = "/api/tokens";
+
+const READINGS_READ_PATHS: [&str; 5] = [
+    "/api/reading",
+    "/api/tags",
+    "/api/export/",
+    "/api/report/",
+    "/api/chart.svg",
+];
+const READINGS_WRITE_PATHS: [&str; 2] = ["/api/reading", "/api/import/"];
+
+/**
+ * Returns the token from an `Authorization: Bearer` header, or None if the request doesn't have one
+ */
+pub fn get_bearer_token(headers: &HeaderMap) -> Option<&str> {
+    let (scheme, token) = headers
+        .get(header::AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .split_once(' ')?;
+
+    scheme
+        .eq_ignore_ascii_case("Bearer")
+        .then_some(token.trim())
+}
+
+fn is_token_management(path: &str) -> bool {
+    path == TOKEN_MANAGEMENT_PATH || path.starts_with(&format!("{}/", TOKEN_MANAGEMENT_PATH))
+}
+
+/**
+ * The scope needed to make the request with a scoped token. None means only a token without scopes can make it.
+ */
+fn required_scope(method: &Method, path: &str) -> Option<ApiTokenScope> {
+    let is_read = *method == Method::GET || *method == Method::HEAD;
+    let matches = |prefixes: &[&str]| prefixes.iter().any(|prefix| path.starts_with(prefix));
+
+    match is_read {
+        true if matches(&READINGS_READ_PATHS) => Some(ApiTokenScope::ReadingsRead),
+        false if matches(&READINGS_WRITE_PATHS) => Some(ApiTokenScope::ReadingsWrite),
+        _ => None,
+    }
+}
+
+fn is_allowed(scopes: &[ApiTokenScope], method: &Method, path: &str) -> bool {
+    // A token that can create tokens could outlive being revoked, so tokens are only managed from a logged in session
+    if is_token_management(path) {
+        return false;
+    }
+
+    if scopes.is_empty() {
+        return true;
+    }
+
+    required_scope(method, path).is_some_and(|scope| scopes.contains(&scope))
+}
+
+/**
+ * Checks the token is one the user has created and that its scopes allow the request, and returns the OIDC subject of
+ * the user it belongs to
+ */
+pub async fn authenticate_api_token<A: ApiTokenRepository>(
+    api_token_repository: &Arc<A>,
+    token: &str,
+    method: &Method,
+    path: &str,
+) -> Result<String, ApiError> {
+    let entity = api_token_repository
+        .get_by_token_hash(hash_secret_token(token))
+        .await?
+        .ok_or(ApiError::Unauthorized)?;
+
+    if !is_allowed(&entity.scopes, method, path) {
+        return Err(ApiError::Forbidden {
+            description: "This token can't be used for that.".to_string(),
+        });
+    }
+
+    // Recording when the token was last used is only to help the user spot stale tokens, so it mustn't fail the request
+    if let Err(error) = api_token_repository
+        .mark_used(entity.token_id, Utc::now())
+        .await
+    {
+        println!("Could not record use of API token: {:?}", error);
+    }
+
+    Ok(entity.user_id)
+}
@@ -1 +1,3 @@
+pub(crate) mod api_token;
 pub(crate) mod oidc;
+pub(crate) mod secret_token;
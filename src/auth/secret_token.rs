@@ -0,0 +1,16 @@
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use sha2::{Digest, Sha256};
+
+/**
+ * A random token for a link or API client, which is shown to the user once and only kept as a hash
+ */
+pub fn generate_secret_token() -> String {
+    URL_SAFE_NO_PAD.encode(rand::random::<[u8; 32]>())
+}
+
+/**
+ * Tokens are random rather than chosen by a person, so a plain hash is enough to keep them safe if the database leaks
+ */
+pub fn hash_secret_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
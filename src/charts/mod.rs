@@ -0,0 +1,2 @@
+pub(crate) mod svg_chart;
+pub(crate) mod trend_chart;
@@ -0,0 +1,355 @@
+use std::fmt::Write;
+
+use crate::charts::trend_chart::{ChartArea, ChartMetric, TrendChart};
+
+pub const SVG_WIDTH: f64 = 800.0;
+pub const SVG_HEIGHT: f64 = 400.0;
+
+/**
+ * Where the plot goes within the image, leaving room for the legend above and the axis labels to the left and below
+ */
+pub const SVG_CHART_AREA: ChartArea = ChartArea {
+    x: 50.0,
+    y: 40.0,
+    width: SVG_WIDTH - 70.0,
+    height: SVG_HEIGHT - 80.0,
+};
+
+const FONT_FAMILY: &str = "Helvetica, Arial, sans-serif";
+const TEXT_COLOUR: &str = "#000000";
+const GRID_COLOUR: &str = "#d2d2d2";
+const TARGET_COLOUR: &str = "#2e8b57";
+
+fn series_colour(metric: ChartMetric) -> &'static str {
+    match metric {
+        ChartMetric::Systolic => "#c82828",
+        ChartMetric::Diastolic => "#2850c8",
+        ChartMetric::Pulse => "#787878",
+    }
+}
+
+pub fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for character in text.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(character),
+        }
+    }
+
+    escaped
+}
+
+fn write_horizontal_line(
+    svg: &mut String,
+    area: ChartArea,
+    y: f64,
+    colour: &str,
+    attributes: &str,
+) {
+    let _ = writeln!(
+        svg,
+        r#"<line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" stroke="{}" stroke-width="1"{}/>"#,
+        area.x,
+        y,
+        area.x + area.width,
+        y,
+        colour,
+        attributes
+    );
+}
+
+fn write_text(svg: &mut String, x: f64, y: f64, size: f64, anchor: &str, text: &str) {
+    let _ = writeln!(
+        svg,
+        r#"<text x="{:.1}" y="{:.1}" font-size="{:.0}" text-anchor="{}" fill="{}">{}</text>"#,
+        x,
+        y,
+        size,
+        anchor,
+        TEXT_COLOUR,
+        escape_xml(text)
+    );
+}
+
+/**
+ * Draws the chart as a standalone SVG document. Everything is inline so it can be embedded in emails and other
+ * places without stylesheets or scripts. The chart should have been built with SVG_CHART_AREA.
+ */
+pub fn render_svg_chart(chart: &TrendChart) -> String {
+    let area = chart.area;
+    let mut svg = String::new();
+
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0:.0}" height="{1:.0}" viewBox="0 0 {0:.0} {1:.0}" font-family="{2}">"#,
+        SVG_WIDTH, SVG_HEIGHT, FONT_FAMILY
+    );
+    let _ = writeln!(
+        svg,
+        r##"<rect width="{:.0}" height="{:.0}" fill="#ffffff"/>"##,
+        SVG_WIDTH, SVG_HEIGHT
+    );
+
+    if chart.series.is_empty() {
+        write_text(
+            &mut svg,
+            SVG_WIDTH / 2.0,
+            SVG_HEIGHT / 2.0,
+            14.0,
+            "middle",
+            "No readings in this period",
+        );
+        svg.push_str("</svg>\n");
+
+        return svg;
+    }
+
+    // Bands overlap, so they are drawn translucent to keep each of them visible
+    for band in &chart.target_bands {
+        let _ = writeln!(
+            svg,
+            r#"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="{}" fill-opacity="0.12"/>"#,
+            area.x,
+            band.top,
+            area.width,
+            band.bottom - band.top,
+            TARGET_COLOUR
+        );
+    }
+
+    for tick in &chart.y_ticks {
+        write_horizontal_line(&mut svg, area, tick.position, GRID_COLOUR, "");
+        write_text(
+            &mut svg,
+            area.x - 6.0,
+            tick.position + 4.0,
+            11.0,
+            "end",
+            &tick.label,
+        );
+    }
+
+    for tick in &chart.x_ticks {
+        write_text(
+            &mut svg,
+            tick.position,
+            area.y + area.height + 18.0,
+            11.0,
+            "middle",
+            &tick.label,
+        );
+    }
+
+    for band in &chart.target_bands {
+        write_horizontal_line(
+            &mut svg,
+            area,
+            band.top,
+            TARGET_COLOUR,
+            r#" stroke-dasharray="4 3""#,
+        );
+        write_text(
+            &mut svg,
+            area.x + 4.0,
+            band.top - 4.0,
+            10.0,
+            "start",
+            &band.label,
+        );
+    }
+
+    for series in &chart.series {
+        let colour = series_colour(series.metric);
+        let points: Vec<String> = series
+            .points
+            .iter()
+            .map(|point| format!("{:.1},{:.1}", point.x, point.y))
+            .collect();
+
+        let _ = writeln!(
+            svg,
+            r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="2"/>"#,
+            points.join(" "),
+            colour
+        );
+
+        for point in &series.points {
+            let _ = writeln!(
+                svg,
+                r#"<circle cx="{:.1}" cy="{:.1}" r="3" fill="{}"/>"#,
+                point.x, point.y, colour
+            );
+        }
+    }
+
+    write_horizontal_line(&mut svg, area, area.y + area.height, TEXT_COLOUR, "");
+
+    for (index, series) in chart.series.iter().enumerate() {
+        let x = area.x + index as f64 * 100.0;
+
+        let _ = writeln!(
+            svg,
+            r#"<rect x="{:.1}" y="14" width="12" height="4" fill="{}"/>"#,
+            x,
+            series_colour(series.metric)
+        );
+        write_text(
+            &mut svg,
+            x + 18.0,
+            20.0,
+            12.0,
+            "start",
+            series.metric.name(),
+        );
+    }
+
+    svg.push_str("</svg>\n");
+
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, path::PathBuf};
+
+    use chrono::{TimeDelta, TimeZone, Utc};
+    use chrono_tz::Tz;
+
+    use super::*;
+    use crate::{
+        charts::trend_chart::build_trend_chart, classification::target_range::TargetRange,
+        repositories::blood_pressure_readings_repository::BloodPressureReadingEntity,
+    };
+
+    /**
+     * Compares the chart with the golden file of the same name in the snapshots directory. Run the tests with
+     * UPDATE_SNAPSHOTS set to write the current output instead, then check the changed files before committing them.
+     */
+    fn assert_snapshot(name: &str, actual: &str) {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src/charts/snapshots")
+            .join(format!("{}.svg", name));
+
+        if env::var_os("UPDATE_SNAPSHOTS").is_some() {
+            fs::write(&path, actual).unwrap();
+            return;
+        }
+
+        let expected = fs::read_to_string(&path)
+            .unwrap_or_else(|_| panic!("Missing snapshot {}", path.display()));
+
+        assert!(
+            expected == actual,
+            "Chart differs from {}, run with UPDATE_SNAPSHOTS=1 to update it\n{}",
+            path.display(),
+            actual
+        );
+    }
+
+    /**
+     * A reading taken the given number of days after the start of March 2025
+     */
+    fn reading(days: i64, systolic: i32, diastolic: i32, pulse: i32) -> BloodPressureReadingEntity {
+        let start = Utc.with_ymd_and_hms(2025, 3, 1, 8, 0, 0).unwrap();
+
+        BloodPressureReadingEntity {
+            reading_id: format!("reading-{}", days),
+            user_id: "user".to_string(),
+            systolic,
+            diastolic,
+            pulse,
+            weight_kilograms: None,
+            taken: start + TimeDelta::days(days),
+            measurement_session_id: None,
+            note: None,
+            arm: None,
+            body_position: None,
+            cuff_size: None,
+            tags: vec![],
+        }
+    }
+
+    fn render(
+        readings: &[BloodPressureReadingEntity],
+        metrics: &[ChartMetric],
+        target_range: Option<TargetRange>,
+    ) -> String {
+        let chart = build_trend_chart(
+            readings,
+            metrics,
+            Tz::Europe__London,
+            target_range,
+            SVG_CHART_AREA,
+        );
+
+        render_svg_chart(&chart)
+    }
+
+    #[test]
+    fn empty_chart() {
+        let svg = render(&[], &[ChartMetric::Systolic, ChartMetric::Diastolic], None);
+
+        assert_snapshot("empty", &svg);
+    }
+
+    #[test]
+    fn single_reading() {
+        let svg = render(
+            &[reading(0, 128, 84, 66)],
+            &[ChartMetric::Systolic, ChartMetric::Diastolic],
+            None,
+        );
+
+        assert_snapshot("single_reading", &svg);
+    }
+
+    #[test]
+    fn pulse_with_target_band() {
+        let readings = [
+            reading(0, 120, 80, 64),
+            reading(3, 122, 81, 88),
+            reading(7, 118, 79, 72),
+            reading(10, 125, 83, 95),
+        ];
+        let targets = TargetRange {
+            systolic: 135,
+            diastolic: 85,
+            pulse_min: Some(60),
+            pulse_max: Some(90),
+        };
+
+        let svg = render(&readings, &[ChartMetric::Pulse], Some(targets));
+
+        assert_snapshot("pulse_with_target_band", &svg);
+    }
+
+    #[test]
+    fn blood_pressure_with_clipped_target_bands() {
+        // The systolic target is above the plotted range, so its band is cut off at the top of the plot
+        let readings = [
+            reading(0, 104, 66, 70),
+            reading(4, 112, 72, 68),
+            reading(9, 108, 70, 71),
+            reading(14, 115, 74, 69),
+        ];
+        let targets = TargetRange {
+            systolic: 150,
+            diastolic: 80,
+            pulse_min: None,
+            pulse_max: None,
+        };
+
+        let svg = render(
+            &readings,
+            &[ChartMetric::Systolic, ChartMetric::Diastolic],
+            Some(targets),
+        );
+
+        assert_snapshot("blood_pressure_with_clipped_target_bands", &svg);
+    }
+}
@@ -0,0 +1,254 @@
+use chrono::{DateTime, TimeDelta, Utc};
+use chrono_tz::Tz;
+
+use crate::{
+    classification::target_range::TargetRange,
+    repositories::blood_pressure_readings_repository::BloodPressureReadingEntity,
+};
+
+const Y_PADDING: i32 = 10;
+const Y_TICK_STEP: i32 = 20;
+const X_TICK_COUNT: i32 = 5;
+
+/**
+ * The rectangle the plot is drawn in. Coordinates have their origin at the top left and grow downwards, as in SVG.
+ */
+#[derive(Clone, Copy)]
+pub struct ChartArea {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+#[derive(Clone, Copy)]
+pub struct ChartPoint {
+    pub x: f64,
+    pub y: f64,
+}
+
+pub struct AxisTick {
+    /**
+     * The x coordinate for ticks on the time axis and the y coordinate for ticks on the value axis
+     */
+    pub position: f64,
+    pub label: String,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ChartMetric {
+    Systolic,
+    Diastolic,
+    Pulse,
+}
+
+impl ChartMetric {
+    pub fn name(self) -> &'static str {
+        match self {
+            ChartMetric::Systolic => "Systolic",
+            ChartMetric::Diastolic => "Diastolic",
+            ChartMetric::Pulse => "Pulse",
+        }
+    }
+
+    fn value(self, reading: &BloodPressureReadingEntity) -> i32 {
+        match self {
+            ChartMetric::Systolic => reading.systolic,
+            ChartMetric::Diastolic => reading.diastolic,
+            ChartMetric::Pulse => reading.pulse,
+        }
+    }
+}
+
+pub struct ChartSeries {
+    pub metric: ChartMetric,
+    pub points: Vec<ChartPoint>,
+}
+
+/**
+ * The part of the plot that is within the user's target for a metric. Blood pressure targets are upper limits, so
+ * their bands run from the bottom of the plot up to the target.
+ */
+pub struct TargetBand {
+    pub metric: ChartMetric,
+    pub top: f64,
+    pub bottom: f64,
+    pub label: String,
+}
+
+/**
+ * The geometry of a trend chart, shared by the PDF report and the SVG chart so that both draw the same thing
+ */
+pub struct TrendChart {
+    pub area: ChartArea,
+    /**
+     * One series per requested metric, in the order they were requested
+     */
+    pub series: Vec<ChartSeries>,
+    pub x_ticks: Vec<AxisTick>,
+    pub y_ticks: Vec<AxisTick>,
+    /**
+     * Bands for the targets of the plotted metrics that are at least partly within the plotted range
+     */
+    pub target_bands: Vec<TargetBand>,
+}
+
+fn round_down(value: i32, step: i32) -> i32 {
+    value.div_euclid(step) * step
+}
+
+fn round_up(value: i32, step: i32) -> i32 {
+    -round_down(-value, step)
+}
+
+/**
+ * The lowest and highest values of the target for a metric, where None means the band reaches the edge of the plot.
+ * Returns None if the user has no target for the metric.
+ */
+fn target_limits(
+    metric: ChartMetric,
+    targets: &TargetRange,
+) -> Option<(Option<i32>, Option<i32>, String)> {
+    match metric {
+        ChartMetric::Systolic => Some((
+            None,
+            Some(targets.systolic),
+            format!("Systolic target below {}", targets.systolic),
+        )),
+        ChartMetric::Diastolic => Some((
+            None,
+            Some(targets.diastolic),
+            format!("Diastolic target below {}", targets.diastolic),
+        )),
+        ChartMetric::Pulse => match (targets.pulse_min, targets.pulse_max) {
+            (None, None) => None,
+            (Some(min), None) => Some((Some(min), None, format!("Pulse target {} and above", min))),
+            (None, Some(max)) => Some((None, Some(max), format!("Pulse target {} and below", max))),
+            (Some(min), Some(max)) => Some((
+                Some(min),
+                Some(max),
+                format!("Pulse target {} to {}", min, max),
+            )),
+        },
+    }
+}
+
+/**
+ * Works out where each reading and axis tick goes within the area. Readings can be in any order. The time axis runs
+ * from the earliest to the latest reading, and the value axis from just below the lowest to just above the highest
+ * value of the requested metrics.
+ */
+pub fn build_trend_chart(
+    readings: &[BloodPressureReadingEntity],
+    metrics: &[ChartMetric],
+    timezone: Tz,
+    target_range: Option<TargetRange>,
+    area: ChartArea,
+) -> TrendChart {
+    let mut sorted: Vec<&BloodPressureReadingEntity> = readings.iter().collect();
+    sorted.sort_by_key(|reading| reading.taken);
+
+    let values = || {
+        sorted
+            .iter()
+            .flat_map(|reading| metrics.iter().map(|metric| metric.value(reading)))
+    };
+
+    let (Some(first), Some(last), Some(lowest), Some(highest)) = (
+        sorted.first(),
+        sorted.last(),
+        values().min(),
+        values().max(),
+    ) else {
+        return TrendChart {
+            area,
+            series: vec![],
+            x_ticks: vec![],
+            y_ticks: vec![],
+            target_bands: vec![],
+        };
+    };
+
+    let start = first.taken;
+    let duration = (last.taken - start).num_seconds();
+
+    let y_min = round_down(lowest - Y_PADDING, Y_TICK_STEP);
+    let y_max = round_up(highest + Y_PADDING, Y_TICK_STEP).max(y_min + Y_TICK_STEP);
+
+    // A single reading, or several taken at the same moment, is drawn in the middle of the time axis
+    let x_for = |taken: DateTime<Utc>| match duration {
+        0 => area.x + area.width / 2.0,
+        _ => area.x + area.width * (taken - start).num_seconds() as f64 / duration as f64,
+    };
+    let y_for = |value: i32| {
+        area.y + area.height - area.height * (value - y_min) as f64 / (y_max - y_min) as f64
+    };
+
+    let series = metrics
+        .iter()
+        .map(|metric| ChartSeries {
+            metric: *metric,
+            points: sorted
+                .iter()
+                .map(|reading| ChartPoint {
+                    x: x_for(reading.taken),
+                    y: y_for(metric.value(reading)),
+                })
+                .collect(),
+        })
+        .collect();
+
+    let x_ticks = match duration {
+        0 => vec![AxisTick {
+            position: x_for(start),
+            label: start.with_timezone(&timezone).format("%e %b").to_string(),
+        }],
+        _ => (0..X_TICK_COUNT)
+            .map(|index| {
+                let taken =
+                    start + TimeDelta::seconds(duration * index as i64 / (X_TICK_COUNT - 1) as i64);
+
+                AxisTick {
+                    position: x_for(taken),
+                    label: taken.with_timezone(&timezone).format("%e %b").to_string(),
+                }
+            })
+            .collect(),
+    };
+
+    let y_ticks = (y_min..=y_max)
+        .step_by(Y_TICK_STEP as usize)
+        .map(|value| AxisTick {
+            position: y_for(value),
+            label: value.to_string(),
+        })
+        .collect();
+
+    let target_bands = target_range
+        .map(|targets| {
+            metrics
+                .iter()
+                .filter_map(|metric| {
+                    let (low, high, label) = target_limits(*metric, &targets)?;
+                    let low = low.unwrap_or(y_min).max(y_min);
+                    let high = high.unwrap_or(y_max).min(y_max);
+
+                    (low < high).then(|| TargetBand {
+                        metric: *metric,
+                        top: y_for(high),
+                        bottom: y_for(low),
+                        label,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    TrendChart {
+        area,
+        series,
+        x_ticks,
+        y_ticks,
+        target_bands,
+    }
+}
@@ -0,0 +1,109 @@
+use serde::{Deserialize, Serialize};
+
+/**
+ * The clinical guideline used to decide which category a reading falls into
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClassificationGuideline {
+    /**
+     * The 2017 American College of Cardiology / American Heart Association guideline
+     */
+    #[default]
+    AccAha2017,
+    /**
+     * The 2023 European Society of Hypertension guideline
+     */
+    Esh2023,
+}
+
+/**
+ * Categories are ordered by severity so that the more severe of the systolic and diastolic categories can be taken
+ * as the category of the reading
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BloodPressureCategory {
+    Normal,
+    Elevated,
+    Stage1Hypertension,
+    Stage2Hypertension,
+    HypertensiveCrisis,
+}
+
+#[derive(Default, Serialize)]
+pub struct CategoryCounts {
+    pub normal: i64,
+    pub elevated: i64,
+    pub stage1_hypertension: i64,
+    pub stage2_hypertension: i64,
+    pub hypertensive_crisis: i64,
+}
+
+impl CategoryCounts {
+    pub fn add(&mut self, category: BloodPressureCategory) {
+        match category {
+            BloodPressureCategory::Normal => self.normal += 1,
+            BloodPressureCategory::Elevated => self.elevated += 1,
+            BloodPressureCategory::Stage1Hypertension => self.stage1_hypertension += 1,
+            BloodPressureCategory::Stage2Hypertension => self.stage2_hypertension += 1,
+            BloodPressureCategory::HypertensiveCrisis => self.hypertensive_crisis += 1,
+        }
+    }
+}
+
+fn classify_acc_aha_2017(systolic: i32, diastolic: i32) -> BloodPressureCategory {
+    let systolic_category = match systolic {
+        s if s > 180 => BloodPressureCategory::HypertensiveCrisis,
+        s if s >= 140 => BloodPressureCategory::Stage2Hypertension,
+        s if s >= 130 => BloodPressureCategory::Stage1Hypertension,
+        s if s >= 120 => BloodPressureCategory::Elevated,
+        _ => BloodPressureCategory::Normal,
+    };
+
+    // 'Elevated' is defined by the systolic value alone
+    let diastolic_category = match diastolic {
+        d if d > 120 => BloodPressureCategory::HypertensiveCrisis,
+        d if d >= 90 => BloodPressureCategory::Stage2Hypertension,
+        d if d >= 80 => BloodPressureCategory::Stage1Hypertension,
+        _ => BloodPressureCategory::Normal,
+    };
+
+    systolic_category.max(diastolic_category)
+}
+
+/**
+ * ESH 2023 uses optimal / normal / high normal / grade 1-3. These are mapped onto the common categories: optimal and
+ * normal are 'normal', high normal is 'elevated', grades 1 and 2 are stages 1 and 2, and grade 3 (>= 180/110) is
+ * treated as a crisis since that is the threshold at which ESH recommends urgent assessment.
+ */
+fn classify_esh_2023(systolic: i32, diastolic: i32) -> BloodPressureCategory {
+    let systolic_category = match systolic {
+        s if s >= 180 => BloodPressureCategory::HypertensiveCrisis,
+        s if s >= 160 => BloodPressureCategory::Stage2Hypertension,
+        s if s >= 140 => BloodPressureCategory::Stage1Hypertension,
+        s if s >= 130 => BloodPressureCategory::Elevated,
+        _ => BloodPressureCategory::Normal,
+    };
+
+    let diastolic_category = match diastolic {
+        d if d >= 110 => BloodPressureCategory::HypertensiveCrisis,
+        d if d >= 100 => BloodPressureCategory::Stage2Hypertension,
+        d if d >= 90 => BloodPressureCategory::Stage1Hypertension,
+        d if d >= 85 => BloodPressureCategory::Elevated,
+        _ => BloodPressureCategory::Normal,
+    };
+
+    systolic_category.max(diastolic_category)
+}
+
+pub fn classify(
+    systolic: i32,
+    diastolic: i32,
+    guideline: ClassificationGuideline,
+) -> BloodPressureCategory {
+    match guideline {
+        ClassificationGuideline::AccAha2017 => classify_acc_aha_2017(systolic, diastolic),
+        ClassificationGuideline::Esh2023 => classify_esh_2023(systolic, diastolic),
+    }
+}
@@ -0,0 +1,9 @@
+/**
+ * Calculates the body mass index (kg/m²) rounded to one decimal place, which is the precision it's usually quoted at
+ */
+pub fn body_mass_index(weight_kilograms: f64, height_centimetres: f64) -> f64 {
+    let height_metres = height_centimetres / 100.0;
+    let bmi = weight_kilograms / (height_metres * height_metres);
+
+    (bmi * 10.0).round() / 10.0
+}
@@ -0,0 +1,3 @@
+pub(crate) mod blood_pressure_category;
+pub(crate) mod body_mass_index;
+pub(crate) mod target_range;
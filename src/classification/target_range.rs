@@ -0,0 +1,59 @@
+use serde::Serialize;
+
+use crate::repositories::user_repository::UserProfileEntity;
+
+/**
+ * The limits a user's doctor has asked them to keep their readings within. Blood pressure targets are upper limits
+ * that readings should stay below (e.g. 'below 135/85'), while pulse is a range that is only checked if it's set.
+ */
+#[derive(Clone, Copy)]
+pub struct TargetRange {
+    pub systolic: i32,
+    pub diastolic: i32,
+    pub pulse_min: Option<i32>,
+    pub pulse_max: Option<i32>,
+}
+
+impl TargetRange {
+    pub fn from_profile(profile: &UserProfileEntity) -> TargetRange {
+        TargetRange {
+            systolic: profile.target_systolic,
+            diastolic: profile.target_diastolic,
+            pulse_min: profile.target_pulse_min,
+            pulse_max: profile.target_pulse_max,
+        }
+    }
+
+    pub fn is_within(&self, systolic: i32, diastolic: i32, pulse: i32) -> bool {
+        systolic < self.systolic
+            && diastolic < self.diastolic
+            && self.pulse_min.is_none_or(|min| pulse >= min)
+            && self.pulse_max.is_none_or(|max| pulse <= max)
+    }
+}
+
+/**
+ * How many readings over a period were within the user's targets
+ */
+#[derive(Default, Serialize)]
+pub struct TimeInRange {
+    pub within_target: i64,
+    pub outside_target: i64,
+    /**
+     * None when there are no readings in the period
+     */
+    pub percentage_within_target: Option<f64>,
+}
+
+impl TimeInRange {
+    pub fn add(&mut self, within_target: bool) {
+        match within_target {
+            true => self.within_target += 1,
+            false => self.outside_target += 1,
+        }
+
+        let total = self.within_target + self.outside_target;
+
+        self.percentage_within_target = Some(self.within_target as f64 * 100.0 / total as f64);
+    }
+}
@@ -0,0 +1,329 @@
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    alerts::rule_engine::evaluate_alert_rules,
+    controllers::{
+        api_error::ApiError,
+        extractors::{ApiJson, ApiQuery},
+        validation::{
+            DIASTOLIC_RANGE, FieldError, SYSTOLIC_RANGE, check_range, field_error, to_result,
+        },
+        webhooks::to_webhook_event,
+    },
+    notifications::{notification_queue::NotificationQueue, notifier::Notification},
+    repositories::{
+        alert_repository::{
+            AbsoluteThresholdRule, AlertEntity, AlertKind, AlertRepository, AlertRulesEntity,
+            ConsecutiveHighRule, SuddenJumpRule,
+        },
+        blood_pressure_readings_repository::{
+            BloodPressureReadingEntity, BloodPressureReadingRepository,
+        },
+        session_repository::{LoggedInSessionRepository, SessionRepository},
+        webhook_repository::WebhookEvent,
+    },
+};
+
+// Keeps the number of earlier readings fetched for each submission small
+const MAX_RULE_READING_COUNT: u32 = 50;
+
+#[derive(Serialize)]
+pub struct AlertResponse {
+    pub id: String,
+    pub reading_id: String,
+    pub kind: AlertKind,
+    pub message: String,
+    pub triggered: DateTime<Utc>,
+}
+
+/**
+ * Used for both reading and replacing the rules. A rule that is null is switched off.
+ */
+#[derive(Serialize, Deserialize)]
+pub struct AlertRulesBody {
+    pub absolute_threshold: Option<AbsoluteThresholdRule>,
+    pub consecutive_high: Option<ConsecutiveHighRule>,
+    pub sudden_jump: Option<SuddenJumpRule>,
+}
+
+/**
+ * Checks the alert rules a user has submitted. Rules that are switched off aren't checked.
+ */
+pub fn validate_alert_rules(rules: &AlertRulesBody) -> Result<(), Vec<FieldError>> {
+    let mut errors = vec![];
+
+    if let Some(rule) = rules.absolute_threshold {
+        check_range(
+            &mut errors,
+            "absolute_threshold.systolic",
+            rule.systolic,
+            SYSTOLIC_RANGE,
+        );
+        check_range(
+            &mut errors,
+            "absolute_threshold.diastolic",
+            rule.diastolic,
+            DIASTOLIC_RANGE,
+        );
+    }
+
+    if let Some(rule) = rules.consecutive_high {
+        if !(1..=MAX_RULE_READING_COUNT).contains(&rule.count) {
+            errors.push(field_error(
+                "consecutive_high.count",
+                format!("Must be between 1 and {}", MAX_RULE_READING_COUNT),
+            ));
+        }
+
+        check_range(
+            &mut errors,
+            "consecutive_high.systolic",
+            rule.systolic,
+            SYSTOLIC_RANGE,
+        );
+        check_range(
+            &mut errors,
+            "consecutive_high.diastolic",
+            rule.diastolic,
+            DIASTOLIC_RANGE,
+        );
+    }
+
+    if let Some(rule) = rules.sudden_jump {
+        if !(1..=MAX_RULE_READING_COUNT).contains(&rule.window) {
+            errors.push(field_error(
+                "sudden_jump.window",
+                format!("Must be between 1 and {}", MAX_RULE_READING_COUNT),
+            ));
+        }
+
+        if rule.systolic_increase <= 0 || rule.diastolic_increase <= 0 {
+            errors.push(field_error(
+                "sudden_jump",
+                "Increases must be greater than zero".to_string(),
+            ));
+        }
+    }
+
+    to_result(errors)
+}
+
+#[derive(Deserialize)]
+pub struct GetAlertsQueryParameters {
+    pub from_inclusive: DateTime<Utc>,
+    pub to_inclusive: DateTime<Utc>,
+}
+
+pub fn to_api_representation(entity: AlertEntity) -> AlertResponse {
+    AlertResponse {
+        id: entity.alert_id,
+        reading_id: entity.reading_id,
+        kind: entity.kind,
+        message: entity.message,
+        triggered: entity.triggered,
+    }
+}
+
+/**
+ * Evaluates the user's alert rules against a reading that has just been saved and records any alerts it triggers,
+ * along with their webhook events. The reading has already been saved by this point, so a failure here is logged
+ * rather than failing the request.
+ */
+pub async fn raise_alerts<T: BloodPressureReadingRepository, W: AlertRepository>(
+    reading_repository: &Arc<T>,
+    alert_repository: &Arc<W>,
+    reading: &BloodPressureReadingEntity,
+) -> Vec<AlertEntity> {
+    let result: Result<Vec<AlertEntity>, ApiError> = async {
+        let rules = alert_repository.get_rules(reading.user_id.clone()).await?;
+        let triggered = evaluate_alert_rules(reading_repository.as_ref(), &rules, reading).await?;
+
+        let now = Utc::now();
+        let alerts: Vec<AlertEntity> = triggered
+            .into_iter()
+            .map(|alert| AlertEntity {
+                alert_id: Uuid::now_v7().to_string(),
+                user_id: reading.user_id.clone(),
+                reading_id: reading.reading_id.clone(),
+                kind: alert.kind,
+                message: alert.message,
+                triggered: now,
+            })
+            .collect();
+
+        if !alerts.is_empty() {
+            let webhook_events = alerts
+                .iter()
+                .filter_map(|alert| {
+                    to_webhook_event(
+                        WebhookEvent::AlertTriggered,
+                        &to_api_representation(alert.clone()),
+                    )
+                })
+                .collect();
+
+            alert_repository
+                .save_alerts(alerts.clone(), webhook_events)
+                .await?;
+        }
+
+        Ok(alerts)
+    }
+    .await;
+
+    match result {
+        Ok(alerts) => alerts,
+        Err(error) => {
+            println!(
+                "Could not evaluate alert rules for reading {}: {:?}",
+                reading.reading_id, error
+            );
+            vec![]
+        }
+    }
+}
+
+/**
+ * Queues an email to the user about the alerts a reading triggered. Users without an email address from the identity
+ * provider aren't emailed, and delivery failures are only logged.
+ */
+pub fn email_alerts(
+    notification_queue: &NotificationQueue,
+    email: Option<String>,
+    reading: &BloodPressureReadingEntity,
+    alerts: &[AlertEntity],
+) {
+    let Some(email) = email else {
+        return;
+    };
+
+    if alerts.is_empty() {
+        return;
+    }
+
+    let messages: Vec<String> = alerts
+        .iter()
+        .map(|alert| format!("- {}", alert.message))
+        .collect();
+
+    let notification = Notification {
+        to: email,
+        subject: "Blood pressure alert".to_string(),
+        body: format!(
+            "Your reading of {}/{} taken at {} triggered the following alerts:\n\n{}",
+            reading.systolic,
+            reading.diastolic,
+            reading.taken.to_rfc3339(),
+            messages.join("\n")
+        ),
+    };
+
+    notification_queue.push(notification);
+}
+
+async fn get_alerts_from_database<W: AlertRepository, U: SessionRepository>(
+    alert_repository: Arc<W>,
+    session_repository: LoggedInSessionRepository<U>,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<Vec<AlertResponse>, ApiError> {
+    let user_id = session_repository.get_oidc_user_subject().await?;
+
+    let alerts = alert_repository.list_alerts(user_id, from, to).await?;
+
+    Ok(alerts.into_iter().map(to_api_representation).collect())
+}
+
+pub async fn get_alerts<W: AlertRepository, U: SessionRepository>(
+    alert_repository: Arc<W>,
+    session_repository: LoggedInSessionRepository<U>,
+    ApiQuery(query): ApiQuery<GetAlertsQueryParameters>,
+) -> Response {
+    if query.from_inclusive > query.to_inclusive {
+        return ApiError::bad_request("from_inclusive must not be after to_inclusive")
+            .into_response();
+    }
+
+    let result = get_alerts_from_database(
+        alert_repository,
+        session_repository,
+        query.from_inclusive,
+        query.to_inclusive,
+    )
+    .await;
+
+    match result {
+        Err(error) => error.into_response(),
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+    }
+}
+
+async fn get_alert_rules_from_database<W: AlertRepository, U: SessionRepository>(
+    alert_repository: Arc<W>,
+    session_repository: LoggedInSessionRepository<U>,
+) -> Result<AlertRulesBody, ApiError> {
+    let user_id = session_repository.get_oidc_user_subject().await?;
+
+    let rules = alert_repository.get_rules(user_id).await?;
+
+    Ok(AlertRulesBody {
+        absolute_threshold: rules.absolute_threshold,
+        consecutive_high: rules.consecutive_high,
+        sudden_jump: rules.sudden_jump,
+    })
+}
+
+pub async fn get_alert_rules<W: AlertRepository, U: SessionRepository>(
+    alert_repository: Arc<W>,
+    session_repository: LoggedInSessionRepository<U>,
+) -> Response {
+    let result = get_alert_rules_from_database(alert_repository, session_repository).await;
+
+    match result {
+        Err(error) => error.into_response(),
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+    }
+}
+
+async fn save_alert_rules_to_database<W: AlertRepository, U: SessionRepository>(
+    alert_repository: Arc<W>,
+    session_repository: LoggedInSessionRepository<U>,
+    rules: AlertRulesBody,
+) -> Result<AlertRulesBody, ApiError> {
+    validate_alert_rules(&rules)?;
+
+    let user_id = session_repository.get_oidc_user_subject().await?;
+
+    let entity = AlertRulesEntity {
+        user_id,
+        absolute_threshold: rules.absolute_threshold,
+        consecutive_high: rules.consecutive_high,
+        sudden_jump: rules.sudden_jump,
+    };
+
+    alert_repository.save_rules(entity).await?;
+
+    Ok(rules)
+}
+
+pub async fn save_alert_rules<W: AlertRepository, U: SessionRepository>(
+    alert_repository: Arc<W>,
+    session_repository: LoggedInSessionRepository<U>,
+    ApiJson(body): ApiJson<AlertRulesBody>,
+) -> Response {
+    let result = save_alert_rules_to_database(alert_repository, session_repository, body).await;
+
+    match result {
+        Err(error) => error.into_response(),
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+    }
+}
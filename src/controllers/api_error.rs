@@ -0,0 +1,187 @@
+use axum::{
+    Json,
+    extract::{
+        multipart::MultipartRejection,
+        rejection::{JsonRejection, QueryRejection},
+    },
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+
+use crate::{
+    controllers::validation::FieldError,
+    repositories::{
+        blood_pressure_readings_repository::{DeleteError, RetrieveError, SaveError},
+        session_repository::{LoggedInSessionError, SessionRepositoryError},
+    },
+};
+
+/**
+ * The error type returned by every API handler. Each variant maps to a HTTP status code and is rendered using the
+ * same JSON envelope so that the client only has to understand one error shape.
+ */
+#[derive(Debug)]
+pub enum ApiError {
+    Unauthorized,
+    Forbidden { description: String },
+    BadRequest { description: String },
+    Validation { errors: Vec<FieldError> },
+    NotFound,
+    Internal { description: String },
+}
+
+#[derive(Serialize)]
+struct ErrorBody {
+    code: &'static str,
+    message: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    fields: Vec<FieldError>,
+}
+
+#[derive(Serialize)]
+struct ErrorEnvelope {
+    error: ErrorBody,
+}
+
+impl ApiError {
+    pub fn bad_request(description: &str) -> ApiError {
+        ApiError::BadRequest {
+            description: description.to_string(),
+        }
+    }
+
+    pub fn internal(description: &str) -> ApiError {
+        ApiError::Internal {
+            description: description.to_string(),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, code, message, fields) = match self {
+            ApiError::Unauthorized => (
+                StatusCode::UNAUTHORIZED,
+                "unauthorized",
+                "You must be logged in to do that.".to_string(),
+                vec![],
+            ),
+            ApiError::Forbidden { description } => {
+                (StatusCode::FORBIDDEN, "forbidden", description, vec![])
+            }
+            ApiError::BadRequest { description } => {
+                (StatusCode::BAD_REQUEST, "bad_request", description, vec![])
+            }
+            ApiError::Validation { errors } => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "validation_failed",
+                "The submission was not valid.".to_string(),
+                errors,
+            ),
+            ApiError::NotFound => (
+                StatusCode::NOT_FOUND,
+                "not_found",
+                "The requested resource does not exist.".to_string(),
+                vec![],
+            ),
+            ApiError::Internal { description } => {
+                // Storage errors can contain details about the database that the client has no business seeing
+                println!("Internal error: {}", description);
+
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "internal_error",
+                    "Something went wrong on our side.".to_string(),
+                    vec![],
+                )
+            }
+        };
+
+        let envelope = ErrorEnvelope {
+            error: ErrorBody {
+                code,
+                message,
+                fields,
+            },
+        };
+
+        (status, Json(envelope)).into_response()
+    }
+}
+
+impl From<Vec<FieldError>> for ApiError {
+    fn from(errors: Vec<FieldError>) -> Self {
+        ApiError::Validation { errors }
+    }
+}
+
+impl From<JsonRejection> for ApiError {
+    fn from(value: JsonRejection) -> Self {
+        ApiError::BadRequest {
+            description: value.body_text(),
+        }
+    }
+}
+
+impl From<QueryRejection> for ApiError {
+    fn from(value: QueryRejection) -> Self {
+        ApiError::BadRequest {
+            description: value.body_text(),
+        }
+    }
+}
+
+impl From<MultipartRejection> for ApiError {
+    fn from(value: MultipartRejection) -> Self {
+        ApiError::BadRequest {
+            description: value.body_text(),
+        }
+    }
+}
+
+impl From<SessionRepositoryError> for ApiError {
+    fn from(value: SessionRepositoryError) -> Self {
+        ApiError::Internal {
+            description: value.description,
+        }
+    }
+}
+
+impl From<LoggedInSessionError> for ApiError {
+    fn from(value: LoggedInSessionError) -> Self {
+        match value {
+            LoggedInSessionError::MissingValue() => ApiError::Unauthorized,
+            LoggedInSessionError::Error(error) => ApiError::from(error),
+        }
+    }
+}
+
+impl From<SaveError> for ApiError {
+    fn from(value: SaveError) -> Self {
+        match value {
+            SaveError::NotFound => ApiError::NotFound,
+            SaveError::LowLevelError { description } => ApiError::Internal { description },
+        }
+    }
+}
+
+impl From<DeleteError> for ApiError {
+    fn from(value: DeleteError) -> Self {
+        match value {
+            DeleteError::NotFound => ApiError::NotFound,
+            DeleteError::LowLevelError { description } => ApiError::Internal { description },
+        }
+    }
+}
+
+impl From<RetrieveError> for ApiError {
+    fn from(value: RetrieveError) -> Self {
+        match value {
+            RetrieveError::LowLevelError { description } => ApiError::Internal { description },
+            RetrieveError::DeserializationError { description } => {
+                ApiError::Internal { description }
+            }
+        }
+    }
+}
@@ -0,0 +1,193 @@
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    extract::Path,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    auth::secret_token::{generate_secret_token, hash_secret_token},
+    controllers::{
+        api_error::ApiError,
+        extractors::ApiJson,
+        validation::{FieldError, field_error, to_result},
+    },
+    repositories::{
+        api_token_repository::{ApiTokenEntity, ApiTokenRepository, ApiTokenScope},
+        session_repository::{LoggedInSessionRepository, SessionRepository},
+    },
+};
+
+const MAX_API_TOKENS_PER_USER: usize = 20;
+const MAX_API_TOKEN_NAME_LENGTH: usize = 100;
+
+#[derive(Deserialize)]
+pub struct ApiTokenSubmission {
+    pub name: String,
+    /**
+     * What the token can be used for. A token without scopes can do anything the user can, except manage tokens.
+     */
+    #[serde(default)]
+    pub scopes: Vec<ApiTokenScope>,
+}
+
+pub fn validate_api_token_submission(
+    api_token: &ApiTokenSubmission,
+) -> Result<(), Vec<FieldError>> {
+    let mut errors = vec![];
+
+    let name_length = api_token.name.trim().chars().count();
+
+    if name_length == 0 || name_length > MAX_API_TOKEN_NAME_LENGTH {
+        errors.push(field_error(
+            "name",
+            format!(
+                "Must be between 1 and {} characters",
+                MAX_API_TOKEN_NAME_LENGTH
+            ),
+        ));
+    }
+
+    to_result(errors)
+}
+
+#[derive(Serialize)]
+pub struct ApiTokenResponse {
+    pub id: String,
+    pub name: String,
+    pub scopes: Vec<ApiTokenScope>,
+    pub created: DateTime<Utc>,
+    pub last_used: Option<DateTime<Utc>>,
+}
+
+/**
+ * The token is only returned when it is created, as only its hash is kept. It is sent in an
+ * `Authorization: Bearer` header.
+ */
+#[derive(Serialize)]
+pub struct CreatedApiTokenResponse {
+    #[serde(flatten)]
+    pub api_token: ApiTokenResponse,
+    pub token: String,
+}
+
+fn to_api_representation(entity: ApiTokenEntity) -> ApiTokenResponse {
+    ApiTokenResponse {
+        id: entity.token_id,
+        name: entity.name,
+        scopes: entity.scopes,
+        created: entity.created,
+        last_used: entity.last_used,
+    }
+}
+
+async fn get_api_tokens_from_database<A: ApiTokenRepository, U: SessionRepository>(
+    api_token_repository: Arc<A>,
+    session_repository: LoggedInSessionRepository<U>,
+) -> Result<Vec<ApiTokenResponse>, ApiError> {
+    let user_id = session_repository.get_oidc_user_subject().await?;
+
+    let api_tokens = api_token_repository.list(user_id).await?;
+
+    Ok(api_tokens.into_iter().map(to_api_representation).collect())
+}
+
+pub async fn get_api_tokens<A: ApiTokenRepository, U: SessionRepository>(
+    api_token_repository: Arc<A>,
+    session_repository: LoggedInSessionRepository<U>,
+) -> Response {
+    let result = get_api_tokens_from_database(api_token_repository, session_repository).await;
+
+    match result {
+        Err(error) => error.into_response(),
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+    }
+}
+
+async fn add_api_token_to_database<A: ApiTokenRepository, U: SessionRepository>(
+    api_token_repository: Arc<A>,
+    session_repository: LoggedInSessionRepository<U>,
+    api_token: ApiTokenSubmission,
+) -> Result<CreatedApiTokenResponse, ApiError> {
+    validate_api_token_submission(&api_token)?;
+
+    let user_id = session_repository.get_oidc_user_subject().await?;
+
+    let existing = api_token_repository.list(user_id.clone()).await?;
+    if existing.len() >= MAX_API_TOKENS_PER_USER {
+        return Err(ApiError::bad_request(&format!(
+            "No more than {} tokens can be created",
+            MAX_API_TOKENS_PER_USER
+        )));
+    }
+
+    let mut scopes = vec![];
+    for scope in api_token.scopes {
+        if !scopes.contains(&scope) {
+            scopes.push(scope);
+        }
+    }
+
+    let token = generate_secret_token();
+
+    let entity = ApiTokenEntity {
+        token_id: Uuid::now_v7().to_string(),
+        user_id,
+        name: api_token.name.trim().to_string(),
+        token_hash: hash_secret_token(&token),
+        scopes,
+        created: Utc::now(),
+        last_used: None,
+    };
+
+    api_token_repository.save(entity.clone()).await?;
+
+    Ok(CreatedApiTokenResponse {
+        api_token: to_api_representation(entity),
+        token,
+    })
+}
+
+pub async fn add_api_token<A: ApiTokenRepository, U: SessionRepository>(
+    api_token_repository: Arc<A>,
+    session_repository: LoggedInSessionRepository<U>,
+    ApiJson(body): ApiJson<ApiTokenSubmission>,
+) -> Response {
+    let result = add_api_token_to_database(api_token_repository, session_repository, body).await;
+
+    match result {
+        Err(error) => error.into_response(),
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+    }
+}
+
+async fn delete_api_token_from_database<A: ApiTokenRepository, U: SessionRepository>(
+    api_token_repository: Arc<A>,
+    session_repository: LoggedInSessionRepository<U>,
+    token_id: String,
+) -> Result<(), ApiError> {
+    let user_id = session_repository.get_oidc_user_subject().await?;
+
+    api_token_repository.delete(user_id, token_id).await?;
+
+    Ok(())
+}
+
+pub async fn delete_api_token<A: ApiTokenRepository, U: SessionRepository>(
+    api_token_repository: Arc<A>,
+    session_repository: LoggedInSessionRepository<U>,
+    Path(token_id): Path<String>,
+) -> Response {
+    let result =
+        delete_api_token_from_database(api_token_repository, session_repository, token_id).await;
+
+    match result {
+        Err(error) => error.into_response(),
+        Ok(_) => (StatusCode::NO_CONTENT).into_response(),
+    }
+}
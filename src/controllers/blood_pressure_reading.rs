@@ -1,21 +1,45 @@
-use std::sync::Arc;
+use std::{fmt, sync::Arc};
 
 use axum::{
     Json,
-    extract::Query,
+    extract::Path,
     response::{IntoResponse, Response},
 };
-use chrono::{DateTime, Utc};
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use chrono::{DateTime, TimeDelta, Utc};
 use reqwest::StatusCode;
-use serde::{Deserialize, Serialize};
+use serde::{
+    Deserialize, Deserializer, Serialize,
+    de::{self, SeqAccess, Visitor},
+};
 use uuid::Uuid;
 
+use crate::classification::{
+    blood_pressure_category::{BloodPressureCategory, classify},
+    target_range::TargetRange,
+};
+use crate::controllers::{
+    alerts::{self, AlertResponse, email_alerts, raise_alerts},
+    api_error::ApiError,
+    extractors::{ApiJson, ApiQuery},
+    validation::{
+        DIASTOLIC_RANGE, FieldError, PULSE_RANGE, SYSTOLIC_RANGE, check_range, check_taken,
+        check_weight, field_error, to_result,
+    },
+    webhooks::to_webhook_event,
+};
+use crate::notifications::notification_queue::NotificationQueue;
 use crate::repositories::{
+    alert_repository::AlertRepository,
     blood_pressure_readings_repository::{
-        BloodPressureReadingEntity, BloodPressureReadingRepository,
+        Arm, BloodPressureReadingEntity, BloodPressureReadingRepository, BodyPosition, CuffSize,
+        ReadingCursor, ReadingPageQuery,
     },
     session_repository::{LoggedInSessionRepository, SessionRepository},
+    user_repository::{UserProfileEntity, UserRepository},
+    webhook_repository::WebhookEvent,
 };
+use crate::units::weight::Weight;
 
 #[derive(Deserialize)]
 pub struct BloodPressureReadingSubmission {
@@ -23,7 +47,116 @@ pub struct BloodPressureReadingSubmission {
     pub diastolic: i32,
     pub pulse: i32,
     pub weight_kilograms: Option<f64>,
+    /**
+     * An alternative to weight_kilograms for giving the weight in pounds or stones and pounds
+     */
+    pub weight: Option<Weight>,
     pub taken: DateTime<Utc>,
+    /**
+     * Groups the reading with others taken in the same sitting. If this is missing the reading joins the session of
+     * any reading taken within a few minutes of it, or starts a new one.
+     */
+    pub measurement_session_id: Option<String>,
+    pub note: Option<String>,
+    pub arm: Option<Arm>,
+    pub body_position: Option<BodyPosition>,
+    pub cuff_size: Option<CuffSize>,
+    #[serde(default, deserialize_with = "deserialize_tags")]
+    pub tags: Vec<String>,
+}
+
+impl BloodPressureReadingSubmission {
+    pub fn weight_in_kilograms(&self) -> Option<f64> {
+        self.weight
+            .map(|weight| weight.to_kilograms())
+            .or(self.weight_kilograms)
+    }
+}
+
+/**
+ * Splits a ';' separated string of tags, as used for the tags cell in CSV files
+ */
+pub fn split_tags(value: &str) -> Vec<String> {
+    value
+        .split(';')
+        .filter(|tag| !tag.trim().is_empty())
+        .map(|tag| tag.to_string())
+        .collect()
+}
+
+struct TagsVisitor;
+
+impl<'de> Visitor<'de> for TagsVisitor {
+    type Value = Vec<String>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a list of tags or a ';' separated string of tags")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+        Ok(split_tags(value))
+    }
+
+    // CSV cells are typed by their contents, so a single tag such as '2024' or 'true' arrives as a number or a bool
+    fn visit_bool<E: de::Error>(self, value: bool) -> Result<Self::Value, E> {
+        self.visit_str(&value.to_string())
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<Self::Value, E> {
+        self.visit_str(&value.to_string())
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Self::Value, E> {
+        self.visit_str(&value.to_string())
+    }
+
+    fn visit_i128<E: de::Error>(self, value: i128) -> Result<Self::Value, E> {
+        self.visit_str(&value.to_string())
+    }
+
+    fn visit_u128<E: de::Error>(self, value: u128) -> Result<Self::Value, E> {
+        self.visit_str(&value.to_string())
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<Self::Value, E> {
+        self.visit_str(&value.to_string())
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(vec![])
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(vec![])
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut tags = vec![];
+
+        while let Some(tag) = seq.next_element()? {
+            tags.push(tag);
+        }
+
+        Ok(tags)
+    }
+}
+
+/**
+ * Tags are a list in JSON but a ';' separated cell in the CSV export, and both need to be accepted so that exported
+ * files can be imported again
+ */
+fn deserialize_tags<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    deserializer.deserialize_any(TagsVisitor)
+}
+
+/**
+ * Returned when a reading is added, so the UI can warn about any alerts it triggered straight away
+ */
+#[derive(Serialize)]
+pub struct ReadingSubmissionResponse {
+    #[serde(flatten)]
+    pub reading: BloodPressureReadingResponse,
+    pub alerts: Vec<AlertResponse>,
 }
 
 #[derive(Serialize)]
@@ -32,112 +165,500 @@ pub struct BloodPressureReadingResponse {
     pub diastolic: i32,
     pub pulse: i32,
     pub weight_kilograms: Option<f64>,
+    /**
+     * The weight in the user's preferred unit
+     */
+    pub weight: Option<Weight>,
     pub taken: DateTime<Utc>,
     pub id: String,
+    pub category: BloodPressureCategory,
+    /**
+     * Whether the reading is within the targets in the user's profile
+     */
+    pub within_target: bool,
+    pub measurement_session_id: Option<String>,
+    pub note: Option<String>,
+    pub arm: Option<Arm>,
+    pub body_position: Option<BodyPosition>,
+    pub cuff_size: Option<CuffSize>,
+    pub tags: Vec<String>,
 }
 
+// Readings taken this close together are assumed to be part of the same sitting
+pub const MEASUREMENT_SESSION_WINDOW_MINUTES: i64 = 10;
+
+const DEFAULT_PAGE_LIMIT: u32 = 100;
+const MAX_PAGE_LIMIT: u32 = 500;
+
+const MAX_NOTE_LENGTH: usize = 1000;
+const MAX_TAGS: usize = 20;
+const MAX_TAG_LENGTH: usize = 50;
 
 #[derive(Deserialize)]
 pub struct GetReadingQueryParameters {
-    pub from_inclusive: DateTime<Utc>,
-    pub to_inclusive: DateTime<Utc>,
+    pub from_inclusive: Option<DateTime<Utc>>,
+    pub to_inclusive: Option<DateTime<Utc>>,
+    pub tag: Option<String>,
+    pub limit: Option<u32>,
+    /**
+     * The next_cursor from the previous page. The first page is returned if this is missing.
+     */
+    pub cursor: Option<String>,
 }
 
-async fn add_reading_to_database<T: BloodPressureReadingRepository, U: SessionRepository>(
-    reading_repository: &Arc<T>,
-    session_repository: LoggedInSessionRepository<U>,
-    reading: BloodPressureReadingSubmission,
-) -> Result<(), String> {
-    let user_id = session_repository
-        .get_oidc_user_subject()
-        .await
-        .map_err(|_| "Could not access user ID from")?;
+#[derive(Serialize)]
+pub struct ReadingPageResponse {
+    pub readings: Vec<BloodPressureReadingResponse>,
+    /**
+     * Pass this as the cursor to get the next page. None when there are no more readings.
+     */
+    pub next_cursor: Option<String>,
+}
+
+/**
+ * Cursors are opaque to the client so that what they contain can change without breaking it
+ */
+fn encode_cursor(cursor: &ReadingCursor) -> String {
+    URL_SAFE_NO_PAD.encode(format!("{}|{}", cursor.taken.to_rfc3339(), cursor.reading_id))
+}
+
+fn decode_cursor(cursor: &str) -> Result<ReadingCursor, ApiError> {
+    let invalid_cursor = || ApiError::bad_request("cursor is not valid");
+
+    let decoded = URL_SAFE_NO_PAD
+        .decode(cursor)
+        .ok()
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .ok_or_else(invalid_cursor)?;
+
+    let (taken, reading_id) = decoded.split_once('|').ok_or_else(invalid_cursor)?;
+
+    let taken = DateTime::parse_from_rfc3339(taken)
+        .map_err(|_| invalid_cursor())?
+        .with_timezone(&Utc);
+
+    Ok(ReadingCursor {
+        taken,
+        reading_id: reading_id.to_string(),
+    })
+}
+
+/**
+ * Checks that a submitted reading is physiologically plausible and not taken in the future. Returns every problem
+ * found rather than just the first so that the form can highlight all the offending fields at once.
+ */
+pub fn validate_submission(
+    reading: &BloodPressureReadingSubmission,
+    now: DateTime<Utc>,
+) -> Result<(), Vec<FieldError>> {
+    let mut errors = vec![];
+
+    check_range(&mut errors, "systolic", reading.systolic, SYSTOLIC_RANGE);
+    check_range(&mut errors, "diastolic", reading.diastolic, DIASTOLIC_RANGE);
+    check_range(&mut errors, "pulse", reading.pulse, PULSE_RANGE);
+
+    if reading.systolic <= reading.diastolic {
+        errors.push(field_error(
+            "systolic",
+            "Must be greater than diastolic".to_string(),
+        ));
+    }
+
+    check_weight(&mut errors, reading.weight_kilograms, reading.weight);
+
+    if let Some(note) = &reading.note
+        && note.chars().count() > MAX_NOTE_LENGTH
+    {
+        errors.push(field_error(
+            "note",
+            format!("Must be at most {} characters", MAX_NOTE_LENGTH),
+        ));
+    }
+
+    if reading.tags.len() > MAX_TAGS {
+        errors.push(field_error(
+            "tags",
+            format!("Must have at most {} tags", MAX_TAGS),
+        ));
+    }
+
+    if reading
+        .tags
+        .iter()
+        .any(|tag| tag.trim().is_empty() || tag.trim().chars().count() > MAX_TAG_LENGTH)
+    {
+        errors.push(field_error(
+            "tags",
+            format!("Each tag must be between 1 and {} characters", MAX_TAG_LENGTH),
+        ));
+    }
+
+    check_taken(&mut errors, reading.taken, now);
+
+    to_result(errors)
+}
 
+/**
+ * Tags are matched exactly when filtering, so they're stored trimmed and lowercased to stop 'Coffee' and 'coffee '
+ * being treated as different tags
+ */
+pub fn normalise_tags(tags: Vec<String>) -> Vec<String> {
+    let mut normalised: Vec<String> = tags
+        .into_iter()
+        .map(|tag| tag.trim().to_lowercase())
+        .filter(|tag| !tag.is_empty())
+        .collect();
+
+    normalised.sort();
+    normalised.dedup();
+
+    normalised
+}
+
+pub fn to_new_entity(
+    user_id: String,
+    reading: BloodPressureReadingSubmission,
+) -> BloodPressureReadingEntity {
     let blood_pressure_reading_id = Uuid::now_v7().to_string();
 
-    let entity: BloodPressureReadingEntity = BloodPressureReadingEntity {
+    BloodPressureReadingEntity {
         reading_id: blood_pressure_reading_id,
-        user_id: user_id,
+        user_id,
         systolic: reading.systolic,
         diastolic: reading.diastolic,
         pulse: reading.pulse,
-        weight_kilograms: reading.weight_kilograms,
+        weight_kilograms: reading.weight_in_kilograms(),
         taken: reading.taken,
-    };
+        measurement_session_id: reading.measurement_session_id,
+        note: reading.note,
+        arm: reading.arm,
+        body_position: reading.body_position,
+        cuff_size: reading.cuff_size,
+        tags: normalise_tags(reading.tags),
+    }
+}
+
+/**
+ * Puts a reading without an explicit measurement session into the session of the user's latest reading taken within
+ * a few minutes of it, or into a new session if there isn't one
+ */
+pub async fn assign_measurement_session<T: BloodPressureReadingRepository>(
+    reading_repository: &Arc<T>,
+    mut entity: BloodPressureReadingEntity,
+) -> Result<BloodPressureReadingEntity, ApiError> {
+    if entity.measurement_session_id.is_some() {
+        return Ok(entity);
+    }
+
+    let window = TimeDelta::minutes(MEASUREMENT_SESSION_WINDOW_MINUTES);
+
+    let existing_session = reading_repository
+        .find_measurement_session(
+            entity.user_id.clone(),
+            entity.taken - window,
+            entity.taken + window,
+        )
+        .await?;
+
+    entity.measurement_session_id =
+        Some(existing_session.unwrap_or_else(|| Uuid::now_v7().to_string()));
+
+    Ok(entity)
+}
+
+async fn add_reading_to_database<
+    T: BloodPressureReadingRepository,
+    U: SessionRepository,
+    V: UserRepository,
+    W: AlertRepository,
+>(
+    reading_repository: &Arc<T>,
+    session_repository: LoggedInSessionRepository<U>,
+    user_repository: Arc<V>,
+    alert_repository: Arc<W>,
+    notification_queue: Arc<NotificationQueue>,
+    reading: BloodPressureReadingSubmission,
+) -> Result<ReadingSubmissionResponse, ApiError> {
+    validate_submission(&reading, Utc::now())?;
+
+    let user_id = session_repository.get_oidc_user_subject().await?;
+
+    let profile = user_repository.get_profile(user_id.clone()).await?;
+
+    let entity = to_new_entity(user_id, reading);
+    let entity = assign_measurement_session(reading_repository, entity).await?;
+
+    let response = to_api_representation(entity.clone(), &profile);
+    let webhook_events = to_webhook_event(WebhookEvent::ReadingAdded, &response)
+        .into_iter()
+        .collect();
 
     reading_repository
-        .save(entity)
-        .await
-        .map_err(|_| "Could not save to database")?;
+        .save(entity.clone(), webhook_events)
+        .await?;
 
-    Ok(())
+    let alerts = raise_alerts(reading_repository, &alert_repository, &entity).await;
+    email_alerts(&notification_queue, profile.email.clone(), &entity, &alerts);
+
+    Ok(ReadingSubmissionResponse {
+        reading: response,
+        alerts: alerts
+            .into_iter()
+            .map(alerts::to_api_representation)
+            .collect(),
+    })
 }
 
-pub async fn add_reading<T: BloodPressureReadingRepository, U: SessionRepository>(
+pub async fn add_reading<
+    T: BloodPressureReadingRepository,
+    U: SessionRepository,
+    V: UserRepository,
+    W: AlertRepository,
+>(
     reading_repository: Arc<T>,
     session_repository: LoggedInSessionRepository<U>,
-    Json(body): Json<BloodPressureReadingSubmission>,
+    user_repository: Arc<V>,
+    alert_repository: Arc<W>,
+    notification_queue: Arc<NotificationQueue>,
+    ApiJson(body): ApiJson<BloodPressureReadingSubmission>,
 ) -> Response {
-    let result = add_reading_to_database(&reading_repository, session_repository, body).await;
+    let result = add_reading_to_database(
+        &reading_repository,
+        session_repository,
+        user_repository,
+        alert_repository,
+        notification_queue,
+        body,
+    )
+    .await;
 
     match result {
-        Ok(_) => (StatusCode::OK).into_response(),
-        Err(_) => (StatusCode::INTERNAL_SERVER_ERROR).into_response(),
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(error) => error.into_response(),
     }
 }
 
-fn to_api_representation(entity: BloodPressureReadingEntity) -> BloodPressureReadingResponse {
+pub fn to_api_representation(
+    entity: BloodPressureReadingEntity,
+    profile: &UserProfileEntity,
+) -> BloodPressureReadingResponse {
     BloodPressureReadingResponse {
+        category: classify(
+            entity.systolic,
+            entity.diastolic,
+            profile.classification_guideline,
+        ),
+        within_target: TargetRange::from_profile(profile).is_within(
+            entity.systolic,
+            entity.diastolic,
+            entity.pulse,
+        ),
+        weight: entity
+            .weight_kilograms
+            .map(|kilograms| Weight::from_kilograms(kilograms, profile.weight_unit)),
         systolic: entity.systolic,
         diastolic: entity.diastolic,
         pulse: entity.pulse,
         taken: entity.taken,
         weight_kilograms: entity.weight_kilograms,
         id: entity.reading_id,
+        measurement_session_id: entity.measurement_session_id,
+        note: entity.note,
+        arm: entity.arm,
+        body_position: entity.body_position,
+        cuff_size: entity.cuff_size,
+        tags: entity.tags,
     }
 }
 
-async fn get_readings_from_database<T: BloodPressureReadingRepository, U: SessionRepository>(
+async fn get_readings_from_database<
+    T: BloodPressureReadingRepository,
+    U: SessionRepository,
+    V: UserRepository,
+>(
     reading_repository: Arc<T>,
     session_repository: LoggedInSessionRepository<U>,
-    from: DateTime<Utc>,
-    to: DateTime<Utc>,
-) -> Result<Vec<BloodPressureReadingResponse>, String> {
-    let user_id = session_repository
-        .get_oidc_user_subject()
-        .await
-        .map_err(|_| "Could not access user ID from session")?;
-
-    let db_result = reading_repository
-        .list(user_id, from, to)
-        .await
-        .map_err(|_| "Error retrieving from database")?;
-
-    let result: Vec<BloodPressureReadingResponse> = db_result
+    user_repository: Arc<V>,
+    query: GetReadingQueryParameters,
+) -> Result<ReadingPageResponse, ApiError> {
+    let after = query.cursor.as_deref().map(decode_cursor).transpose()?;
+    let limit = query.limit.unwrap_or(DEFAULT_PAGE_LIMIT).clamp(1, MAX_PAGE_LIMIT);
+
+    let user_id = session_repository.get_oidc_user_subject().await?;
+
+    let profile = user_repository.get_profile(user_id.clone()).await?;
+
+    // One extra reading is fetched to find out whether there is another page without a separate count query
+    let page_query = ReadingPageQuery {
+        from: query.from_inclusive,
+        to: query.to_inclusive,
+        tag: query.tag.map(|tag| tag.trim().to_lowercase()),
+        after,
+        limit: limit + 1,
+    };
+
+    let mut db_result = reading_repository.list_page(user_id, page_query).await?;
+
+    let has_next_page = db_result.len() > limit as usize;
+    db_result.truncate(limit as usize);
+
+    let next_cursor = match (has_next_page, db_result.last()) {
+        (true, Some(last)) => Some(encode_cursor(&ReadingCursor {
+            taken: last.taken,
+            reading_id: last.reading_id.clone(),
+        })),
+        _ => None,
+    };
+
+    let readings: Vec<BloodPressureReadingResponse> = db_result
         .into_iter()
-        .map(|entity| to_api_representation(entity))
+        .map(|entity| to_api_representation(entity, &profile))
         .collect();
 
-    Ok(result)
+    Ok(ReadingPageResponse {
+        readings,
+        next_cursor,
+    })
+}
+
+pub async fn get_readings<
+    T: BloodPressureReadingRepository,
+    U: SessionRepository,
+    V: UserRepository,
+>(
+    reading_repository: Arc<T>,
+    session_repository: LoggedInSessionRepository<U>,
+    user_repository: Arc<V>,
+    ApiQuery(query): ApiQuery<GetReadingQueryParameters>,
+) -> Response {
+    if let (Some(from), Some(to)) = (query.from_inclusive, query.to_inclusive)
+        && from > to
+    {
+        return ApiError::bad_request("from_inclusive must not be after to_inclusive")
+            .into_response();
+    }
+
+    let result =
+        get_readings_from_database(reading_repository, session_repository, user_repository, query)
+            .await;
+
+    match result {
+        Err(error) => error.into_response(),
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+    }
+}
+
+async fn get_tags_from_database<T: BloodPressureReadingRepository, U: SessionRepository>(
+    reading_repository: Arc<T>,
+    session_repository: LoggedInSessionRepository<U>,
+) -> Result<Vec<String>, ApiError> {
+    let user_id = session_repository.get_oidc_user_subject().await?;
+
+    let tags = reading_repository.list_tags(user_id).await?;
+
+    Ok(tags)
 }
 
-pub async fn get_readings<T: BloodPressureReadingRepository, U: SessionRepository>(
+pub async fn get_tags<T: BloodPressureReadingRepository, U: SessionRepository>(
     reading_repository: Arc<T>,
     session_repository: LoggedInSessionRepository<U>,
-    query: Query<GetReadingQueryParameters>,
 ) -> Response {
-    // TODO: validate query parameters
+    let result = get_tags_from_database(reading_repository, session_repository).await;
+
+    match result {
+        Err(error) => error.into_response(),
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+    }
+}
+
+async fn update_reading_in_database<
+    T: BloodPressureReadingRepository,
+    U: SessionRepository,
+    V: UserRepository,
+>(
+    reading_repository: Arc<T>,
+    session_repository: LoggedInSessionRepository<U>,
+    user_repository: Arc<V>,
+    reading_id: String,
+    reading: BloodPressureReadingSubmission,
+) -> Result<BloodPressureReadingResponse, ApiError> {
+    validate_submission(&reading, Utc::now())?;
+
+    // The user ID comes from the session rather than the request so that readings belonging to other users
+    // can never be matched by the update
+    let user_id = session_repository.get_oidc_user_subject().await?;
+
+    let profile = user_repository.get_profile(user_id.clone()).await?;
+
+    let entity = BloodPressureReadingEntity {
+        reading_id,
+        user_id,
+        systolic: reading.systolic,
+        diastolic: reading.diastolic,
+        pulse: reading.pulse,
+        weight_kilograms: reading.weight_in_kilograms(),
+        taken: reading.taken,
+        measurement_session_id: reading.measurement_session_id,
+        note: reading.note,
+        arm: reading.arm,
+        body_position: reading.body_position,
+        cuff_size: reading.cuff_size,
+        tags: normalise_tags(reading.tags),
+    };
+
+    let updated = reading_repository.update(entity).await?;
 
-    let result = get_readings_from_database(
+    Ok(to_api_representation(updated, &profile))
+}
+
+pub async fn update_reading<
+    T: BloodPressureReadingRepository,
+    U: SessionRepository,
+    V: UserRepository,
+>(
+    reading_repository: Arc<T>,
+    session_repository: LoggedInSessionRepository<U>,
+    user_repository: Arc<V>,
+    Path(reading_id): Path<String>,
+    ApiJson(body): ApiJson<BloodPressureReadingSubmission>,
+) -> Response {
+    let result = update_reading_in_database(
         reading_repository,
         session_repository,
-        query.from_inclusive,
-        query.to_inclusive,
+        user_repository,
+        reading_id,
+        body,
     )
     .await;
 
     match result {
-        Err(_) => (StatusCode::INTERNAL_SERVER_ERROR).into_response(),
+        Err(error) => error.into_response(),
         Ok(result) => (StatusCode::OK, Json(result)).into_response(),
     }
-}
\ No newline at end of file
+}
+
+async fn delete_reading_from_database<T: BloodPressureReadingRepository, U: SessionRepository>(
+    reading_repository: Arc<T>,
+    session_repository: LoggedInSessionRepository<U>,
+    reading_id: String,
+) -> Result<(), ApiError> {
+    let user_id = session_repository.get_oidc_user_subject().await?;
+
+    reading_repository.delete(user_id, reading_id).await?;
+
+    Ok(())
+}
+
+pub async fn delete_reading<T: BloodPressureReadingRepository, U: SessionRepository>(
+    reading_repository: Arc<T>,
+    session_repository: LoggedInSessionRepository<U>,
+    Path(reading_id): Path<String>,
+) -> Response {
+    let result =
+        delete_reading_from_database(reading_repository, session_repository, reading_id).await;
+
+    match result {
+        Err(error) => error.into_response(),
+        Ok(_) => (StatusCode::NO_CONTENT).into_response(),
+    }
+}
@@ -0,0 +1,131 @@
+use std::sync::Arc;
+
+use axum::{
+    http::header,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
+use reqwest::StatusCode;
+use serde::Deserialize;
+
+use crate::{
+    charts::{
+        svg_chart::{SVG_CHART_AREA, render_svg_chart},
+        trend_chart::{ChartMetric, build_trend_chart},
+    },
+    classification::target_range::TargetRange,
+    controllers::{api_error::ApiError, extractors::ApiQuery, validation::FieldError},
+    repositories::{
+        blood_pressure_readings_repository::BloodPressureReadingRepository,
+        session_repository::{LoggedInSessionRepository, SessionRepository},
+        user_repository::UserRepository,
+    },
+};
+
+#[derive(Deserialize)]
+pub struct GetChartQueryParameters {
+    pub from_inclusive: DateTime<Utc>,
+    pub to_inclusive: DateTime<Utc>,
+    /**
+     * A ',' separated list of systolic, diastolic and pulse. Systolic and diastolic are drawn if this is missing.
+     */
+    pub metrics: Option<String>,
+}
+
+const DEFAULT_METRICS: [ChartMetric; 2] = [ChartMetric::Systolic, ChartMetric::Diastolic];
+
+fn parse_metrics(metrics: Option<&str>) -> Result<Vec<ChartMetric>, Vec<FieldError>> {
+    let Some(metrics) = metrics else {
+        return Ok(DEFAULT_METRICS.to_vec());
+    };
+
+    let mut parsed = vec![];
+
+    for name in metrics.split(',').map(str::trim) {
+        let metric = match name {
+            "systolic" => ChartMetric::Systolic,
+            "diastolic" => ChartMetric::Diastolic,
+            "pulse" => ChartMetric::Pulse,
+            _ => {
+                return Err(vec![FieldError {
+                    field: "metrics".to_string(),
+                    message: format!(
+                        "Unknown metric '{}', expected systolic, diastolic or pulse",
+                        name
+                    ),
+                }]);
+            }
+        };
+
+        if !parsed.contains(&metric) {
+            parsed.push(metric);
+        }
+    }
+
+    Ok(parsed)
+}
+
+async fn get_chart_svg_from_database<
+    T: BloodPressureReadingRepository,
+    U: SessionRepository,
+    V: UserRepository,
+>(
+    reading_repository: Arc<T>,
+    session_repository: LoggedInSessionRepository<U>,
+    user_repository: Arc<V>,
+    query: GetChartQueryParameters,
+) -> Result<String, ApiError> {
+    let metrics = parse_metrics(query.metrics.as_deref())?;
+
+    let user_id = session_repository.get_oidc_user_subject().await?;
+
+    let profile = user_repository.get_profile(user_id.clone()).await?;
+
+    let readings = reading_repository
+        .list(user_id, query.from_inclusive, query.to_inclusive, None)
+        .await?;
+
+    let chart = build_trend_chart(
+        &readings,
+        &metrics,
+        profile.timezone,
+        Some(TargetRange::from_profile(&profile)),
+        SVG_CHART_AREA,
+    );
+
+    Ok(render_svg_chart(&chart))
+}
+
+pub async fn get_chart_svg<
+    T: BloodPressureReadingRepository,
+    U: SessionRepository,
+    V: UserRepository,
+>(
+    reading_repository: Arc<T>,
+    session_repository: LoggedInSessionRepository<U>,
+    user_repository: Arc<V>,
+    ApiQuery(query): ApiQuery<GetChartQueryParameters>,
+) -> Response {
+    if query.from_inclusive > query.to_inclusive {
+        return ApiError::bad_request("from_inclusive must not be after to_inclusive")
+            .into_response();
+    }
+
+    let result = get_chart_svg_from_database(
+        reading_repository,
+        session_repository,
+        user_repository,
+        query,
+    )
+    .await;
+
+    match result {
+        Err(error) => error.into_response(),
+        Ok(svg) => (
+            StatusCode::OK,
+            [(header::CONTENT_TYPE, "image/svg+xml")],
+            svg,
+        )
+            .into_response(),
+    }
+}
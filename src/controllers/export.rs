@@ -1,11 +1,25 @@
 use std::sync::Arc;
 
-use axum::{extract::Query, response::Response};
+use axum::{
+    http::header,
+    response::{IntoResponse, Response},
+};
 use chrono::{DateTime, Utc};
-use serde::Deserialize;
-use tower_sessions::Session;
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
 
-use crate::repositories::blood_pressure_readings_repository::BloodPressureReadingRepository;
+use crate::{
+    controllers::{api_error::ApiError, extractors::ApiQuery},
+    repositories::{
+        blood_pressure_readings_repository::{
+            Arm, BloodPressureReadingEntity, BloodPressureReadingRepository, BodyPosition,
+            CuffSize, ReadingCursor, ReadingPageQuery,
+        },
+        session_repository::{LoggedInSessionRepository, SessionRepository},
+        user_repository::UserRepository,
+    },
+    units::weight::{Weight, WeightUnit},
+};
 
 #[derive(Deserialize)]
 pub struct GetCsvExportQueryParameters {
@@ -13,10 +27,227 @@ pub struct GetCsvExportQueryParameters {
     pub to_inclusive: DateTime<Utc>,
 }
 
-fn get_reading_csv_export<T: BloodPressureReadingRepository>(
-    _reading_repository: Arc<T>,
-    _session_repository: Arc<Session>,
-    _query_params: Query<GetCsvExportQueryParameters>,
+/**
+ * A row in the CSV export. The field order here determines the header row, so new columns should only
+ * ever be appended to keep the format stable for anyone parsing the exports.
+ */
+#[derive(Serialize)]
+struct CsvExportRow {
+    id: String,
+    taken: String,
+    systolic: i32,
+    diastolic: i32,
+    pulse: i32,
+    weight_kilograms: Option<f64>,
+    note: Option<String>,
+    arm: Option<Arm>,
+    body_position: Option<BodyPosition>,
+    cuff_size: Option<CuffSize>,
+    // Joined with ';' since a CSV cell can't hold a list
+    tags: String,
+    /**
+     * The weight in the user's preferred unit, e.g. '12 st 3.5 lb'. weight_kilograms stays the machine readable value.
+     */
+    weight_display: Option<String>,
+}
+
+fn to_csv_row(entity: BloodPressureReadingEntity, weight_unit: WeightUnit) -> CsvExportRow {
+    CsvExportRow {
+        weight_display: entity
+            .weight_kilograms
+            .map(|kilograms| Weight::from_kilograms(kilograms, weight_unit).display()),
+        id: entity.reading_id,
+        taken: entity.taken.to_rfc3339(),
+        systolic: entity.systolic,
+        diastolic: entity.diastolic,
+        pulse: entity.pulse,
+        weight_kilograms: entity.weight_kilograms,
+        note: entity.note,
+        arm: entity.arm,
+        body_position: entity.body_position,
+        cuff_size: entity.cuff_size,
+        tags: entity.tags.join(";"),
+    }
+}
+
+/**
+ * The header row of the export, for exports without any readings. Matches the fields of CsvExportRow, which is what
+ * provides the header otherwise.
+ */
+const CSV_HEADER: [&str; 12] = [
+    "id",
+    "taken",
+    "systolic",
+    "diastolic",
+    "pulse",
+    "weight_kilograms",
+    "note",
+    "arm",
+    "body_position",
+    "cuff_size",
+    "tags",
+    "weight_display",
+];
+
+/**
+ * How many readings are fetched from the database at a time while writing the export, so that only the CSV output
+ * and not every reading entity is held in memory at once
+ */
+const EXPORT_PAGE_SIZE: u32 = 500;
+
+fn to_csv_error(error: csv::Error) -> ApiError {
+    ApiError::Internal {
+        description: format!("Could not write CSV export: {}", error),
+    }
+}
+
+fn to_export_filename(from: DateTime<Utc>, to: DateTime<Utc>) -> String {
+    format!(
+        "blood-pressure-readings-{}-to-{}.csv",
+        from.format("%Y-%m-%d"),
+        to.format("%Y-%m-%d")
+    )
+}
+
+async fn get_csv_export_from_database<
+    T: BloodPressureReadingRepository,
+    U: SessionRepository,
+    V: UserRepository,
+>(
+    reading_repository: Arc<T>,
+    session_repository: LoggedInSessionRepository<U>,
+    user_repository: Arc<V>,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<Vec<u8>, ApiError> {
+    let user_id = session_repository.get_oidc_user_subject().await?;
+
+    let weight_unit = user_repository
+        .get_profile(user_id.clone())
+        .await?
+        .weight_unit;
+
+    let mut writer = csv::Writer::from_writer(vec![]);
+    let mut after = None;
+    let mut is_empty = true;
+
+    loop {
+        let page_query = ReadingPageQuery {
+            from: Some(from),
+            to: Some(to),
+            tag: None,
+            after,
+            limit: EXPORT_PAGE_SIZE,
+        };
+
+        let readings = reading_repository
+            .list_page(user_id.clone(), page_query)
+            .await?;
+
+        let is_last_page = readings.len() < EXPORT_PAGE_SIZE as usize;
+
+        after = readings.last().map(|last| ReadingCursor {
+            taken: last.taken,
+            reading_id: last.reading_id.clone(),
+        });
+
+        is_empty = is_empty && readings.is_empty();
+
+        for reading in readings {
+            writer
+                .serialize(to_csv_row(reading, weight_unit))
+                .map_err(to_csv_error)?;
+        }
+
+        if is_last_page {
+            break;
+        }
+    }
+
+    // The header is usually written along with the first record, but an empty export should still have one
+    if is_empty {
+        writer.write_record(CSV_HEADER).map_err(to_csv_error)?;
+    }
+
+    writer
+        .into_inner()
+        .map_err(|error| to_csv_error(error.into_error().into()))
+}
+
+pub async fn get_reading_csv_export<
+    T: BloodPressureReadingRepository,
+    U: SessionRepository,
+    V: UserRepository,
+>(
+    reading_repository: Arc<T>,
+    session_repository: LoggedInSessionRepository<U>,
+    user_repository: Arc<V>,
+    ApiQuery(query): ApiQuery<GetCsvExportQueryParameters>,
 ) -> Response {
-    panic!("Panik")
+    let result = get_csv_export_from_database(
+        reading_repository,
+        session_repository,
+        user_repository,
+        query.from_inclusive,
+        query.to_inclusive,
+    )
+    .await;
+
+    match result {
+        Err(error) => error.into_response(),
+        Ok(csv) => {
+            let content_disposition = format!(
+                "attachment; filename=\"{}\"",
+                to_export_filename(query.from_inclusive, query.to_inclusive)
+            );
+
+            (
+                StatusCode::OK,
+                [
+                    (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+                    (header::CONTENT_DISPOSITION, content_disposition),
+                ],
+                csv,
+            )
+                .into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::{CSV_HEADER, to_csv_row};
+    use crate::{
+        repositories::blood_pressure_readings_repository::BloodPressureReadingEntity,
+        units::weight::WeightUnit,
+    };
+
+    #[test]
+    fn header_matches_the_serialized_row_fields() {
+        let reading = BloodPressureReadingEntity {
+            reading_id: "reading".to_string(),
+            user_id: "user".to_string(),
+            systolic: 120,
+            diastolic: 80,
+            pulse: 60,
+            weight_kilograms: None,
+            taken: Utc.with_ymd_and_hms(2024, 1, 1, 8, 0, 0).unwrap(),
+            measurement_session_id: None,
+            note: None,
+            arm: None,
+            body_position: None,
+            cuff_size: None,
+            tags: vec![],
+        };
+
+        let mut writer = csv::Writer::from_writer(vec![]);
+        writer
+            .serialize(to_csv_row(reading, WeightUnit::Kilograms))
+            .unwrap();
+        let csv = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+
+        assert_eq!(csv.lines().next(), Some(CSV_HEADER.join(",").as_str()));
+    }
 }
@@ -0,0 +1,55 @@
+use axum::{
+    Json,
+    extract::{FromRequest, FromRequestParts, Multipart, Query, Request},
+    http::request::Parts,
+};
+use serde::de::DeserializeOwned;
+
+use crate::controllers::api_error::ApiError;
+
+/**
+ * The same as `Json`, except that a body that can't be parsed is rejected using the API's error envelope rather than
+ * axum's plain text response
+ */
+pub struct ApiJson<T>(pub T);
+
+impl<T: DeserializeOwned, S: Send + Sync> FromRequest<S> for ApiJson<T> {
+    type Rejection = ApiError;
+
+    async fn from_request(request: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(value) = Json::<T>::from_request(request, state).await?;
+
+        Ok(ApiJson(value))
+    }
+}
+
+/**
+ * The same as `Query`, except that a query string that can't be parsed is rejected using the API's error envelope
+ */
+pub struct ApiQuery<T>(pub T);
+
+impl<T: DeserializeOwned, S: Send + Sync> FromRequestParts<S> for ApiQuery<T> {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Query(value) = Query::<T>::from_request_parts(parts, state).await?;
+
+        Ok(ApiQuery(value))
+    }
+}
+
+/**
+ * The same as `Multipart`, except that a request that isn't a multipart upload is rejected using the API's error
+ * envelope
+ */
+pub struct ApiMultipart(pub Multipart);
+
+impl<S: Send + Sync> FromRequest<S> for ApiMultipart {
+    type Rejection = ApiError;
+
+    async fn from_request(request: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let multipart = Multipart::from_request(request, state).await?;
+
+        Ok(ApiMultipart(multipart))
+    }
+}
@@ -0,0 +1,310 @@
+use std::{collections::HashSet, sync::Arc};
+
+use axum::{
+    Json,
+    body::Bytes,
+    extract::Multipart,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    controllers::{
+        api_error::ApiError,
+        blood_pressure_reading::{
+            BloodPressureReadingResponse, BloodPressureReadingSubmission, split_tags,
+            to_api_representation, to_new_entity, validate_submission,
+        },
+        extractors::{ApiMultipart, ApiQuery},
+        reading_batch::assign_batch_measurement_sessions,
+        validation::FieldError,
+    },
+    repositories::{
+        blood_pressure_readings_repository::{
+            BatchReadingEntity, BloodPressureReadingEntity, BloodPressureReadingRepository,
+        },
+        session_repository::{LoggedInSessionRepository, SessionRepository},
+        user_repository::UserRepository,
+    },
+};
+
+#[derive(Deserialize)]
+pub struct CsvImportQueryParameters {
+    pub dry_run: Option<bool>,
+}
+
+#[derive(Serialize)]
+pub struct ImportRowError {
+    pub line: u64,
+    pub reason: String,
+}
+
+#[derive(Serialize)]
+pub struct ImportedReading {
+    pub line: u64,
+    pub reading: BloodPressureReadingResponse,
+}
+
+#[derive(Serialize)]
+pub struct CsvImportResponse {
+    pub dry_run: bool,
+    /**
+     * The readings that were inserted, or that would have been inserted if this was a dry run
+     */
+    pub imported: Vec<ImportedReading>,
+    /**
+     * The lines that were skipped because the reading already exists (e.g. the file was uploaded before)
+     */
+    pub duplicate_lines: Vec<u64>,
+    pub errors: Vec<ImportRowError>,
+}
+
+struct ParsedRow {
+    line: u64,
+    reading: BloodPressureReadingSubmission,
+}
+
+/**
+ * Two readings are considered the same if they were taken at the same time with the same values, which is
+ * what makes re-uploading a file (or an overlapping export) safe
+ */
+#[derive(PartialEq, Eq, Hash)]
+struct ReadingIdentity {
+    taken: DateTime<Utc>,
+    systolic: i32,
+    diastolic: i32,
+    pulse: i32,
+}
+
+impl ReadingIdentity {
+    fn from_submission(reading: &BloodPressureReadingSubmission) -> ReadingIdentity {
+        ReadingIdentity {
+            taken: reading.taken,
+            systolic: reading.systolic,
+            diastolic: reading.diastolic,
+            pulse: reading.pulse,
+        }
+    }
+
+    fn from_entity(entity: &BloodPressureReadingEntity) -> ReadingIdentity {
+        ReadingIdentity {
+            taken: entity.taken,
+            systolic: entity.systolic,
+            diastolic: entity.diastolic,
+            pulse: entity.pulse,
+        }
+    }
+}
+
+fn to_validation_reason(errors: Vec<FieldError>) -> String {
+    errors
+        .iter()
+        .map(|error| format!("{}: {}", error.field, error.message))
+        .collect::<Vec<String>>()
+        .join("; ")
+}
+
+/**
+ * Parses the uploaded file using the same column names as the CSV export. Unknown columns (such as the export's
+ * 'id' column) are ignored. Rows are validated the same way as readings submitted through the form.
+ */
+fn parse_rows(
+    file_contents: &[u8],
+    now: DateTime<Utc>,
+) -> (Vec<ParsedRow>, Vec<ImportRowError>) {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(file_contents);
+
+    let mut rows = vec![];
+    let mut errors = vec![];
+
+    let headers = match reader.headers() {
+        Ok(headers) => headers.clone(),
+        Err(error) => {
+            errors.push(ImportRowError {
+                line: 1,
+                reason: format!("Could not read header row: {}", error),
+            });
+            return (rows, errors);
+        }
+    };
+
+    let tags_column = headers.iter().position(|header| header == "tags");
+
+    for record in reader.records() {
+        match record {
+            Err(error) => errors.push(ImportRowError {
+                line: error.position().map(|p| p.line()).unwrap_or(0),
+                reason: error.to_string(),
+            }),
+            Ok(record) => {
+                let line = record.position().map(|p| p.line()).unwrap_or(0);
+
+                let parsed = record
+                    .deserialize::<BloodPressureReadingSubmission>(Some(&headers))
+                    .map(|mut reading| {
+                        // Cells are typed by their contents when deserialized, which would turn a tag such as '007'
+                        // into the number 7, so the tags are taken from the cell as it was written
+                        if let Some(cell) = tags_column.and_then(|column| record.get(column)) {
+                            reading.tags = split_tags(cell);
+                        }
+
+                        reading
+                    })
+                    .map_err(|error| error.to_string())
+                    .and_then(|reading| {
+                        validate_submission(&reading, now)
+                            .map(|_| reading)
+                            .map_err(to_validation_reason)
+                    });
+
+                match parsed {
+                    Ok(reading) => rows.push(ParsedRow { line, reading }),
+                    Err(reason) => errors.push(ImportRowError { line, reason }),
+                }
+            }
+        }
+    }
+
+    (rows, errors)
+}
+
+/**
+ * The identities of the user's saved readings that the rows could duplicate, fetched in a single query covering the
+ * time span of the file
+ */
+async fn find_saved_identities<T: BloodPressureReadingRepository>(
+    reading_repository: &Arc<T>,
+    user_id: &str,
+    rows: &[ParsedRow],
+) -> Result<HashSet<ReadingIdentity>, ApiError> {
+    let taken = || rows.iter().map(|row| row.reading.taken);
+
+    let (Some(from), Some(to)) = (taken().min(), taken().max()) else {
+        return Ok(HashSet::new());
+    };
+
+    let existing = reading_repository
+        .list(user_id.to_string(), from, to, None)
+        .await?;
+
+    Ok(existing.iter().map(ReadingIdentity::from_entity).collect())
+}
+
+async fn import_readings<
+    T: BloodPressureReadingRepository,
+    U: SessionRepository,
+    V: UserRepository,
+>(
+    reading_repository: Arc<T>,
+    session_repository: LoggedInSessionRepository<U>,
+    user_repository: Arc<V>,
+    file_contents: Bytes,
+    dry_run: bool,
+) -> Result<CsvImportResponse, ApiError> {
+    let user_id = session_repository.get_oidc_user_subject().await?;
+
+    let profile = user_repository.get_profile(user_id.clone()).await?;
+
+    let (rows, errors) = parse_rows(&file_contents, Utc::now());
+
+    // Rows repeated within the file are caught the same way as readings that were already saved
+    let mut seen = find_saved_identities(&reading_repository, &user_id, &rows).await?;
+    let mut lines = vec![];
+    let mut entities = vec![];
+    let mut duplicate_lines = vec![];
+
+    for row in rows {
+        if !seen.insert(ReadingIdentity::from_submission(&row.reading)) {
+            duplicate_lines.push(row.line);
+            continue;
+        }
+
+        entities.push((
+            lines.len(),
+            BatchReadingEntity {
+                reading: to_new_entity(user_id.clone(), row.reading),
+                idempotency_key: None,
+                webhook_events: vec![],
+            },
+        ));
+        lines.push(row.line);
+    }
+
+    assign_batch_measurement_sessions(&reading_repository, &mut entities).await?;
+
+    let entities: Vec<BatchReadingEntity> =
+        entities.into_iter().map(|(_, entity)| entity).collect();
+
+    let imported = lines
+        .into_iter()
+        .zip(&entities)
+        .map(|(line, entity)| ImportedReading {
+            line,
+            reading: to_api_representation(entity.reading.clone(), &profile),
+        })
+        .collect();
+
+    // The rows are saved in a single transaction, so a failure part way through doesn't leave half the file imported
+    if !dry_run {
+        reading_repository.save_batch(entities).await?;
+    }
+
+    Ok(CsvImportResponse {
+        dry_run,
+        imported,
+        duplicate_lines,
+        errors,
+    })
+}
+
+async fn read_file_field(mut multipart: Multipart) -> Result<Bytes, ApiError> {
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|_| ApiError::bad_request("Could not read multipart body"))?
+    {
+        if field.name() == Some("file") {
+            return field
+                .bytes()
+                .await
+                .map_err(|_| ApiError::bad_request("Could not read uploaded file"));
+        }
+    }
+
+    Err(ApiError::bad_request("Expected a 'file' field"))
+}
+
+pub async fn import_csv<
+    T: BloodPressureReadingRepository,
+    U: SessionRepository,
+    V: UserRepository,
+>(
+    reading_repository: Arc<T>,
+    session_repository: LoggedInSessionRepository<U>,
+    user_repository: Arc<V>,
+    ApiQuery(query): ApiQuery<CsvImportQueryParameters>,
+    ApiMultipart(multipart): ApiMultipart,
+) -> Response {
+    let file_contents = match read_file_field(multipart).await {
+        Ok(file_contents) => file_contents,
+        Err(error) => return error.into_response(),
+    };
+
+    let result = import_readings(
+        reading_repository,
+        session_repository,
+        user_repository,
+        file_contents,
+        query.dry_run.unwrap_or(false),
+    )
+    .await;
+
+    match result {
+        Err(error) => error.into_response(),
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+    }
+}
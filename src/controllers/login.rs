@@ -1,8 +1,13 @@
 use std::sync::Arc;
 
-use crate::repositories::session_repository::SessionRepository;
+use crate::auth::api_token::{authenticate_api_token, get_bearer_token};
+use crate::controllers::api_error::ApiError;
+use crate::controllers::extractors::ApiQuery;
+use crate::repositories::api_token_repository::ApiTokenRepository;
+use crate::repositories::session_repository::{ApiTokenSubject, SessionRepository};
+use crate::repositories::user_repository::{UserProfileEntity, UserRepository};
 use axum::{
-    extract::{Query, Request},
+    extract::Request,
     middleware::Next,
     response::{IntoResponse, Redirect, Response},
 };
@@ -13,7 +18,6 @@ use openidconnect::{
     core::{CoreClient, CoreResponseType},
     reqwest::Client,
 };
-use reqwest::StatusCode;
 use serde::Deserialize;
 use tokio::join;
 use tower_sessions::Session;
@@ -26,6 +30,8 @@ const SUBJECT_SESSION_KEY: &str = "OIDC_SUBJECT_KEY";
 
 struct OpenIdDetails {
     subject: String,
+    display_name: Option<String>,
+    email: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -70,11 +76,7 @@ pub async fn login_handler<T: SessionRepository>(
 
     match result {
         (Ok(_), Ok(_), Ok(_)) => Redirect::temporary(authorize_url.as_str()).into_response(),
-        _ => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            "Error storing auth details into session",
-        )
-            .into_response(),
+        _ => ApiError::internal("Error storing auth details into session").into_response(),
     }
 }
 
@@ -152,16 +154,30 @@ async fn get_user_details<T: SessionRepository>(
 
             let subject: &openidconnect::SubjectIdentifier = claims.subject();
 
+            // Not every provider sends a name, so fall back to the username if there is one
+            let display_name = claims
+                .name()
+                .and_then(|name| name.get(None))
+                .map(|name| name.as_str().to_string())
+                .or_else(|| {
+                    claims
+                        .preferred_username()
+                        .map(|username| username.as_str().to_string())
+                });
+
             return Ok(OpenIdDetails {
                 subject: subject.as_str().to_string(),
+                display_name,
+                email: claims.email().map(|email| email.as_str().to_string()),
             });
         }
         _ => Err("Error getting auth details from session".to_string()),
     }
 }
 
-pub async fn oidc_callback_handler<T: SessionRepository>(
+pub async fn oidc_callback_handler<T: SessionRepository, V: UserRepository>(
     session: T,
+    user_repository: Arc<V>,
     oidc_client: Arc<
         CoreClient<
             EndpointSet,
@@ -173,9 +189,9 @@ pub async fn oidc_callback_handler<T: SessionRepository>(
         >,
     >,
     http_client: Client,
-    query_params: Query<CallBackParameters>,
+    ApiQuery(query_params): ApiQuery<CallBackParameters>,
 ) -> Response {
-    let CallBackParameters { code, state } = query_params.0;
+    let CallBackParameters { code, state } = query_params;
 
     let authorization_code = AuthorizationCode::new(code);
 
@@ -190,25 +206,36 @@ pub async fn oidc_callback_handler<T: SessionRepository>(
 
     match details {
         Ok(user_details) => {
+            let profile = UserProfileEntity {
+                display_name: user_details.display_name,
+                email: user_details.email,
+                ..UserProfileEntity::defaults_for(user_details.subject.clone())
+            };
+
+            if user_repository
+                .create_or_refresh_profile(profile)
+                .await
+                .is_err()
+            {
+                return ApiError::internal("Could not create user profile.").into_response();
+            }
+
             let store_subject_result = session.save_oidc_user_subject(user_details.subject).await;
 
             let clear_result = session.clear_oidc_flow_details().await;
 
             if store_subject_result.is_err() || clear_result.is_err() {
-                return (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    "Could not start session.",
-                )
-                    .into_response();
+                return ApiError::internal("Could not start session.").into_response();
             }
 
             Redirect::temporary("/").into_response()
         }
-        Err(_) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            "Authentication not successful.",
-        )
-            .into_response(),
+        Err(description) => {
+            // Why the login failed is only of use to us, so it is logged rather than returned
+            println!("Could not log in: {}", description);
+
+            ApiError::Unauthorized.into_response()
+        }
     }
 }
 
@@ -217,21 +244,48 @@ pub async fn logout_handler(session: Session) -> Response {
 
     match result {
         Ok(_) => Redirect::temporary("/").into_response(),
-        Err(_) => (StatusCode::INTERNAL_SERVER_ERROR).into_response(),
+        Err(_) => ApiError::internal("Could not end session.").into_response(),
     }
 }
 
-pub async fn auth_middleware(session: Session, request: Request, next: Next) -> Response {
+/**
+ * Requires requests to /api/ to come from a logged in session or carry a personal access token. A token is checked
+ * and resolved to its owner here, so handlers see the same user either way.
+ */
+pub async fn auth_middleware<A: ApiTokenRepository>(
+    api_token_repository: Arc<A>,
+    session: Session,
+    mut request: Request,
+    next: Next,
+) -> Response {
     let is_api_request = request.uri().to_string().starts_with("/api/");
 
     if !is_api_request {
         return next.run(request).await;
     }
 
+    if let Some(token) = get_bearer_token(request.headers()) {
+        let result = authenticate_api_token(
+            &api_token_repository,
+            token,
+            request.method(),
+            request.uri().path(),
+        )
+        .await;
+
+        return match result {
+            Ok(subject) => {
+                request.extensions_mut().insert(ApiTokenSubject(subject));
+                next.run(request).await
+            }
+            Err(error) => error.into_response(),
+        };
+    }
+
     let result = session.get::<String>(SUBJECT_SESSION_KEY).await;
 
     match result {
         Ok(Some(_)) => return next.run(request).await,
-        _ => return (StatusCode::UNAUTHORIZED).into_response(),
+        _ => return ApiError::Unauthorized.into_response(),
     }
 }
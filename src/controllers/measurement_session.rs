@@ -0,0 +1,96 @@
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    controllers::{api_error::ApiError, extractors::ApiQuery},
+    repositories::{
+        blood_pressure_readings_repository::{
+            BloodPressureReadingRepository, MeasurementSessionEntity,
+        },
+        session_repository::{LoggedInSessionRepository, SessionRepository},
+    },
+};
+
+#[derive(Deserialize)]
+pub struct GetMeasurementSessionsQueryParameters {
+    pub from_inclusive: DateTime<Utc>,
+    pub to_inclusive: DateTime<Utc>,
+    /**
+     * Leave the first reading of each sitting out of the averages. Defaults to false
+     */
+    pub discard_first: Option<bool>,
+}
+
+#[derive(Serialize)]
+pub struct MeasurementSessionResponse {
+    pub id: String,
+    pub started: DateTime<Utc>,
+    pub ended: DateTime<Utc>,
+    pub reading_count: i64,
+    pub averaged_count: i64,
+    pub systolic_mean: f64,
+    pub diastolic_mean: f64,
+    pub pulse_mean: f64,
+}
+
+fn to_api_representation(entity: MeasurementSessionEntity) -> MeasurementSessionResponse {
+    MeasurementSessionResponse {
+        id: entity.measurement_session_id,
+        started: entity.started,
+        ended: entity.ended,
+        reading_count: entity.reading_count,
+        averaged_count: entity.averaged_count,
+        systolic_mean: entity.systolic_mean,
+        diastolic_mean: entity.diastolic_mean,
+        pulse_mean: entity.pulse_mean,
+    }
+}
+
+async fn get_measurement_sessions_from_database<
+    T: BloodPressureReadingRepository,
+    U: SessionRepository,
+>(
+    reading_repository: Arc<T>,
+    session_repository: LoggedInSessionRepository<U>,
+    query: GetMeasurementSessionsQueryParameters,
+) -> Result<Vec<MeasurementSessionResponse>, ApiError> {
+    let user_id = session_repository.get_oidc_user_subject().await?;
+
+    let sessions = reading_repository
+        .list_measurement_sessions(
+            user_id,
+            query.from_inclusive,
+            query.to_inclusive,
+            query.discard_first.unwrap_or(false),
+        )
+        .await?;
+
+    Ok(sessions.into_iter().map(to_api_representation).collect())
+}
+
+pub async fn get_measurement_sessions<T: BloodPressureReadingRepository, U: SessionRepository>(
+    reading_repository: Arc<T>,
+    session_repository: LoggedInSessionRepository<U>,
+    ApiQuery(query): ApiQuery<GetMeasurementSessionsQueryParameters>,
+) -> Response {
+    if query.from_inclusive > query.to_inclusive {
+        return ApiError::bad_request("from_inclusive must not be after to_inclusive")
+            .into_response();
+    }
+
+    let result =
+        get_measurement_sessions_from_database(reading_repository, session_repository, query)
+            .await;
+
+    match result {
+        Err(error) => error.into_response(),
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+    }
+}
@@ -1,5 +1,22 @@
+pub(crate) mod alerts;
+pub(crate) mod api_error;
+pub(crate) mod api_tokens;
 pub(crate) mod blood_pressure_reading;
+pub(crate) mod chart;
 pub(crate) mod export;
+pub(crate) mod extractors;
+pub(crate) mod import;
 pub(crate) mod login;
+pub(crate) mod measurement_session;
 pub(crate) mod ocr;
+pub(crate) mod reading_batch;
+pub(crate) mod reading_series;
+pub(crate) mod reading_summary;
+pub(crate) mod reminders;
+pub(crate) mod report;
+pub(crate) mod share;
+pub(crate) mod time_of_day;
+pub(crate) mod user_info;
+pub(crate) mod validation;
+pub(crate) mod webhooks;
 pub(crate) mod weight;
@@ -1,4 +1,6 @@
-use axum::{Json, body::Bytes, extract::Multipart, http::StatusCode};
+use std::sync::Arc;
+
+use axum::{Json, body::Bytes};
 use bpm_ocr::{
     get_reading_from_buffer,
     models::{BloodPressureReading, DebuggerTrace, ProcessingError},
@@ -6,6 +8,17 @@ use bpm_ocr::{
 use serde::Serialize;
 use tokio::task;
 
+use crate::{
+    classification::blood_pressure_category::{
+        BloodPressureCategory, ClassificationGuideline, classify,
+    },
+    controllers::{api_error::ApiError, extractors::ApiMultipart},
+    repositories::{
+        session_repository::{LoggedInSessionRepository, SessionRepository},
+        user_repository::UserRepository,
+    },
+};
+
 #[derive(Serialize)]
 #[serde(tag = "type")]
 pub enum BloodPressureReadingResponse {
@@ -13,6 +26,7 @@ pub enum BloodPressureReadingResponse {
         systolic: i32,
         diastolic: i32,
         pulse: i32,
+        category: BloodPressureCategory,
     },
     ReadingError {
         description: String,
@@ -21,6 +35,7 @@ pub enum BloodPressureReadingResponse {
         systolic: i32,
         diastolic: i32,
         pulse: i32,
+        category: BloodPressureCategory,
     },
 }
 
@@ -42,6 +57,7 @@ fn is_unlikely_reading(reading: &BloodPressureReading) -> bool {
 
 fn map_ocr_result(
     reading: Result<BloodPressureReading, ProcessingError>,
+    guideline: ClassificationGuideline,
 ) -> BloodPressureReadingResponse {
     match reading {
         Err(_) => BloodPressureReadingResponse::ReadingError {
@@ -52,22 +68,33 @@ fn map_ocr_result(
                 systolic: reading.systolic,
                 diastolic: reading.diastolic,
                 pulse: reading.pulse,
+                category: classify(reading.systolic, reading.diastolic, guideline),
             }
         }
         Ok(reading) => BloodPressureReadingResponse::Reading {
             systolic: reading.systolic,
             diastolic: reading.diastolic,
             pulse: reading.pulse,
+            category: classify(reading.systolic, reading.diastolic, guideline),
         },
     }
 }
 
-pub async fn run_ocr(
-    mut multipart: Multipart,
-) -> Result<Json<BloodPressureReadingResponse>, StatusCode> {
+pub async fn run_ocr<U: SessionRepository, V: UserRepository>(
+    session_repository: LoggedInSessionRepository<U>,
+    user_repository: Arc<V>,
+    ApiMultipart(mut multipart): ApiMultipart,
+) -> Result<Json<BloodPressureReadingResponse>, ApiError> {
+    let user_id = session_repository.get_oidc_user_subject().await?;
+
+    let guideline = user_repository
+        .get_profile(user_id)
+        .await?
+        .classification_guideline;
+
     let field = multipart.next_field().await.map_err(|ee| {
         println!("{:?}", ee);
-        StatusCode::BAD_REQUEST
+        ApiError::bad_request("Could not read multipart body")
     })?;
 
     match field {
@@ -75,9 +102,12 @@ pub async fn run_ocr(
             field
                 .name()
                 .filter(|n| *n == "image")
-                .ok_or(StatusCode::BAD_REQUEST)?;
+                .ok_or(ApiError::bad_request("Expected an 'image' field"))?;
 
-            let file_contents: Bytes = field.bytes().await.map_err(|_| StatusCode::BAD_REQUEST)?;
+            let file_contents: Bytes = field
+                .bytes()
+                .await
+                .map_err(|_| ApiError::bad_request("Could not read uploaded image"))?;
             let file_contents_vec = file_contents.to_vec();
 
             task::spawn_blocking(move || {
@@ -86,13 +116,13 @@ pub async fn run_ocr(
 
                 println!("{:?}", ocr_result);
 
-                let result = map_ocr_result(ocr_result);
+                let result = map_ocr_result(ocr_result, guideline);
 
                 Json(result)
             })
             .await
-            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+            .map_err(|_| ApiError::internal("OCR task did not complete"))
         }
-        None => Err(StatusCode::BAD_REQUEST),
+        None => Err(ApiError::bad_request("Expected an 'image' field")),
     }
 }
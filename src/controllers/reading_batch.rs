@@ -0,0 +1,356 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use axum::{
+    Json,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, TimeDelta, Utc};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    controllers::{
+        alerts::{self, AlertResponse, email_alerts, raise_alerts},
+        api_error::ApiError,
+        blood_pressure_reading::{
+            BloodPressureReadingResponse, BloodPressureReadingSubmission,
+            MEASUREMENT_SESSION_WINDOW_MINUTES, assign_measurement_session, to_api_representation,
+            to_new_entity, validate_submission,
+        },
+        extractors::ApiJson,
+        validation::{FieldError, field_error, to_result},
+        webhooks::to_webhook_event,
+    },
+    notifications::notification_queue::NotificationQueue,
+    repositories::{
+        alert_repository::AlertRepository,
+        blood_pressure_readings_repository::{
+            BatchReadingEntity, BatchSaveOutcome, BloodPressureReadingEntity,
+            BloodPressureReadingRepository,
+        },
+        session_repository::{LoggedInSessionRepository, SessionRepository},
+        user_repository::UserRepository,
+        webhook_repository::WebhookEvent,
+    },
+};
+
+const MAX_BATCH_SIZE: usize = 500;
+
+// Readings taken longer ago than this were most likely synced after the fact, so alerting on them would only be noise
+const RECENT_READING_MINUTES: i64 = 60;
+const MAX_IDEMPOTENCY_KEY_CHARACTERS: usize = 255;
+
+#[derive(Deserialize)]
+pub struct BatchReadingSubmission {
+    /**
+     * Chosen by the client, e.g. a UUID stored with the reading on the device. A reading with a key that has already
+     * been used isn't saved again, so a batch can be resent safely if the response was lost.
+     */
+    pub idempotency_key: Option<String>,
+    #[serde(flatten)]
+    pub reading: BloodPressureReadingSubmission,
+}
+
+/**
+ * Validates the reading the same way as a single submission, along with the idempotency key
+ */
+pub fn validate_batch_reading_submission(
+    item: &BatchReadingSubmission,
+    now: DateTime<Utc>,
+) -> Result<(), Vec<FieldError>> {
+    let mut errors = validate_submission(&item.reading, now)
+        .err()
+        .unwrap_or_default();
+
+    if let Some(idempotency_key) = &item.idempotency_key {
+        let length = idempotency_key.chars().count();
+
+        if length == 0 || length > MAX_IDEMPOTENCY_KEY_CHARACTERS {
+            errors.push(field_error(
+                "idempotency_key",
+                format!(
+                    "Must be between 1 and {} characters",
+                    MAX_IDEMPOTENCY_KEY_CHARACTERS
+                ),
+            ));
+        }
+    }
+
+    to_result(errors)
+}
+
+/**
+ * The result for one reading in a batch. The index is its position in the submitted array.
+ */
+#[derive(Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum BatchItemResult {
+    Created {
+        index: usize,
+        id: String,
+        reading: BloodPressureReadingResponse,
+        /**
+         * Only readings taken recently are checked against the alert rules, so this is empty for older ones
+         */
+        alerts: Vec<AlertResponse>,
+    },
+    /**
+     * A reading with the same idempotency key was already saved. The ID is that of the saved reading.
+     */
+    Duplicate { index: usize, id: String },
+    Invalid {
+        index: usize,
+        errors: Vec<FieldError>,
+    },
+}
+
+#[derive(Serialize)]
+pub struct BatchSubmissionResponse {
+    pub results: Vec<BatchItemResult>,
+}
+
+/**
+ * Puts readings without an explicit measurement session into a session the same way a single reading is. Readings
+ * from the same sitting usually arrive in the same batch, so earlier readings in the batch are checked before the
+ * saved ones. The readings are returned in order of the index they were given with.
+ */
+pub async fn assign_batch_measurement_sessions<T: BloodPressureReadingRepository>(
+    reading_repository: &Arc<T>,
+    entities: &mut [(usize, BatchReadingEntity)],
+) -> Result<(), ApiError> {
+    let window = TimeDelta::minutes(MEASUREMENT_SESSION_WINDOW_MINUTES);
+
+    entities.sort_by_key(|(_, entity)| entity.reading.taken);
+
+    for position in 0..entities.len() {
+        if entities[position]
+            .1
+            .reading
+            .measurement_session_id
+            .is_some()
+        {
+            continue;
+        }
+
+        let taken = entities[position].1.reading.taken;
+        let batch_session = entities[..position]
+            .iter()
+            .rev()
+            .take_while(|(_, earlier)| taken - earlier.reading.taken <= window)
+            .find_map(|(_, earlier)| earlier.reading.measurement_session_id.clone());
+
+        let reading = &mut entities[position].1.reading;
+
+        match batch_session {
+            Some(measurement_session_id) => {
+                reading.measurement_session_id = Some(measurement_session_id)
+            }
+            None => {
+                let assigned =
+                    assign_measurement_session(reading_repository, reading.clone()).await?;
+                reading.measurement_session_id = assigned.measurement_session_id;
+            }
+        }
+    }
+
+    entities.sort_by_key(|(index, _)| *index);
+
+    Ok(())
+}
+
+async fn add_reading_batch_to_database<
+    T: BloodPressureReadingRepository,
+    U: SessionRepository,
+    V: UserRepository,
+    W: AlertRepository,
+>(
+    reading_repository: Arc<T>,
+    session_repository: LoggedInSessionRepository<U>,
+    user_repository: Arc<V>,
+    alert_repository: Arc<W>,
+    notification_queue: Arc<NotificationQueue>,
+    batch: Vec<BatchReadingSubmission>,
+) -> Result<BatchSubmissionResponse, ApiError> {
+    if batch.len() > MAX_BATCH_SIZE {
+        return Err(ApiError::bad_request(&format!(
+            "No more than {} readings can be submitted at once",
+            MAX_BATCH_SIZE
+        )));
+    }
+
+    let user_id = session_repository.get_oidc_user_subject().await?;
+
+    let profile = user_repository.get_profile(user_id.clone()).await?;
+
+    let now = Utc::now();
+    let mut results = vec![];
+    let mut valid = vec![];
+
+    for (index, item) in batch.into_iter().enumerate() {
+        match validate_batch_reading_submission(&item, now) {
+            Err(errors) => results.push(BatchItemResult::Invalid { index, errors }),
+            Ok(_) => valid.push((index, item)),
+        }
+    }
+
+    let idempotency_keys = valid
+        .iter()
+        .filter_map(|(_, item)| item.idempotency_key.clone())
+        .collect();
+    let already_saved = reading_repository
+        .find_by_idempotency_keys(user_id.clone(), idempotency_keys)
+        .await?;
+
+    // Duplicates are left out before measurement sessions are assigned, so that a reading that won't be saved can't
+    // pull the readings around it into its session
+    let mut batch_keys = HashSet::new();
+    let mut repeated = vec![];
+    let mut entities = vec![];
+
+    for (index, item) in valid {
+        if let Some(idempotency_key) = &item.idempotency_key {
+            if let Some(reading_id) = already_saved.get(idempotency_key) {
+                results.push(BatchItemResult::Duplicate {
+                    index,
+                    id: reading_id.clone(),
+                });
+                continue;
+            }
+
+            if !batch_keys.insert(idempotency_key.clone()) {
+                repeated.push((index, idempotency_key.clone()));
+                continue;
+            }
+        }
+
+        entities.push((
+            index,
+            BatchReadingEntity {
+                reading: to_new_entity(user_id.clone(), item.reading),
+                idempotency_key: item.idempotency_key,
+                webhook_events: vec![],
+            },
+        ));
+    }
+
+    assign_batch_measurement_sessions(&reading_repository, &mut entities).await?;
+
+    for (_, entity) in &mut entities {
+        entity.webhook_events = to_webhook_event(
+            WebhookEvent::ReadingAdded,
+            &to_api_representation(entity.reading.clone(), &profile),
+        )
+        .into_iter()
+        .collect();
+    }
+
+    let (indexes, entities): (Vec<usize>, Vec<BatchReadingEntity>) = entities.into_iter().unzip();
+    let readings: Vec<(BloodPressureReadingEntity, Option<String>)> = entities
+        .iter()
+        .map(|entity| (entity.reading.clone(), entity.idempotency_key.clone()))
+        .collect();
+
+    let outcomes = reading_repository.save_batch(entities).await?;
+
+    // The IDs that readings repeating a key from earlier in the batch are reported as duplicates of
+    let mut saved_keys = HashMap::new();
+    let recent_since = now - TimeDelta::minutes(RECENT_READING_MINUTES);
+
+    for ((index, (reading, idempotency_key)), outcome) in
+        indexes.into_iter().zip(readings).zip(outcomes)
+    {
+        if let Some(idempotency_key) = idempotency_key {
+            let id = match &outcome {
+                BatchSaveOutcome::AlreadySaved { reading_id } => reading_id.clone(),
+                BatchSaveOutcome::Saved => reading.reading_id.clone(),
+            };
+
+            saved_keys.insert(idempotency_key, id);
+        }
+
+        let result = match outcome {
+            BatchSaveOutcome::AlreadySaved { reading_id } => BatchItemResult::Duplicate {
+                index,
+                id: reading_id,
+            },
+            BatchSaveOutcome::Saved => {
+                let alerts = match reading.taken >= recent_since {
+                    true => raise_alerts(&reading_repository, &alert_repository, &reading).await,
+                    false => vec![],
+                };
+                email_alerts(
+                    &notification_queue,
+                    profile.email.clone(),
+                    &reading,
+                    &alerts,
+                );
+
+                let reading = to_api_representation(reading, &profile);
+
+                BatchItemResult::Created {
+                    index,
+                    id: reading.id.clone(),
+                    reading,
+                    alerts: alerts
+                        .into_iter()
+                        .map(alerts::to_api_representation)
+                        .collect(),
+                }
+            }
+        };
+
+        results.push(result);
+    }
+
+    for (index, idempotency_key) in repeated {
+        results.push(BatchItemResult::Duplicate {
+            index,
+            id: saved_keys[&idempotency_key].clone(),
+        });
+    }
+
+    results.sort_by_key(|result| match result {
+        BatchItemResult::Created { index, .. }
+        | BatchItemResult::Duplicate { index, .. }
+        | BatchItemResult::Invalid { index, .. } => *index,
+    });
+
+    Ok(BatchSubmissionResponse { results })
+}
+
+/**
+ * Adds several readings at once, e.g. from a device that has been offline. Each reading is validated on its own and
+ * the valid ones are saved together, so one bad reading doesn't hold up the rest. Readings synced this way are
+ * usually old by the time they arrive, so only the ones taken within the last hour raise alerts.
+ */
+pub async fn add_reading_batch<
+    T: BloodPressureReadingRepository,
+    U: SessionRepository,
+    V: UserRepository,
+    W: AlertRepository,
+>(
+    reading_repository: Arc<T>,
+    session_repository: LoggedInSessionRepository<U>,
+    user_repository: Arc<V>,
+    alert_repository: Arc<W>,
+    notification_queue: Arc<NotificationQueue>,
+    ApiJson(body): ApiJson<Vec<BatchReadingSubmission>>,
+) -> Response {
+    let result = add_reading_batch_to_database(
+        reading_repository,
+        session_repository,
+        user_repository,
+        alert_repository,
+        notification_queue,
+        body,
+    )
+    .await;
+
+    match result {
+        Err(error) => error.into_response(),
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+    }
+}
@@ -0,0 +1,136 @@
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    aggregation::time_buckets::{BucketSize, to_buckets},
+    controllers::{api_error::ApiError, extractors::ApiQuery},
+    repositories::{
+        blood_pressure_readings_repository::{
+            BloodPressureReadingRepository, BucketAggregateEntity,
+        },
+        session_repository::{LoggedInSessionRepository, SessionRepository},
+        user_repository::UserRepository,
+    },
+};
+
+// Roughly three years of daily buckets
+const MAX_BUCKETS: usize = 1100;
+
+#[derive(Deserialize)]
+pub struct GetReadingSeriesQueryParameters {
+    pub from_inclusive: DateTime<Utc>,
+    pub to_inclusive: DateTime<Utc>,
+    pub bucket: BucketSize,
+    /**
+     * An IANA timezone name such as 'Europe/London'. Defaults to the timezone in the user's profile
+     */
+    pub tz: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct ReadingSeriesBucketResponse {
+    pub start: DateTime<Tz>,
+    pub end: DateTime<Tz>,
+    pub count: i64,
+    pub systolic_mean: Option<f64>,
+    pub diastolic_mean: Option<f64>,
+    pub pulse_mean: Option<f64>,
+    pub weight_kilograms_mean: Option<f64>,
+    pub weight_count: i64,
+}
+
+#[derive(Serialize)]
+pub struct ReadingSeriesResponse {
+    pub timezone: String,
+    pub bucket: BucketSize,
+    pub buckets: Vec<ReadingSeriesBucketResponse>,
+}
+
+fn to_api_representation(
+    entity: BucketAggregateEntity,
+    timezone: Tz,
+) -> ReadingSeriesBucketResponse {
+    ReadingSeriesBucketResponse {
+        start: entity.start.with_timezone(&timezone),
+        end: entity.end.with_timezone(&timezone),
+        count: entity.count,
+        systolic_mean: entity.systolic_mean,
+        diastolic_mean: entity.diastolic_mean,
+        pulse_mean: entity.pulse_mean,
+        weight_kilograms_mean: entity.weight_kilograms_mean,
+        weight_count: entity.weight_count,
+    }
+}
+
+async fn get_series_from_database<
+    T: BloodPressureReadingRepository,
+    U: SessionRepository,
+    V: UserRepository,
+>(
+    reading_repository: Arc<T>,
+    session_repository: LoggedInSessionRepository<U>,
+    user_repository: Arc<V>,
+    query: GetReadingSeriesQueryParameters,
+) -> Result<ReadingSeriesResponse, ApiError> {
+    let user_id = session_repository.get_oidc_user_subject().await?;
+
+    let timezone: Tz = match &query.tz {
+        None => user_repository.get_profile(user_id.clone()).await?.timezone,
+        Some(name) => name
+            .parse()
+            .map_err(|_| ApiError::bad_request("tz must be an IANA timezone name"))?,
+    };
+
+    let buckets = to_buckets(
+        query.from_inclusive,
+        query.to_inclusive,
+        query.bucket,
+        timezone,
+        MAX_BUCKETS,
+    )
+    .ok_or_else(|| ApiError::bad_request("The range contains too many buckets"))?;
+
+    let aggregates = reading_repository
+        .aggregate_by_bucket(user_id, &buckets, query.from_inclusive, query.to_inclusive)
+        .await?;
+
+    Ok(ReadingSeriesResponse {
+        timezone: timezone.name().to_string(),
+        bucket: query.bucket,
+        buckets: aggregates
+            .into_iter()
+            .map(|entity| to_api_representation(entity, timezone))
+            .collect(),
+    })
+}
+
+pub async fn get_reading_series<
+    T: BloodPressureReadingRepository,
+    U: SessionRepository,
+    V: UserRepository,
+>(
+    reading_repository: Arc<T>,
+    session_repository: LoggedInSessionRepository<U>,
+    user_repository: Arc<V>,
+    ApiQuery(query): ApiQuery<GetReadingSeriesQueryParameters>,
+) -> Response {
+    if query.from_inclusive > query.to_inclusive {
+        return ApiError::bad_request("from_inclusive must not be after to_inclusive")
+            .into_response();
+    }
+
+    let result = get_series_from_database(reading_repository, session_repository, user_repository, query).await;
+
+    match result {
+        Err(error) => error.into_response(),
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+    }
+}
@@ -0,0 +1,157 @@
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    classification::{
+        blood_pressure_category::{CategoryCounts, classify},
+        target_range::{TargetRange, TimeInRange},
+    },
+    controllers::{api_error::ApiError, extractors::ApiQuery},
+    repositories::{
+        blood_pressure_readings_repository::{
+            BloodPressureReadingRepository, MetricSummaryEntity, ReadingSummaryEntity,
+        },
+        session_repository::{LoggedInSessionRepository, SessionRepository},
+        user_repository::UserRepository,
+    },
+};
+
+#[derive(Deserialize)]
+pub struct GetReadingSummaryQueryParameters {
+    pub from_inclusive: DateTime<Utc>,
+    pub to_inclusive: DateTime<Utc>,
+}
+
+#[derive(Serialize)]
+pub struct MetricSummaryResponse {
+    pub mean: f64,
+    pub min: i32,
+    pub max: i32,
+    pub standard_deviation: Option<f64>,
+}
+
+#[derive(Serialize)]
+pub struct ReadingSummaryResponse {
+    pub count: i64,
+    pub systolic: Option<MetricSummaryResponse>,
+    pub diastolic: Option<MetricSummaryResponse>,
+    pub pulse: Option<MetricSummaryResponse>,
+    pub mean_pulse_pressure: Option<f64>,
+    pub mean_arterial_pressure: Option<f64>,
+    /**
+     * How many readings fall into each category according to the user's chosen guideline
+     */
+    pub category_counts: CategoryCounts,
+    /**
+     * How many readings were within the targets in the user's profile
+     */
+    pub time_in_range: TimeInRange,
+}
+
+fn to_metric_api_representation(entity: MetricSummaryEntity) -> MetricSummaryResponse {
+    MetricSummaryResponse {
+        mean: entity.mean,
+        min: entity.min,
+        max: entity.max,
+        standard_deviation: entity.standard_deviation,
+    }
+}
+
+fn to_api_representation(
+    entity: ReadingSummaryEntity,
+    category_counts: CategoryCounts,
+    time_in_range: TimeInRange,
+) -> ReadingSummaryResponse {
+    ReadingSummaryResponse {
+        count: entity.count,
+        systolic: entity.systolic.map(to_metric_api_representation),
+        diastolic: entity.diastolic.map(to_metric_api_representation),
+        pulse: entity.pulse.map(to_metric_api_representation),
+        mean_pulse_pressure: entity.mean_pulse_pressure,
+        mean_arterial_pressure: entity.mean_arterial_pressure,
+        category_counts,
+        time_in_range,
+    }
+}
+
+async fn get_summary_from_database<
+    T: BloodPressureReadingRepository,
+    U: SessionRepository,
+    V: UserRepository,
+>(
+    reading_repository: Arc<T>,
+    session_repository: LoggedInSessionRepository<U>,
+    user_repository: Arc<V>,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<ReadingSummaryResponse, ApiError> {
+    let user_id = session_repository.get_oidc_user_subject().await?;
+
+    let profile = user_repository.get_profile(user_id.clone()).await?;
+    let target_range = TargetRange::from_profile(&profile);
+
+    let summary = reading_repository
+        .summarise(user_id.clone(), from, to)
+        .await?;
+
+    // The category thresholds and targets live in the classification module rather than being duplicated in SQL
+    let readings = reading_repository.list(user_id, from, to, None).await?;
+    let mut category_counts = CategoryCounts::default();
+    let mut time_in_range = TimeInRange::default();
+
+    for reading in readings {
+        category_counts.add(classify(
+            reading.systolic,
+            reading.diastolic,
+            profile.classification_guideline,
+        ));
+        time_in_range.add(target_range.is_within(
+            reading.systolic,
+            reading.diastolic,
+            reading.pulse,
+        ));
+    }
+
+    Ok(to_api_representation(
+        summary,
+        category_counts,
+        time_in_range,
+    ))
+}
+
+pub async fn get_reading_summary<
+    T: BloodPressureReadingRepository,
+    U: SessionRepository,
+    V: UserRepository,
+>(
+    reading_repository: Arc<T>,
+    session_repository: LoggedInSessionRepository<U>,
+    user_repository: Arc<V>,
+    ApiQuery(query): ApiQuery<GetReadingSummaryQueryParameters>,
+) -> Response {
+    if query.from_inclusive > query.to_inclusive {
+        return ApiError::bad_request("from_inclusive must not be after to_inclusive")
+            .into_response();
+    }
+
+    let result = get_summary_from_database(
+        reading_repository,
+        session_repository,
+        user_repository,
+        query.from_inclusive,
+        query.to_inclusive,
+    )
+    .await;
+
+    match result {
+        Err(error) => error.into_response(),
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+    }
+}
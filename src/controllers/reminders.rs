@@ -0,0 +1,218 @@
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    extract::Path,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, NaiveTime, Utc};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    controllers::{
+        api_error::ApiError,
+        extractors::ApiJson,
+        validation::{FieldError, field_error, to_result},
+    },
+    reminders::scheduler::latest_occurrence,
+    repositories::{
+        reminder_repository::{ReminderEntity, ReminderRepository},
+        session_repository::{LoggedInSessionRepository, SessionRepository},
+        user_repository::UserRepository,
+    },
+};
+
+const DEFAULT_WINDOW_MINUTES: u32 = 60;
+const MAX_REMINDER_WINDOW_MINUTES: u32 = 12 * 60;
+
+fn default_window_minutes() -> u32 {
+    DEFAULT_WINDOW_MINUTES
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Deserialize)]
+pub struct ReminderSubmission {
+    /**
+     * The time of day in the user's timezone, for example "07:00"
+     */
+    pub time_of_day: NaiveTime,
+    #[serde(default = "default_window_minutes")]
+    pub window_minutes: u32,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+pub fn validate_reminder_submission(reminder: &ReminderSubmission) -> Result<(), Vec<FieldError>> {
+    let mut errors = vec![];
+
+    if !(1..=MAX_REMINDER_WINDOW_MINUTES).contains(&reminder.window_minutes) {
+        errors.push(field_error(
+            "window_minutes",
+            format!("Must be between 1 and {}", MAX_REMINDER_WINDOW_MINUTES),
+        ));
+    }
+
+    to_result(errors)
+}
+
+#[derive(Serialize)]
+pub struct ReminderResponse {
+    pub id: String,
+    pub time_of_day: String,
+    pub window_minutes: u32,
+    pub enabled: bool,
+    pub last_triggered: Option<DateTime<Utc>>,
+}
+
+fn to_api_representation(entity: ReminderEntity) -> ReminderResponse {
+    ReminderResponse {
+        id: entity.reminder_id,
+        time_of_day: entity.time_of_day.format("%H:%M").to_string(),
+        window_minutes: entity.window_minutes,
+        enabled: entity.enabled,
+        last_triggered: entity.last_triggered,
+    }
+}
+
+async fn get_reminders_from_database<R: ReminderRepository, U: SessionRepository>(
+    reminder_repository: Arc<R>,
+    session_repository: LoggedInSessionRepository<U>,
+) -> Result<Vec<ReminderResponse>, ApiError> {
+    let user_id = session_repository.get_oidc_user_subject().await?;
+
+    let reminders = reminder_repository.list(user_id).await?;
+
+    Ok(reminders.into_iter().map(to_api_representation).collect())
+}
+
+pub async fn get_reminders<R: ReminderRepository, U: SessionRepository>(
+    reminder_repository: Arc<R>,
+    session_repository: LoggedInSessionRepository<U>,
+) -> Response {
+    let result = get_reminders_from_database(reminder_repository, session_repository).await;
+
+    match result {
+        Err(error) => error.into_response(),
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+    }
+}
+
+async fn add_reminder_to_database<
+    R: ReminderRepository,
+    U: SessionRepository,
+    V: UserRepository,
+>(
+    reminder_repository: Arc<R>,
+    session_repository: LoggedInSessionRepository<U>,
+    user_repository: Arc<V>,
+    reminder: ReminderSubmission,
+) -> Result<ReminderResponse, ApiError> {
+    validate_reminder_submission(&reminder)?;
+
+    let user_id = session_repository.get_oidc_user_subject().await?;
+
+    let profile = user_repository.get_profile(user_id.clone()).await?;
+
+    // A reminder whose time has already passed today is first due tomorrow, rather than straight away
+    let last_triggered = latest_occurrence(reminder.time_of_day, profile.timezone, Utc::now());
+
+    let entity = ReminderEntity {
+        reminder_id: Uuid::now_v7().to_string(),
+        user_id,
+        time_of_day: reminder.time_of_day,
+        window_minutes: reminder.window_minutes,
+        enabled: reminder.enabled,
+        last_triggered,
+    };
+
+    reminder_repository.save(entity.clone()).await?;
+
+    Ok(to_api_representation(entity))
+}
+
+pub async fn add_reminder<R: ReminderRepository, U: SessionRepository, V: UserRepository>(
+    reminder_repository: Arc<R>,
+    session_repository: LoggedInSessionRepository<U>,
+    user_repository: Arc<V>,
+    ApiJson(body): ApiJson<ReminderSubmission>,
+) -> Response {
+    let result =
+        add_reminder_to_database(reminder_repository, session_repository, user_repository, body)
+            .await;
+
+    match result {
+        Err(error) => error.into_response(),
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+    }
+}
+
+async fn update_reminder_in_database<R: ReminderRepository, U: SessionRepository>(
+    reminder_repository: Arc<R>,
+    session_repository: LoggedInSessionRepository<U>,
+    reminder_id: String,
+    reminder: ReminderSubmission,
+) -> Result<ReminderResponse, ApiError> {
+    validate_reminder_submission(&reminder)?;
+
+    let user_id = session_repository.get_oidc_user_subject().await?;
+
+    let entity = ReminderEntity {
+        reminder_id,
+        user_id,
+        time_of_day: reminder.time_of_day,
+        window_minutes: reminder.window_minutes,
+        enabled: reminder.enabled,
+        last_triggered: None,
+    };
+
+    let updated = reminder_repository.update(entity).await?;
+
+    Ok(to_api_representation(updated))
+}
+
+pub async fn update_reminder<R: ReminderRepository, U: SessionRepository>(
+    reminder_repository: Arc<R>,
+    session_repository: LoggedInSessionRepository<U>,
+    Path(reminder_id): Path<String>,
+    ApiJson(body): ApiJson<ReminderSubmission>,
+) -> Response {
+    let result =
+        update_reminder_in_database(reminder_repository, session_repository, reminder_id, body)
+            .await;
+
+    match result {
+        Err(error) => error.into_response(),
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+    }
+}
+
+async fn delete_reminder_from_database<R: ReminderRepository, U: SessionRepository>(
+    reminder_repository: Arc<R>,
+    session_repository: LoggedInSessionRepository<U>,
+    reminder_id: String,
+) -> Result<(), ApiError> {
+    let user_id = session_repository.get_oidc_user_subject().await?;
+
+    reminder_repository.delete(user_id, reminder_id).await?;
+
+    Ok(())
+}
+
+pub async fn delete_reminder<R: ReminderRepository, U: SessionRepository>(
+    reminder_repository: Arc<R>,
+    session_repository: LoggedInSessionRepository<U>,
+    Path(reminder_id): Path<String>,
+) -> Response {
+    let result =
+        delete_reminder_from_database(reminder_repository, session_repository, reminder_id).await;
+
+    match result {
+        Err(error) => error.into_response(),
+        Ok(_) => (StatusCode::NO_CONTENT).into_response(),
+    }
+}
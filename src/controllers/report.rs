@@ -0,0 +1,170 @@
+use std::sync::Arc;
+
+use axum::{
+    http::header,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
+use reqwest::StatusCode;
+use serde::Deserialize;
+
+use crate::{
+    aggregation::time_of_day::split_morning_evening,
+    classification::{
+        blood_pressure_category::{CategoryCounts, classify},
+        target_range::{TargetRange, TimeInRange},
+    },
+    controllers::{
+        api_error::ApiError,
+        extractors::ApiQuery,
+        time_of_day::{
+            DEFAULT_DIVERGENCE_THRESHOLDS, DEFAULT_EVENING_WINDOW, DEFAULT_MORNING_WINDOW,
+            to_window,
+        },
+    },
+    reports::reading_report::{ReadingReport, render_reading_report},
+    repositories::{
+        blood_pressure_readings_repository::BloodPressureReadingRepository,
+        session_repository::{LoggedInSessionRepository, SessionRepository},
+        user_repository::UserRepository,
+    },
+};
+
+#[derive(Deserialize)]
+pub struct GetPdfReportQueryParameters {
+    pub from_inclusive: DateTime<Utc>,
+    pub to_inclusive: DateTime<Utc>,
+}
+
+fn to_report_filename(from: DateTime<Utc>, to: DateTime<Utc>) -> String {
+    format!(
+        "blood-pressure-report-{}-to-{}.pdf",
+        from.format("%Y-%m-%d"),
+        to.format("%Y-%m-%d")
+    )
+}
+
+/**
+ * Gathers everything the report shows about the user's readings over the period. This is also what a share link
+ * shows, so that the doctor sees the same figures whichever way they were sent.
+ */
+pub async fn build_reading_report<T: BloodPressureReadingRepository, V: UserRepository>(
+    reading_repository: &Arc<T>,
+    user_repository: &Arc<V>,
+    user_id: String,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<ReadingReport, ApiError> {
+    let profile = user_repository.get_profile(user_id.clone()).await?;
+    let target_range = TargetRange::from_profile(&profile);
+
+    let summary = reading_repository
+        .summarise(user_id.clone(), from, to)
+        .await?;
+    let readings = reading_repository.list(user_id, from, to, None).await?;
+
+    let mut category_counts = CategoryCounts::default();
+    let mut time_in_range = TimeInRange::default();
+
+    for reading in &readings {
+        category_counts.add(classify(
+            reading.systolic,
+            reading.diastolic,
+            profile.classification_guideline,
+        ));
+        time_in_range.add(target_range.is_within(
+            reading.systolic,
+            reading.diastolic,
+            reading.pulse,
+        ));
+    }
+
+    let morning_evening = split_morning_evening(
+        &readings,
+        profile.timezone,
+        to_window(None, None, DEFAULT_MORNING_WINDOW),
+        to_window(None, None, DEFAULT_EVENING_WINDOW),
+        DEFAULT_DIVERGENCE_THRESHOLDS,
+    );
+
+    Ok(ReadingReport {
+        patient_name: profile
+            .display_name
+            .or(profile.email)
+            .unwrap_or_else(|| "Not given".to_string()),
+        timezone: profile.timezone,
+        from,
+        to,
+        classification_guideline: profile.classification_guideline,
+        target_range,
+        summary,
+        morning_evening,
+        category_counts,
+        time_in_range,
+        readings,
+    })
+}
+
+async fn get_pdf_report_from_database<
+    T: BloodPressureReadingRepository,
+    U: SessionRepository,
+    V: UserRepository,
+>(
+    reading_repository: Arc<T>,
+    session_repository: LoggedInSessionRepository<U>,
+    user_repository: Arc<V>,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<Vec<u8>, ApiError> {
+    let user_id = session_repository.get_oidc_user_subject().await?;
+
+    let report =
+        build_reading_report(&reading_repository, &user_repository, user_id, from, to).await?;
+
+    Ok(render_reading_report(report))
+}
+
+pub async fn get_pdf_report<
+    T: BloodPressureReadingRepository,
+    U: SessionRepository,
+    V: UserRepository,
+>(
+    reading_repository: Arc<T>,
+    session_repository: LoggedInSessionRepository<U>,
+    user_repository: Arc<V>,
+    ApiQuery(query): ApiQuery<GetPdfReportQueryParameters>,
+) -> Response {
+    if query.from_inclusive > query.to_inclusive {
+        return ApiError::bad_request("from_inclusive must not be after to_inclusive")
+            .into_response();
+    }
+
+    let result = get_pdf_report_from_database(
+        reading_repository,
+        session_repository,
+        user_repository,
+        query.from_inclusive,
+        query.to_inclusive,
+    )
+    .await;
+
+    match result {
+        Err(error) => error.into_response(),
+        Ok(pdf) => {
+            let content_disposition = format!(
+                "attachment; filename=\"{}\"",
+                to_report_filename(query.from_inclusive, query.to_inclusive)
+            );
+
+            (
+                StatusCode::OK,
+                [
+                    (header::CONTENT_TYPE, "application/pdf".to_string()),
+                    (header::CONTENT_DISPOSITION, content_disposition),
+                ],
+                pdf,
+            )
+                .into_response()
+        }
+    }
+}
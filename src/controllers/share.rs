@@ -0,0 +1,339 @@
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    extract::Path,
+    http::{HeaderMap, header},
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, TimeDelta, Utc};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    auth::secret_token::{generate_secret_token, hash_secret_token},
+    controllers::{
+        api_error::ApiError,
+        extractors::ApiJson,
+        report::build_reading_report,
+        validation::{FieldError, field_error, to_result},
+    },
+    reports::share_page::{render_share_page, render_unavailable_share_page},
+    repositories::{
+        blood_pressure_readings_repository::BloodPressureReadingRepository,
+        session_repository::{LoggedInSessionRepository, SessionRepository},
+        share_repository::{ShareAccessEntity, ShareLinkEntity, ShareRepository},
+        user_repository::UserRepository,
+    },
+};
+
+const MAX_SHARE_DAYS: i64 = 90;
+
+#[derive(Deserialize)]
+pub struct ShareSubmission {
+    pub from_inclusive: DateTime<Utc>,
+    pub to_inclusive: DateTime<Utc>,
+    pub expires: DateTime<Utc>,
+}
+
+/**
+ * Checks the range of readings being shared, and that the link expires within the allowed time
+ */
+pub fn validate_share_submission(
+    share: &ShareSubmission,
+    now: DateTime<Utc>,
+) -> Result<(), Vec<FieldError>> {
+    let mut errors = vec![];
+
+    if share.from_inclusive > share.to_inclusive {
+        errors.push(field_error(
+            "to_inclusive",
+            "Must not be before from_inclusive".to_string(),
+        ));
+    }
+
+    if share.expires <= now {
+        errors.push(field_error("expires", "Must be in the future".to_string()));
+    } else if share.expires > now + TimeDelta::days(MAX_SHARE_DAYS) {
+        errors.push(field_error(
+            "expires",
+            format!("Must be within {} days", MAX_SHARE_DAYS),
+        ));
+    }
+
+    to_result(errors)
+}
+
+#[derive(Serialize)]
+pub struct ShareResponse {
+    pub id: String,
+    pub from_inclusive: DateTime<Utc>,
+    pub to_inclusive: DateTime<Utc>,
+    pub expires: DateTime<Utc>,
+    pub created: DateTime<Utc>,
+    pub revoked: Option<DateTime<Utc>>,
+    /**
+     * Whether the link still works, i.e. it has neither expired nor been revoked
+     */
+    pub active: bool,
+}
+
+/**
+ * The token is only returned when the link is created, as only its hash is kept
+ */
+#[derive(Serialize)]
+pub struct CreatedShareResponse {
+    #[serde(flatten)]
+    pub share: ShareResponse,
+    pub token: String,
+    /**
+     * The path of the public page for the link, to be appended to the app's address
+     */
+    pub path: String,
+}
+
+#[derive(Serialize)]
+pub struct ShareAccessResponse {
+    pub accessed: DateTime<Utc>,
+    pub user_agent: Option<String>,
+}
+
+fn to_api_representation(entity: ShareLinkEntity) -> ShareResponse {
+    ShareResponse {
+        active: entity.is_active(Utc::now()),
+        id: entity.share_id,
+        from_inclusive: entity.from_inclusive,
+        to_inclusive: entity.to_inclusive,
+        expires: entity.expires,
+        created: entity.created,
+        revoked: entity.revoked,
+    }
+}
+
+async fn get_shares_from_database<S: ShareRepository, U: SessionRepository>(
+    share_repository: Arc<S>,
+    session_repository: LoggedInSessionRepository<U>,
+) -> Result<Vec<ShareResponse>, ApiError> {
+    let user_id = session_repository.get_oidc_user_subject().await?;
+
+    let shares = share_repository.list(user_id).await?;
+
+    Ok(shares.into_iter().map(to_api_representation).collect())
+}
+
+pub async fn get_shares<S: ShareRepository, U: SessionRepository>(
+    share_repository: Arc<S>,
+    session_repository: LoggedInSessionRepository<U>,
+) -> Response {
+    let result = get_shares_from_database(share_repository, session_repository).await;
+
+    match result {
+        Err(error) => error.into_response(),
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+    }
+}
+
+async fn add_share_to_database<S: ShareRepository, U: SessionRepository>(
+    share_repository: Arc<S>,
+    session_repository: LoggedInSessionRepository<U>,
+    share: ShareSubmission,
+) -> Result<CreatedShareResponse, ApiError> {
+    let now = Utc::now();
+
+    validate_share_submission(&share, now)?;
+
+    let user_id = session_repository.get_oidc_user_subject().await?;
+
+    let token = generate_secret_token();
+
+    let entity = ShareLinkEntity {
+        share_id: Uuid::now_v7().to_string(),
+        user_id,
+        token_hash: hash_secret_token(&token),
+        from_inclusive: share.from_inclusive,
+        to_inclusive: share.to_inclusive,
+        expires: share.expires,
+        created: now,
+        revoked: None,
+    };
+
+    share_repository.save(entity.clone()).await?;
+
+    Ok(CreatedShareResponse {
+        share: to_api_representation(entity),
+        path: format!("/share/{}", token),
+        token,
+    })
+}
+
+pub async fn add_share<S: ShareRepository, U: SessionRepository>(
+    share_repository: Arc<S>,
+    session_repository: LoggedInSessionRepository<U>,
+    ApiJson(body): ApiJson<ShareSubmission>,
+) -> Response {
+    let result = add_share_to_database(share_repository, session_repository, body).await;
+
+    match result {
+        Err(error) => error.into_response(),
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+    }
+}
+
+async fn revoke_share_in_database<S: ShareRepository, U: SessionRepository>(
+    share_repository: Arc<S>,
+    session_repository: LoggedInSessionRepository<U>,
+    share_id: String,
+) -> Result<(), ApiError> {
+    let user_id = session_repository.get_oidc_user_subject().await?;
+
+    share_repository
+        .revoke(user_id, share_id, Utc::now())
+        .await?;
+
+    Ok(())
+}
+
+pub async fn revoke_share<S: ShareRepository, U: SessionRepository>(
+    share_repository: Arc<S>,
+    session_repository: LoggedInSessionRepository<U>,
+    Path(share_id): Path<String>,
+) -> Response {
+    let result = revoke_share_in_database(share_repository, session_repository, share_id).await;
+
+    match result {
+        Err(error) => error.into_response(),
+        Ok(_) => (StatusCode::NO_CONTENT).into_response(),
+    }
+}
+
+async fn get_share_accesses_from_database<S: ShareRepository, U: SessionRepository>(
+    share_repository: Arc<S>,
+    session_repository: LoggedInSessionRepository<U>,
+    share_id: String,
+) -> Result<Vec<ShareAccessResponse>, ApiError> {
+    let user_id = session_repository.get_oidc_user_subject().await?;
+
+    // The log is only visible to the owner of the link
+    let share = share_repository
+        .get(user_id, share_id)
+        .await?
+        .ok_or(ApiError::NotFound)?;
+
+    let accesses = share_repository.list_accesses(share.share_id).await?;
+
+    Ok(accesses
+        .into_iter()
+        .map(|access| ShareAccessResponse {
+            accessed: access.accessed,
+            user_agent: access.user_agent,
+        })
+        .collect())
+}
+
+pub async fn get_share_accesses<S: ShareRepository, U: SessionRepository>(
+    share_repository: Arc<S>,
+    session_repository: LoggedInSessionRepository<U>,
+    Path(share_id): Path<String>,
+) -> Response {
+    let result =
+        get_share_accesses_from_database(share_repository, session_repository, share_id).await;
+
+    match result {
+        Err(error) => error.into_response(),
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+    }
+}
+
+/**
+ * Returns the page for the link, or None if the link doesn't exist or no longer works
+ */
+async fn get_shared_page_from_database<
+    S: ShareRepository,
+    T: BloodPressureReadingRepository,
+    V: UserRepository,
+>(
+    share_repository: Arc<S>,
+    reading_repository: Arc<T>,
+    user_repository: Arc<V>,
+    token: String,
+    user_agent: Option<String>,
+) -> Result<Option<String>, ApiError> {
+    let now = Utc::now();
+
+    let share = share_repository
+        .get_by_token_hash(hash_secret_token(&token))
+        .await?;
+
+    let Some(share) = share.filter(|share| share.is_active(now)) else {
+        return Ok(None);
+    };
+
+    // The owner has been promised a record of every view, so the page isn't shown if it can't be recorded
+    share_repository
+        .record_access(ShareAccessEntity {
+            access_id: Uuid::now_v7().to_string(),
+            share_id: share.share_id.clone(),
+            accessed: now,
+            user_agent,
+        })
+        .await?;
+
+    let report = build_reading_report(
+        &reading_repository,
+        &user_repository,
+        share.user_id,
+        share.from_inclusive,
+        share.to_inclusive,
+    )
+    .await?;
+
+    Ok(Some(render_share_page(&report, share.expires)))
+}
+
+/**
+ * The public page for a share link. It is outside /api/ so that it can be viewed without logging in.
+ */
+pub async fn get_shared_page<
+    S: ShareRepository,
+    T: BloodPressureReadingRepository,
+    V: UserRepository,
+>(
+    share_repository: Arc<S>,
+    reading_repository: Arc<T>,
+    user_repository: Arc<V>,
+    Path(token): Path<String>,
+    headers: HeaderMap,
+) -> Response {
+    let user_agent = headers
+        .get(header::USER_AGENT)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+
+    let result = get_shared_page_from_database(
+        share_repository,
+        reading_repository,
+        user_repository,
+        token,
+        user_agent,
+    )
+    .await;
+
+    let (status, html) = match result {
+        Err(error) => return error.into_response(),
+        Ok(Some(html)) => (StatusCode::OK, html),
+        Ok(None) => (StatusCode::NOT_FOUND, render_unavailable_share_page()),
+    };
+
+    // The token is in the URL, so it shouldn't be cached or passed on to other sites
+    (
+        status,
+        [
+            (header::CONTENT_TYPE, "text/html; charset=utf-8"),
+            (header::CACHE_CONTROL, "no-store"),
+            (header::REFERRER_POLICY, "no-referrer"),
+        ],
+        html,
+    )
+        .into_response()
+}
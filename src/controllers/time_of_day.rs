@@ -0,0 +1,155 @@
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, NaiveTime, Utc};
+use chrono_tz::Tz;
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    aggregation::time_of_day::{
+        DivergenceThresholds, MorningEveningSplit, TimeWindow, split_morning_evening,
+    },
+    controllers::{api_error::ApiError, extractors::ApiQuery},
+    repositories::{
+        blood_pressure_readings_repository::BloodPressureReadingRepository,
+        session_repository::{LoggedInSessionRepository, SessionRepository},
+        user_repository::UserRepository,
+    },
+};
+
+pub const DEFAULT_MORNING_WINDOW: (u32, u32) = (4, 12);
+pub const DEFAULT_EVENING_WINDOW: (u32, u32) = (18, 0);
+
+// A morning-evening difference of 15/10 mmHg or more is commonly treated as clinically relevant
+pub const DEFAULT_DIVERGENCE_THRESHOLDS: DivergenceThresholds = DivergenceThresholds {
+    systolic: 15.0,
+    diastolic: 10.0,
+};
+
+#[derive(Deserialize)]
+pub struct GetTimeOfDayQueryParameters {
+    pub from_inclusive: DateTime<Utc>,
+    pub to_inclusive: DateTime<Utc>,
+    /**
+     * An IANA timezone name such as 'Europe/London'. Defaults to the timezone in the user's profile
+     */
+    pub tz: Option<String>,
+    pub morning_start: Option<NaiveTime>,
+    pub morning_end: Option<NaiveTime>,
+    pub evening_start: Option<NaiveTime>,
+    pub evening_end: Option<NaiveTime>,
+    pub systolic_divergence_threshold: Option<f64>,
+    pub diastolic_divergence_threshold: Option<f64>,
+}
+
+#[derive(Serialize)]
+pub struct TimeOfDayResponse {
+    pub timezone: String,
+    pub morning_window: TimeWindow,
+    pub evening_window: TimeWindow,
+    pub thresholds: DivergenceThresholds,
+    #[serde(flatten)]
+    pub split: MorningEveningSplit,
+}
+
+fn hour(hour: u32) -> NaiveTime {
+    NaiveTime::from_hms_opt(hour, 0, 0).expect("hour is within a day")
+}
+
+pub fn to_window(
+    start: Option<NaiveTime>,
+    end: Option<NaiveTime>,
+    (default_start, default_end): (u32, u32),
+) -> TimeWindow {
+    TimeWindow {
+        start: start.unwrap_or_else(|| hour(default_start)),
+        end: end.unwrap_or_else(|| hour(default_end)),
+    }
+}
+
+async fn get_time_of_day_split<
+    T: BloodPressureReadingRepository,
+    U: SessionRepository,
+    V: UserRepository,
+>(
+    reading_repository: Arc<T>,
+    session_repository: LoggedInSessionRepository<U>,
+    user_repository: Arc<V>,
+    query: GetTimeOfDayQueryParameters,
+) -> Result<TimeOfDayResponse, ApiError> {
+    let user_id = session_repository.get_oidc_user_subject().await?;
+
+    let timezone: Tz = match &query.tz {
+        None => user_repository.get_profile(user_id.clone()).await?.timezone,
+        Some(name) => name
+            .parse()
+            .map_err(|_| ApiError::bad_request("tz must be an IANA timezone name"))?,
+    };
+
+    let morning_window = to_window(
+        query.morning_start,
+        query.morning_end,
+        DEFAULT_MORNING_WINDOW,
+    );
+    let evening_window = to_window(
+        query.evening_start,
+        query.evening_end,
+        DEFAULT_EVENING_WINDOW,
+    );
+
+    let thresholds = DivergenceThresholds {
+        systolic: query
+            .systolic_divergence_threshold
+            .unwrap_or(DEFAULT_DIVERGENCE_THRESHOLDS.systolic),
+        diastolic: query
+            .diastolic_divergence_threshold
+            .unwrap_or(DEFAULT_DIVERGENCE_THRESHOLDS.diastolic),
+    };
+
+    let readings = reading_repository
+        .list(user_id, query.from_inclusive, query.to_inclusive, None)
+        .await?;
+
+    let split = split_morning_evening(
+        &readings,
+        timezone,
+        morning_window,
+        evening_window,
+        thresholds,
+    );
+
+    Ok(TimeOfDayResponse {
+        timezone: timezone.name().to_string(),
+        morning_window,
+        evening_window,
+        thresholds,
+        split,
+    })
+}
+
+pub async fn get_time_of_day<
+    T: BloodPressureReadingRepository,
+    U: SessionRepository,
+    V: UserRepository,
+>(
+    reading_repository: Arc<T>,
+    session_repository: LoggedInSessionRepository<U>,
+    user_repository: Arc<V>,
+    ApiQuery(query): ApiQuery<GetTimeOfDayQueryParameters>,
+) -> Response {
+    if query.from_inclusive > query.to_inclusive {
+        return ApiError::bad_request("from_inclusive must not be after to_inclusive")
+            .into_response();
+    }
+
+    let result = get_time_of_day_split(reading_repository, session_repository, user_repository, query).await;
+
+    match result {
+        Err(error) => error.into_response(),
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+    }
+}
@@ -0,0 +1,223 @@
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    response::{IntoResponse, Response},
+};
+use chrono_tz::Tz;
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    classification::blood_pressure_category::ClassificationGuideline,
+    controllers::{
+        api_error::ApiError,
+        extractors::ApiJson,
+        validation::{
+            DIASTOLIC_RANGE, FieldError, PULSE_RANGE, SYSTOLIC_RANGE, check_decimal_range,
+            check_range, field_error, to_result,
+        },
+    },
+    repositories::{
+        session_repository::{LoggedInSessionRepository, SessionRepository},
+        user_repository::{UserProfileEntity, UserRepository},
+    },
+    units::weight::WeightUnit,
+};
+
+const HEIGHT_CENTIMETRES_RANGE: (f64, f64) = (40.0, 275.0);
+const MAX_DISPLAY_NAME_LENGTH: usize = 100;
+
+#[derive(Serialize)]
+pub struct UserProfileResponse {
+    pub display_name: Option<String>,
+    pub email: Option<String>,
+    pub timezone: String,
+    pub classification_guideline: ClassificationGuideline,
+    pub height_centimetres: Option<f64>,
+    pub weight_unit: WeightUnit,
+    pub target_systolic: i32,
+    pub target_diastolic: i32,
+    pub target_pulse_min: Option<i32>,
+    pub target_pulse_max: Option<i32>,
+}
+
+/**
+ * The fields of the profile the user can change. The email always comes from the identity provider.
+ */
+#[derive(Deserialize)]
+pub struct UserProfileSubmission {
+    pub display_name: Option<String>,
+    pub timezone: String,
+    pub classification_guideline: ClassificationGuideline,
+    pub height_centimetres: Option<f64>,
+    #[serde(default)]
+    pub weight_unit: WeightUnit,
+    pub target_systolic: i32,
+    pub target_diastolic: i32,
+    pub target_pulse_min: Option<i32>,
+    pub target_pulse_max: Option<i32>,
+}
+
+/**
+ * Checks the profile fields the user can edit. The targets use the same ranges as readings since they are compared
+ * against them.
+ */
+pub fn validate_profile_submission(profile: &UserProfileSubmission) -> Result<(), Vec<FieldError>> {
+    let mut errors = vec![];
+
+    if let Some(display_name) = &profile.display_name
+        && display_name.chars().count() > MAX_DISPLAY_NAME_LENGTH
+    {
+        errors.push(field_error(
+            "display_name",
+            format!("Must be at most {} characters", MAX_DISPLAY_NAME_LENGTH),
+        ));
+    }
+
+    if profile.timezone.parse::<Tz>().is_err() {
+        errors.push(field_error(
+            "timezone",
+            "Must be an IANA timezone name".to_string(),
+        ));
+    }
+
+    if let Some(height_centimetres) = profile.height_centimetres {
+        check_decimal_range(
+            &mut errors,
+            "height_centimetres",
+            height_centimetres,
+            HEIGHT_CENTIMETRES_RANGE,
+        );
+    }
+
+    check_range(
+        &mut errors,
+        "target_systolic",
+        profile.target_systolic,
+        SYSTOLIC_RANGE,
+    );
+    check_range(
+        &mut errors,
+        "target_diastolic",
+        profile.target_diastolic,
+        DIASTOLIC_RANGE,
+    );
+
+    if profile.target_systolic <= profile.target_diastolic {
+        errors.push(field_error(
+            "target_systolic",
+            "Must be greater than target_diastolic".to_string(),
+        ));
+    }
+
+    if let Some(target_pulse_min) = profile.target_pulse_min {
+        check_range(&mut errors, "target_pulse_min", target_pulse_min, PULSE_RANGE);
+    }
+
+    if let Some(target_pulse_max) = profile.target_pulse_max {
+        check_range(&mut errors, "target_pulse_max", target_pulse_max, PULSE_RANGE);
+    }
+
+    if let (Some(min), Some(max)) = (profile.target_pulse_min, profile.target_pulse_max)
+        && min > max
+    {
+        errors.push(field_error(
+            "target_pulse_min",
+            "Must not be greater than target_pulse_max".to_string(),
+        ));
+    }
+
+    to_result(errors)
+}
+
+fn to_api_representation(entity: UserProfileEntity) -> UserProfileResponse {
+    UserProfileResponse {
+        display_name: entity.display_name,
+        email: entity.email,
+        timezone: entity.timezone.name().to_string(),
+        classification_guideline: entity.classification_guideline,
+        height_centimetres: entity.height_centimetres,
+        weight_unit: entity.weight_unit,
+        target_systolic: entity.target_systolic,
+        target_diastolic: entity.target_diastolic,
+        target_pulse_min: entity.target_pulse_min,
+        target_pulse_max: entity.target_pulse_max,
+    }
+}
+
+async fn get_user_info_from_database<U: SessionRepository, V: UserRepository>(
+    session_repository: LoggedInSessionRepository<U>,
+    user_repository: Arc<V>,
+) -> Result<UserProfileResponse, ApiError> {
+    let user_id = session_repository.get_oidc_user_subject().await?;
+
+    let profile = user_repository.get_profile(user_id).await?;
+
+    Ok(to_api_representation(profile))
+}
+
+pub async fn get_user_info<U: SessionRepository, V: UserRepository>(
+    session_repository: LoggedInSessionRepository<U>,
+    user_repository: Arc<V>,
+) -> Response {
+    let result = get_user_info_from_database(session_repository, user_repository).await;
+
+    match result {
+        Err(error) => error.into_response(),
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+    }
+}
+
+async fn save_user_info_to_database<U: SessionRepository, V: UserRepository>(
+    session_repository: LoggedInSessionRepository<U>,
+    user_repository: Arc<V>,
+    profile: UserProfileSubmission,
+) -> Result<UserProfileResponse, ApiError> {
+    validate_profile_submission(&profile)?;
+
+    let timezone = profile
+        .timezone
+        .parse()
+        .map_err(|_| ApiError::bad_request("timezone must be an IANA timezone name"))?;
+
+    let user_id = session_repository.get_oidc_user_subject().await?;
+
+    let existing = user_repository.get_profile(user_id.clone()).await?;
+
+    let entity = UserProfileEntity {
+        user_id,
+        display_name: profile
+            .display_name
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty()),
+        email: existing.email,
+        timezone,
+        classification_guideline: profile.classification_guideline,
+        height_centimetres: profile.height_centimetres,
+        weight_unit: profile.weight_unit,
+        target_systolic: profile.target_systolic,
+        target_diastolic: profile.target_diastolic,
+        target_pulse_min: profile.target_pulse_min,
+        target_pulse_max: profile.target_pulse_max,
+    };
+
+    user_repository.save_profile(entity).await?;
+
+    let saved = user_repository.get_profile(existing.user_id).await?;
+
+    Ok(to_api_representation(saved))
+}
+
+pub async fn save_user_info<U: SessionRepository, V: UserRepository>(
+    session_repository: LoggedInSessionRepository<U>,
+    user_repository: Arc<V>,
+    ApiJson(body): ApiJson<UserProfileSubmission>,
+) -> Response {
+    let result = save_user_info_to_database(session_repository, user_repository, body).await;
+
+    match result {
+        Err(error) => error.into_response(),
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+    }
+}
@@ -0,0 +1,195 @@
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::Serialize;
+
+use crate::units::weight::{POUNDS_PER_STONE, Weight};
+
+pub const SYSTOLIC_RANGE: (i32, i32) = (50, 300);
+pub const DIASTOLIC_RANGE: (i32, i32) = (30, 200);
+pub const PULSE_RANGE: (i32, i32) = (25, 250);
+const WEIGHT_KILOGRAMS_RANGE: (f64, f64) = (2.0, 500.0);
+
+// Device clocks drift a little from the server's, so a reading taken 'now' may arrive slightly in the future
+const FUTURE_TOLERANCE_MINUTES: i64 = 5;
+
+#[derive(Debug, Serialize)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+pub fn field_error(field: &str, message: String) -> FieldError {
+    FieldError {
+        field: field.to_string(),
+        message,
+    }
+}
+
+pub fn check_range(errors: &mut Vec<FieldError>, field: &str, value: i32, (min, max): (i32, i32)) {
+    if value < min || value > max {
+        errors.push(field_error(
+            field,
+            format!("Must be between {} and {}", min, max),
+        ));
+    }
+}
+
+pub fn check_decimal_range(
+    errors: &mut Vec<FieldError>,
+    field: &str,
+    value: f64,
+    (min, max): (f64, f64),
+) {
+    if !(min..=max).contains(&value) {
+        errors.push(field_error(
+            field,
+            format!("Must be between {} and {}", min, max),
+        ));
+    }
+}
+
+/**
+ * Checks the parts of a weight given in another unit, which can be wrong even when the total is plausible, e.g. 10 st
+ * -5 lb
+ */
+fn weight_part_error(weight: Weight) -> Option<FieldError> {
+    match weight {
+        Weight::Kilograms { kilograms } if kilograms < 0.0 => Some(field_error(
+            "weight.kilograms",
+            "Must not be negative".to_string(),
+        )),
+        Weight::Pounds { pounds } if pounds < 0.0 => Some(field_error(
+            "weight.pounds",
+            "Must not be negative".to_string(),
+        )),
+        Weight::StonesAndPounds { pounds, .. } if !(0.0..POUNDS_PER_STONE).contains(&pounds) => {
+            Some(field_error(
+                "weight.pounds",
+                format!(
+                    "Must be at least 0 and less than {} when given with stones",
+                    POUNDS_PER_STONE
+                ),
+            ))
+        }
+        _ => None,
+    }
+}
+
+/**
+ * Weights can be given either in kilograms or in another unit, but not both. Errors are reported against whichever
+ * field was used.
+ */
+pub fn check_weight(
+    errors: &mut Vec<FieldError>,
+    weight_kilograms: Option<f64>,
+    weight: Option<Weight>,
+) {
+    match (weight_kilograms, weight) {
+        (Some(_), Some(_)) => errors.push(field_error(
+            "weight",
+            "Only one of weight and weight_kilograms may be given".to_string(),
+        )),
+        (Some(weight_kilograms), None) => check_decimal_range(
+            errors,
+            "weight_kilograms",
+            weight_kilograms,
+            WEIGHT_KILOGRAMS_RANGE,
+        ),
+        (None, Some(weight)) => {
+            let (min, max) = WEIGHT_KILOGRAMS_RANGE;
+
+            if let Some(error) = weight_part_error(weight) {
+                errors.push(error);
+            } else if !(min..=max).contains(&weight.to_kilograms()) {
+                errors.push(field_error(
+                    "weight",
+                    format!("Must be between {} and {} kilograms", min, max),
+                ));
+            }
+        }
+        (None, None) => {}
+    }
+}
+
+pub fn check_taken(errors: &mut Vec<FieldError>, taken: DateTime<Utc>, now: DateTime<Utc>) {
+    if taken > now + TimeDelta::minutes(FUTURE_TOLERANCE_MINUTES) {
+        errors.push(field_error("taken", "Must not be in the future".to_string()));
+    }
+}
+
+pub fn to_result(errors: Vec<FieldError>) -> Result<(), Vec<FieldError>> {
+    match errors.is_empty() {
+        true => Ok(()),
+        false => Err(errors),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn weight_errors(weight: Weight) -> Vec<String> {
+        let mut errors = vec![];
+        check_weight(&mut errors, None, Some(weight));
+
+        errors.into_iter().map(|error| error.field).collect()
+    }
+
+    #[test]
+    fn stones_and_pounds_within_a_stone_is_accepted() {
+        assert!(
+            weight_errors(Weight::StonesAndPounds {
+                stones: 11,
+                pounds: 0.0
+            })
+            .is_empty()
+        );
+        assert!(
+            weight_errors(Weight::StonesAndPounds {
+                stones: 11,
+                pounds: 13.9
+            })
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn stones_and_pounds_outside_a_stone_is_rejected() {
+        assert_eq!(
+            weight_errors(Weight::StonesAndPounds {
+                stones: 10,
+                pounds: 14.0
+            }),
+            ["weight.pounds"]
+        );
+        assert_eq!(
+            weight_errors(Weight::StonesAndPounds {
+                stones: 12,
+                pounds: -5.0
+            }),
+            ["weight.pounds"]
+        );
+    }
+
+    #[test]
+    fn negative_weight_is_rejected_against_its_part() {
+        assert_eq!(
+            weight_errors(Weight::Pounds { pounds: -150.0 }),
+            ["weight.pounds"]
+        );
+        assert_eq!(
+            weight_errors(Weight::Kilograms { kilograms: -70.0 }),
+            ["weight.kilograms"]
+        );
+    }
+
+    #[test]
+    fn implausible_total_is_rejected_against_the_weight() {
+        assert_eq!(
+            weight_errors(Weight::StonesAndPounds {
+                stones: 0,
+                pounds: 1.0
+            }),
+            ["weight"]
+        );
+    }
+}
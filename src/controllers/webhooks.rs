@@ -0,0 +1,202 @@
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    extract::Path,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
+use reqwest::{StatusCode, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    controllers::{
+        api_error::ApiError,
+        extractors::ApiJson,
+        validation::{FieldError, field_error, to_result},
+    },
+    repositories::{
+        session_repository::{LoggedInSessionRepository, SessionRepository},
+        webhook_repository::{WebhookEntity, WebhookEvent, WebhookEventEntity, WebhookRepository},
+    },
+};
+
+const MAX_WEBHOOKS_PER_USER: usize = 10;
+
+#[derive(Deserialize)]
+pub struct WebhookSubmission {
+    pub url: String,
+}
+
+pub fn validate_webhook_submission(webhook: &WebhookSubmission) -> Result<(), Vec<FieldError>> {
+    let mut errors = vec![];
+
+    let valid = Url::parse(&webhook.url)
+        .map(|url| matches!(url.scheme(), "http" | "https") && url.has_host())
+        .unwrap_or(false);
+
+    if !valid {
+        errors.push(field_error(
+            "url",
+            "Must be an http or https URL".to_string(),
+        ));
+    }
+
+    to_result(errors)
+}
+
+#[derive(Serialize)]
+pub struct WebhookResponse {
+    pub id: String,
+    pub url: String,
+    pub created: DateTime<Utc>,
+}
+
+/**
+ * The secret is only returned when the webhook is created
+ */
+#[derive(Serialize)]
+pub struct CreatedWebhookResponse {
+    #[serde(flatten)]
+    pub webhook: WebhookResponse,
+    pub secret: String,
+}
+
+#[derive(Serialize)]
+struct WebhookPayload<'a, P: Serialize> {
+    event: &'static str,
+    created: DateTime<Utc>,
+    data: &'a P,
+}
+
+fn to_api_representation(entity: WebhookEntity) -> WebhookResponse {
+    WebhookResponse {
+        id: entity.webhook_id,
+        url: entity.url,
+        created: entity.created,
+    }
+}
+
+fn generate_secret() -> String {
+    hex::encode(rand::random::<[u8; 32]>())
+}
+
+/**
+ * Builds the outbox entry for an event, to be saved along with the change that caused it. An event whose data can't
+ * be serialised is logged and left out rather than failing the change.
+ */
+pub fn to_webhook_event<P: Serialize>(event: WebhookEvent, data: &P) -> Option<WebhookEventEntity> {
+    let payload = WebhookPayload {
+        event: event.name(),
+        created: Utc::now(),
+        data,
+    };
+
+    match serde_json::to_string(&payload) {
+        Ok(payload) => Some(WebhookEventEntity { event, payload }),
+        Err(error) => {
+            println!(
+                "Could not serialise {} webhook payload: {}",
+                event.name(),
+                error
+            );
+            None
+        }
+    }
+}
+
+async fn get_webhooks_from_database<W: WebhookRepository, U: SessionRepository>(
+    webhook_repository: Arc<W>,
+    session_repository: LoggedInSessionRepository<U>,
+) -> Result<Vec<WebhookResponse>, ApiError> {
+    let user_id = session_repository.get_oidc_user_subject().await?;
+
+    let webhooks = webhook_repository.list(user_id).await?;
+
+    Ok(webhooks.into_iter().map(to_api_representation).collect())
+}
+
+pub async fn get_webhooks<W: WebhookRepository, U: SessionRepository>(
+    webhook_repository: Arc<W>,
+    session_repository: LoggedInSessionRepository<U>,
+) -> Response {
+    let result = get_webhooks_from_database(webhook_repository, session_repository).await;
+
+    match result {
+        Err(error) => error.into_response(),
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+    }
+}
+
+async fn add_webhook_to_database<W: WebhookRepository, U: SessionRepository>(
+    webhook_repository: Arc<W>,
+    session_repository: LoggedInSessionRepository<U>,
+    webhook: WebhookSubmission,
+) -> Result<CreatedWebhookResponse, ApiError> {
+    validate_webhook_submission(&webhook)?;
+
+    let user_id = session_repository.get_oidc_user_subject().await?;
+
+    let existing = webhook_repository.list(user_id.clone()).await?;
+    if existing.len() >= MAX_WEBHOOKS_PER_USER {
+        return Err(ApiError::bad_request(&format!(
+            "No more than {} webhooks can be registered",
+            MAX_WEBHOOKS_PER_USER
+        )));
+    }
+
+    let entity = WebhookEntity {
+        webhook_id: Uuid::now_v7().to_string(),
+        user_id,
+        url: webhook.url,
+        secret: generate_secret(),
+        created: Utc::now(),
+    };
+
+    webhook_repository.save(entity.clone()).await?;
+
+    Ok(CreatedWebhookResponse {
+        secret: entity.secret.clone(),
+        webhook: to_api_representation(entity),
+    })
+}
+
+pub async fn add_webhook<W: WebhookRepository, U: SessionRepository>(
+    webhook_repository: Arc<W>,
+    session_repository: LoggedInSessionRepository<U>,
+    ApiJson(body): ApiJson<WebhookSubmission>,
+) -> Response {
+    let result = add_webhook_to_database(webhook_repository, session_repository, body).await;
+
+    match result {
+        Err(error) => error.into_response(),
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+    }
+}
+
+async fn delete_webhook_from_database<W: WebhookRepository, U: SessionRepository>(
+    webhook_repository: Arc<W>,
+    session_repository: LoggedInSessionRepository<U>,
+    webhook_id: String,
+) -> Result<(), ApiError> {
+    let user_id = session_repository.get_oidc_user_subject().await?;
+
+    webhook_repository.delete(user_id, webhook_id).await?;
+
+    Ok(())
+}
+
+pub async fn delete_webhook<W: WebhookRepository, U: SessionRepository>(
+    webhook_repository: Arc<W>,
+    session_repository: LoggedInSessionRepository<U>,
+    Path(webhook_id): Path<String>,
+) -> Response {
+    let result =
+        delete_webhook_from_database(webhook_repository, session_repository, webhook_id).await;
+
+    match result {
+        Err(error) => error.into_response(),
+        Ok(_) => (StatusCode::NO_CONTENT).into_response(),
+    }
+}
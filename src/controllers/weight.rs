@@ -1,59 +1,269 @@
 use std::sync::Arc;
 
-use axum::{Json, response::{IntoResponse, Response}};
+use axum::{
+    Json,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
 use reqwest::StatusCode;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
-use crate::repositories::{
-    blood_pressure_readings_repository::{BloodPressureReadingRepository, RetrieveError},
-    session_repository::{LoggedInSessionError, LoggedInSessionRepository, SessionRepository},
+use crate::{
+    classification::body_mass_index::body_mass_index,
+    controllers::{
+        api_error::ApiError,
+        extractors::{ApiJson, ApiQuery},
+        validation::{FieldError, check_taken, check_weight, field_error, to_result},
+    },
+    repositories::{
+        session_repository::{LoggedInSessionRepository, SessionRepository},
+        user_repository::UserRepository,
+        weight_repository::{WeightMeasurementEntity, WeightRepository},
+    },
+    units::weight::{Weight, WeightUnit},
 };
 
 #[derive(Serialize)]
 struct LatestWeightResponse {
     pub weight_kilograms: Option<f64>,
+    /**
+     * The weight in the user's preferred unit
+     */
+    pub weight: Option<Weight>,
+    /**
+     * None if there is no weight or the user hasn't set their height
+     */
+    pub bmi: Option<f64>,
 }
 
-enum LatestWeightError {
-    SessionError(LoggedInSessionError),
-    DatebaseError(RetrieveError)
+#[derive(Deserialize)]
+pub struct WeightSubmission {
+    pub weight_kilograms: Option<f64>,
+    /**
+     * An alternative to weight_kilograms for giving the weight in pounds or stones and pounds
+     */
+    pub weight: Option<Weight>,
+    pub taken: DateTime<Utc>,
 }
 
-impl From<LoggedInSessionError> for LatestWeightError {
-    fn from(value: LoggedInSessionError) -> Self {
-        LatestWeightError::SessionError(value)
+impl WeightSubmission {
+    pub fn weight_in_kilograms(&self) -> Option<f64> {
+        self.weight
+            .map(|weight| weight.to_kilograms())
+            .or(self.weight_kilograms)
     }
 }
 
-impl From<RetrieveError> for LatestWeightError {
-    fn from(value: RetrieveError) -> Self {
-        LatestWeightError::DatebaseError(value)
+/**
+ * Checks a weight recorded on its own against the same limits as a weight recorded with a reading
+ */
+pub fn validate_weight_submission(
+    weight: &WeightSubmission,
+    now: DateTime<Utc>,
+) -> Result<(), Vec<FieldError>> {
+    let mut errors = vec![];
+
+    if weight.weight_kilograms.is_none() && weight.weight.is_none() {
+        errors.push(field_error(
+            "weight_kilograms",
+            "Either weight or weight_kilograms must be given".to_string(),
+        ));
+    }
+
+    check_weight(&mut errors, weight.weight_kilograms, weight.weight);
+    check_taken(&mut errors, weight.taken, now);
+
+    to_result(errors)
+}
+
+#[derive(Serialize)]
+pub struct WeightMeasurementResponse {
+    pub id: String,
+    pub weight_kilograms: f64,
+    /**
+     * The weight in the user's preferred unit
+     */
+    pub weight: Weight,
+    pub bmi: Option<f64>,
+    pub taken: DateTime<Utc>,
+    /**
+     * The blood pressure reading the weight was recorded with, if any
+     */
+    pub reading_id: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct WeightHistoryResponse {
+    pub height_centimetres: Option<f64>,
+    pub measurements: Vec<WeightMeasurementResponse>,
+}
+
+#[derive(Deserialize)]
+pub struct GetWeightHistoryQueryParameters {
+    pub from_inclusive: DateTime<Utc>,
+    pub to_inclusive: DateTime<Utc>,
+}
+
+fn to_api_representation(
+    entity: WeightMeasurementEntity,
+    height_centimetres: Option<f64>,
+    weight_unit: WeightUnit,
+) -> WeightMeasurementResponse {
+    WeightMeasurementResponse {
+        bmi: height_centimetres.map(|height| body_mass_index(entity.weight_kilograms, height)),
+        weight: Weight::from_kilograms(entity.weight_kilograms, weight_unit),
+        id: entity.weight_measurement_id,
+        weight_kilograms: entity.weight_kilograms,
+        taken: entity.taken,
+        reading_id: entity.reading_id,
+    }
+}
+
+async fn get_latest_weight_from_database<
+    T: WeightRepository,
+    U: SessionRepository,
+    V: UserRepository,
+>(
+    weight_repository: Arc<T>,
+    session_repository: LoggedInSessionRepository<U>,
+    user_repository: Arc<V>,
+) -> Result<LatestWeightResponse, ApiError> {
+    let user_id = session_repository.get_oidc_user_subject().await?;
+    let profile = user_repository.get_profile(user_id.clone()).await?;
+
+    let weight_kilograms = weight_repository
+        .get_latest(user_id)
+        .await?
+        .map(|entity| entity.weight_kilograms);
+
+    let bmi = match (weight_kilograms, profile.height_centimetres) {
+        (Some(weight), Some(height)) => Some(body_mass_index(weight, height)),
+        _ => None,
+    };
+
+    Ok(LatestWeightResponse {
+        weight_kilograms,
+        weight: weight_kilograms
+            .map(|kilograms| Weight::from_kilograms(kilograms, profile.weight_unit)),
+        bmi,
+    })
+}
+
+pub async fn get_latest_weight<T: WeightRepository, U: SessionRepository, V: UserRepository>(
+    weight_repository: Arc<T>,
+    session_repository: LoggedInSessionRepository<U>,
+    user_repository: Arc<V>,
+) -> Response {
+    let result =
+        get_latest_weight_from_database(weight_repository, session_repository, user_repository)
+            .await;
+
+    match result {
+        Err(error) => error.into_response(),
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
     }
 }
 
-async fn get_latest_weight_from_database<T: BloodPressureReadingRepository, U: SessionRepository>(
-    reading_repository: Arc<T>,
+async fn get_weight_history_from_database<
+    T: WeightRepository,
+    U: SessionRepository,
+    V: UserRepository,
+>(
+    weight_repository: Arc<T>,
     session_repository: LoggedInSessionRepository<U>,
-) -> Result<LatestWeightResponse, LatestWeightError> {
+    user_repository: Arc<V>,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<WeightHistoryResponse, ApiError> {
     let user_id = session_repository.get_oidc_user_subject().await?;
-    let result = reading_repository.get_latest_weight(user_id).await?;
+    let profile = user_repository.get_profile(user_id.clone()).await?;
+
+    let measurements = weight_repository
+        .list(user_id, from, to)
+        .await?
+        .into_iter()
+        .map(|entity| {
+            to_api_representation(entity, profile.height_centimetres, profile.weight_unit)
+        })
+        .collect();
 
-    Ok(LatestWeightResponse { weight_kilograms: result })
+    Ok(WeightHistoryResponse {
+        height_centimetres: profile.height_centimetres,
+        measurements,
+    })
 }
 
-pub async fn get_latest_weight<T: BloodPressureReadingRepository, U: SessionRepository>(
-    reading_repository: Arc<T>,
+pub async fn get_weight_history<T: WeightRepository, U: SessionRepository, V: UserRepository>(
+    weight_repository: Arc<T>,
     session_repository: LoggedInSessionRepository<U>,
+    user_repository: Arc<V>,
+    ApiQuery(query): ApiQuery<GetWeightHistoryQueryParameters>,
 ) -> Response {
+    if query.from_inclusive > query.to_inclusive {
+        return ApiError::bad_request("from_inclusive must not be after to_inclusive")
+            .into_response();
+    }
 
-    let result = get_latest_weight_from_database(
-        reading_repository,
+    let result = get_weight_history_from_database(
+        weight_repository,
         session_repository,
+        user_repository,
+        query.from_inclusive,
+        query.to_inclusive,
     )
     .await;
 
     match result {
-        Err(_) => (StatusCode::INTERNAL_SERVER_ERROR).into_response(),
+        Err(error) => error.into_response(),
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+    }
+}
+
+async fn add_weight_to_database<T: WeightRepository, U: SessionRepository, V: UserRepository>(
+    weight_repository: Arc<T>,
+    session_repository: LoggedInSessionRepository<U>,
+    user_repository: Arc<V>,
+    weight: WeightSubmission,
+) -> Result<WeightMeasurementResponse, ApiError> {
+    validate_weight_submission(&weight, Utc::now())?;
+
+    let weight_kilograms = weight
+        .weight_in_kilograms()
+        .ok_or_else(|| ApiError::bad_request("A weight must be given"))?;
+
+    let user_id = session_repository.get_oidc_user_subject().await?;
+    let profile = user_repository.get_profile(user_id.clone()).await?;
+
+    let entity = WeightMeasurementEntity {
+        weight_measurement_id: Uuid::now_v7().to_string(),
+        user_id,
+        weight_kilograms,
+        taken: weight.taken,
+        reading_id: None,
+    };
+
+    weight_repository.save(entity.clone()).await?;
+
+    Ok(to_api_representation(
+        entity,
+        profile.height_centimetres,
+        profile.weight_unit,
+    ))
+}
+
+pub async fn add_weight<T: WeightRepository, U: SessionRepository, V: UserRepository>(
+    weight_repository: Arc<T>,
+    session_repository: LoggedInSessionRepository<U>,
+    user_repository: Arc<V>,
+    ApiJson(body): ApiJson<WeightSubmission>,
+) -> Response {
+    let result =
+        add_weight_to_database(weight_repository, session_repository, user_repository, body)
+            .await;
+
+    match result {
+        Err(error) => error.into_response(),
         Ok(result) => (StatusCode::OK, Json(result)).into_response(),
     }
 }
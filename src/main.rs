@@ -1,32 +1,68 @@
 use std::env;
 use std::sync::Arc;
 
-use axum::response::IntoResponse;
-use axum::{Json, middleware};
-use axum::{Router, routing::get, routing::post};
-use serde::Serialize;
+use axum::middleware;
+use axum::{Router, routing::delete, routing::get, routing::post, routing::put};
 use tower_http::services::{ServeDir, ServeFile};
 use tower_sessions::cookie::time::Duration;
-use tower_sessions::{Expiry, Session, SessionManagerLayer};
+use tower_sessions::{Expiry, SessionManagerLayer};
 
+mod aggregation;
+mod alerts;
 mod auth;
+mod charts;
+mod classification;
 mod controllers;
+mod notifications;
+mod reminders;
+mod reports;
 mod repositories;
+mod units;
+mod webhooks;
 
-use crate::controllers::blood_pressure_reading::{add_reading, get_readings};
+use crate::controllers::alerts::{get_alert_rules, get_alerts, save_alert_rules};
+use crate::controllers::api_tokens::{add_api_token, delete_api_token, get_api_tokens};
+use crate::controllers::blood_pressure_reading::{
+    add_reading, delete_reading, get_readings, get_tags, update_reading,
+};
+use crate::controllers::chart::get_chart_svg;
+use crate::controllers::export::get_reading_csv_export;
+use crate::controllers::import::import_csv;
 use crate::controllers::login::{
     auth_middleware, login_handler, logout_handler, oidc_callback_handler,
 };
+use crate::controllers::measurement_session::get_measurement_sessions;
 use crate::controllers::ocr::run_ocr;
-use crate::controllers::weight::get_latest_weight;
+use crate::controllers::reading_batch::add_reading_batch;
+use crate::controllers::reading_series::get_reading_series;
+use crate::controllers::reading_summary::get_reading_summary;
+use crate::controllers::reminders::{
+    add_reminder, delete_reminder, get_reminders, update_reminder,
+};
+use crate::controllers::report::get_pdf_report;
+use crate::controllers::share::{
+    add_share, get_share_accesses, get_shared_page, get_shares, revoke_share,
+};
+use crate::controllers::time_of_day::get_time_of_day;
+use crate::controllers::user_info::{get_user_info, save_user_info};
+use crate::controllers::webhooks::{add_webhook, delete_webhook, get_webhooks};
+use crate::controllers::weight::{add_weight, get_latest_weight, get_weight_history};
+use crate::notifications::notification_queue::{NotificationQueue, run_notification_worker};
+use crate::notifications::notifier::get_notifier;
+use crate::reminders::scheduler::run_reminder_scheduler;
 use crate::repositories::session_repository::{LoggedInSessionRepository, TowerSessionRepository};
+use crate::repositories::sql_lite::sql_lite_alert_repository::SqlLiteAlertRepository;
+use crate::repositories::sql_lite::sql_lite_api_token_repository::SqlLiteApiTokenRepository;
 use crate::repositories::sql_lite::sql_lite_blood_pressure_reading_repository::SqlLiteBloodPressureReadingRepository;
+use crate::repositories::sql_lite::sql_lite_reminder_repository::SqlLiteReminderRepository;
+use crate::repositories::sql_lite::sql_lite_share_repository::SqlLiteShareRepository;
+use crate::repositories::sql_lite::sql_lite_user_repository::SqlLiteUserRepository;
+use crate::repositories::sql_lite::sql_lite_webhook_repository::SqlLiteWebhookRepository;
+use crate::repositories::sql_lite::sql_lite_weight_repository::SqlLiteWeightRepository;
+use crate::webhooks::delivery::run_webhook_worker;
 use sqlx::sqlite::SqlitePool;
 use tower_sessions_sqlx_store::SqliteStore;
 
-#[derive(Serialize)]
-struct UserInfo {}
-
 #[tokio::main]
 async fn main() {
     let target_assets_directory = env::var("CLIENT_ASSETS_PATH").unwrap_or("client".to_string());
@@ -58,17 +94,63 @@ async fn main() {
         .with_same_site(tower_sessions::cookie::SameSite::Lax)
         .with_expiry(Expiry::OnInactivity(Duration::weeks(2)));
 
+    let notifier = Arc::new(get_notifier().unwrap());
+    let (notification_queue, notification_receiver) = NotificationQueue::new();
+    let notification_queue = Arc::new(notification_queue);
+
     let serve_dir = ServeDir::new(&target_assets_directory).fallback(ServeFile::new(format!(
         "{}/index.html",
         target_assets_directory
     )));
 
     let blood_pressure_reading_repository = Arc::new(
-        SqlLiteBloodPressureReadingRepository::from_pool(sql_lite_pool),
+        SqlLiteBloodPressureReadingRepository::from_pool(sql_lite_pool.clone()),
     );
 
+    let weight_repository = Arc::new(SqlLiteWeightRepository::from_pool(sql_lite_pool.clone()));
+
+    let alert_repository = Arc::new(SqlLiteAlertRepository::from_pool(sql_lite_pool.clone()));
+
+    let reminder_repository = Arc::new(SqlLiteReminderRepository::from_pool(sql_lite_pool.clone()));
+
+    let webhook_repository = Arc::new(SqlLiteWebhookRepository::from_pool(sql_lite_pool.clone()));
+
+    let share_repository = Arc::new(SqlLiteShareRepository::from_pool(sql_lite_pool.clone()));
+
+    let api_token_repository =
+        Arc::new(SqlLiteApiTokenRepository::from_pool(sql_lite_pool.clone()));
+
+    let user_repository = Arc::new(SqlLiteUserRepository::from_pool(sql_lite_pool));
+
+    tokio::spawn(run_reminder_scheduler(
+        Arc::clone(&reminder_repository),
+        Arc::clone(&blood_pressure_reading_repository),
+        Arc::clone(&user_repository),
+        Arc::clone(&notification_queue),
+    ));
+
+    tokio::spawn(run_notification_worker(Arc::clone(&notifier), notification_receiver));
+
+    tokio::spawn(run_webhook_worker(
+        Arc::clone(&webhook_repository),
+        shared_http_client.clone(),
+    ));
+
     let app = Router::new()
-        .route("/api/run-ocr", post(run_ocr))
+        .route(
+            "/api/run-ocr",
+            post({
+                let user_repository = Arc::clone(&user_repository);
+
+                move |session, multipart| {
+                    run_ocr(
+                        LoggedInSessionRepository::new(TowerSessionRepository::new(session)),
+                        user_repository,
+                        multipart,
+                    )
+                }
+            }),
+        )
         .route(
             "/login",
             get({
@@ -82,9 +164,12 @@ async fn main() {
             "/oidc-callback",
             get({
                 let oidc_client = Arc::clone(&shared_oidc_client);
+                let user_repository = Arc::clone(&user_repository);
+
                 move |session, params| {
                     oidc_callback_handler(
                         TowerSessionRepository::new(session),
+                        user_repository,
                         oidc_client,
                         shared_http_client,
                         params,
@@ -94,17 +179,63 @@ async fn main() {
         )
         .route(
             "/api/user-info",
-            get(async |_: Session| Json(UserInfo {}).into_response()),
+            get({
+                let user_repository = Arc::clone(&user_repository);
+
+                move |session| {
+                    get_user_info(
+                        LoggedInSessionRepository::new(TowerSessionRepository::new(session)),
+                        user_repository,
+                    )
+                }
+            })
+            .put({
+                let user_repository = Arc::clone(&user_repository);
+
+                move |session, body| {
+                    save_user_info(
+                        LoggedInSessionRepository::new(TowerSessionRepository::new(session)),
+                        user_repository,
+                        body,
+                    )
+                }
+            }),
         )
         .route(
             "/api/reading",
             post({
                 let repository = Arc::clone(&blood_pressure_reading_repository);
+                let user_repository = Arc::clone(&user_repository);
+                let alert_repository = Arc::clone(&alert_repository);
+                let notification_queue = Arc::clone(&notification_queue);
 
                 move |session, body| {
                     add_reading(
                         repository,
                         LoggedInSessionRepository::new(TowerSessionRepository::new(session)),
+                        user_repository,
+                        alert_repository,
+                        notification_queue,
+                        body,
+                    )
+                }
+            }),
+        )
+        .route(
+            "/api/readings/batch",
+            post({
+                let repository = Arc::clone(&blood_pressure_reading_repository);
+                let user_repository = Arc::clone(&user_repository);
+                let alert_repository = Arc::clone(&alert_repository);
+                let notification_queue = Arc::clone(&notification_queue);
+
+                move |session, body| {
+                    add_reading_batch(
+                        repository,
+                        LoggedInSessionRepository::new(TowerSessionRepository::new(session)),
+                        user_repository,
+                        alert_repository,
+                        notification_queue,
                         body,
                     )
                 }
@@ -114,29 +245,473 @@ async fn main() {
             "/api/reading",
             get({
                 let repository = Arc::clone(&blood_pressure_reading_repository);
+                let user_repository = Arc::clone(&user_repository);
 
                 move |session, params| {
                     get_readings(
                         repository,
                         LoggedInSessionRepository::new(TowerSessionRepository::new(session)),
+                        user_repository,
                         params,
                     )
                 }
             }),
         )
         .route(
-            "/api/weight",
+            "/api/reading/summary",
+            get({
+                let repository = Arc::clone(&blood_pressure_reading_repository);
+                let user_repository = Arc::clone(&user_repository);
+
+                move |session, params| {
+                    get_reading_summary(
+                        repository,
+                        LoggedInSessionRepository::new(TowerSessionRepository::new(session)),
+                        user_repository,
+                        params,
+                    )
+                }
+            }),
+        )
+        .route(
+            "/api/reading/series",
+            get({
+                let repository = Arc::clone(&blood_pressure_reading_repository);
+                let user_repository = Arc::clone(&user_repository);
+
+                move |session, params| {
+                    get_reading_series(
+                        repository,
+                        LoggedInSessionRepository::new(TowerSessionRepository::new(session)),
+                        user_repository,
+                        params,
+                    )
+                }
+            }),
+        )
+        .route(
+            "/api/reading/time-of-day",
+            get({
+                let repository = Arc::clone(&blood_pressure_reading_repository);
+                let user_repository = Arc::clone(&user_repository);
+
+                move |session, params| {
+                    get_time_of_day(
+                        repository,
+                        LoggedInSessionRepository::new(TowerSessionRepository::new(session)),
+                        user_repository,
+                        params,
+                    )
+                }
+            }),
+        )
+        .route(
+            "/api/reading/sessions",
             get({
                 let repository = Arc::clone(&blood_pressure_reading_repository);
+
+                move |session, params| {
+                    get_measurement_sessions(
+                        repository,
+                        LoggedInSessionRepository::new(TowerSessionRepository::new(session)),
+                        params,
+                    )
+                }
+            }),
+        )
+        .route(
+            "/api/reading/{id}",
+            put({
+                let repository = Arc::clone(&blood_pressure_reading_repository);
+                let user_repository = Arc::clone(&user_repository);
+
+                move |session, path, body| {
+                    update_reading(
+                        repository,
+                        LoggedInSessionRepository::new(TowerSessionRepository::new(session)),
+                        user_repository,
+                        path,
+                        body,
+                    )
+                }
+            })
+            .delete({
+                let repository = Arc::clone(&blood_pressure_reading_repository);
+
+                move |session, path| {
+                    delete_reading(
+                        repository,
+                        LoggedInSessionRepository::new(TowerSessionRepository::new(session)),
+                        path,
+                    )
+                }
+            }),
+        )
+        .route(
+            "/api/tags",
+            get({
+                let repository = Arc::clone(&blood_pressure_reading_repository);
+
+                move |session| {
+                    get_tags(
+                        repository,
+                        LoggedInSessionRepository::new(TowerSessionRepository::new(session)),
+                    )
+                }
+            }),
+        )
+        .route(
+            "/api/export/csv",
+            get({
+                let repository = Arc::clone(&blood_pressure_reading_repository);
+                let user_repository = Arc::clone(&user_repository);
+
+                move |session, params| {
+                    get_reading_csv_export(
+                        repository,
+                        LoggedInSessionRepository::new(TowerSessionRepository::new(session)),
+                        user_repository,
+                        params,
+                    )
+                }
+            }),
+        )
+        .route(
+            "/api/report/pdf",
+            get({
+                let repository = Arc::clone(&blood_pressure_reading_repository);
+                let user_repository = Arc::clone(&user_repository);
+
+                move |session, params| {
+                    get_pdf_report(
+                        repository,
+                        LoggedInSessionRepository::new(TowerSessionRepository::new(session)),
+                        user_repository,
+                        params,
+                    )
+                }
+            }),
+        )
+        .route(
+            "/api/chart.svg",
+            get({
+                let repository = Arc::clone(&blood_pressure_reading_repository);
+                let user_repository = Arc::clone(&user_repository);
+
+                move |session, params| {
+                    get_chart_svg(
+                        repository,
+                        LoggedInSessionRepository::new(TowerSessionRepository::new(session)),
+                        user_repository,
+                        params,
+                    )
+                }
+            }),
+        )
+        .route(
+            "/api/import/csv",
+            post({
+                let repository = Arc::clone(&blood_pressure_reading_repository);
+                let user_repository = Arc::clone(&user_repository);
+
+                move |session, params, multipart| {
+                    import_csv(
+                        repository,
+                        LoggedInSessionRepository::new(TowerSessionRepository::new(session)),
+                        user_repository,
+                        params,
+                        multipart,
+                    )
+                }
+            }),
+        )
+        .route(
+            "/api/weight",
+            get({
+                let repository = Arc::clone(&weight_repository);
+                let user_repository = Arc::clone(&user_repository);
+
                 move |session| {
                     get_latest_weight(
                         repository,
-                        LoggedInSessionRepository::new(TowerSessionRepository::new(session))
+                        LoggedInSessionRepository::new(TowerSessionRepository::new(session)),
+                        user_repository,
                     )
                 }
             })
+            .post({
+                let repository = Arc::clone(&weight_repository);
+                let user_repository = Arc::clone(&user_repository);
+
+                move |session, body| {
+                    add_weight(
+                        repository,
+                        LoggedInSessionRepository::new(TowerSessionRepository::new(session)),
+                        user_repository,
+                        body,
+                    )
+                }
+            }),
+        )
+        .route(
+            "/api/weight/history",
+            get({
+                let repository = Arc::clone(&weight_repository);
+                let user_repository = Arc::clone(&user_repository);
+
+                move |session, params| {
+                    get_weight_history(
+                        repository,
+                        LoggedInSessionRepository::new(TowerSessionRepository::new(session)),
+                        user_repository,
+                        params,
+                    )
+                }
+            }),
+        )
+        .route(
+            "/api/alerts",
+            get({
+                let repository = Arc::clone(&alert_repository);
+
+                move |session, params| {
+                    get_alerts(
+                        repository,
+                        LoggedInSessionRepository::new(TowerSessionRepository::new(session)),
+                        params,
+                    )
+                }
+            }),
+        )
+        .route(
+            "/api/alert-rules",
+            get({
+                let repository = Arc::clone(&alert_repository);
+
+                move |session| {
+                    get_alert_rules(
+                        repository,
+                        LoggedInSessionRepository::new(TowerSessionRepository::new(session)),
+                    )
+                }
+            })
+            .put({
+                let repository = Arc::clone(&alert_repository);
+
+                move |session, body| {
+                    save_alert_rules(
+                        repository,
+                        LoggedInSessionRepository::new(TowerSessionRepository::new(session)),
+                        body,
+                    )
+                }
+            }),
+        )
+        .route(
+            "/api/reminders",
+            get({
+                let repository = Arc::clone(&reminder_repository);
+
+                move |session| {
+                    get_reminders(
+                        repository,
+                        LoggedInSessionRepository::new(TowerSessionRepository::new(session)),
+                    )
+                }
+            })
+            .post({
+                let repository = Arc::clone(&reminder_repository);
+                let user_repository = Arc::clone(&user_repository);
+
+                move |session, body| {
+                    add_reminder(
+                        repository,
+                        LoggedInSessionRepository::new(TowerSessionRepository::new(session)),
+                        user_repository,
+                        body,
+                    )
+                }
+            }),
+        )
+        .route(
+            "/api/reminders/{id}",
+            put({
+                let repository = Arc::clone(&reminder_repository);
+
+                move |session, path, body| {
+                    update_reminder(
+                        repository,
+                        LoggedInSessionRepository::new(TowerSessionRepository::new(session)),
+                        path,
+                        body,
+                    )
+                }
+            })
+            .delete({
+                let repository = Arc::clone(&reminder_repository);
+
+                move |session, path| {
+                    delete_reminder(
+                        repository,
+                        LoggedInSessionRepository::new(TowerSessionRepository::new(session)),
+                        path,
+                    )
+                }
+            }),
+        )
+        .route(
+            "/api/webhooks",
+            get({
+                let repository = Arc::clone(&webhook_repository);
+
+                move |session| {
+                    get_webhooks(
+                        repository,
+                        LoggedInSessionRepository::new(TowerSessionRepository::new(session)),
+                    )
+                }
+            })
+            .post({
+                let repository = Arc::clone(&webhook_repository);
+
+                move |session, body| {
+                    add_webhook(
+                        repository,
+                        LoggedInSessionRepository::new(TowerSessionRepository::new(session)),
+                        body,
+                    )
+                }
+            }),
+        )
+        .route(
+            "/api/webhooks/{id}",
+            delete({
+                let repository = Arc::clone(&webhook_repository);
+
+                move |session, path| {
+                    delete_webhook(
+                        repository,
+                        LoggedInSessionRepository::new(TowerSessionRepository::new(session)),
+                        path,
+                    )
+                }
+            }),
+        )
+        .route(
+            "/api/shares",
+            get({
+                let repository = Arc::clone(&share_repository);
+
+                move |session| {
+                    get_shares(
+                        repository,
+                        LoggedInSessionRepository::new(TowerSessionRepository::new(session)),
+                    )
+                }
+            })
+            .post({
+                let repository = Arc::clone(&share_repository);
+
+                move |session, body| {
+                    add_share(
+                        repository,
+                        LoggedInSessionRepository::new(TowerSessionRepository::new(session)),
+                        body,
+                    )
+                }
+            }),
+        )
+        .route(
+            "/api/shares/{id}",
+            delete({
+                let repository = Arc::clone(&share_repository);
+
+                move |session, path| {
+                    revoke_share(
+                        repository,
+                        LoggedInSessionRepository::new(TowerSessionRepository::new(session)),
+                        path,
+                    )
+                }
+            }),
         )
-        .route_layer(middleware::from_fn(auth_middleware))
+        .route(
+            "/api/shares/{id}/accesses",
+            get({
+                let repository = Arc::clone(&share_repository);
+
+                move |session, path| {
+                    get_share_accesses(
+                        repository,
+                        LoggedInSessionRepository::new(TowerSessionRepository::new(session)),
+                        path,
+                    )
+                }
+            }),
+        )
+        .route(
+            "/share/{token}",
+            get({
+                let repository = Arc::clone(&share_repository);
+                let reading_repository = Arc::clone(&blood_pressure_reading_repository);
+                let user_repository = Arc::clone(&user_repository);
+
+                move |path, headers| {
+                    get_shared_page(
+                        repository,
+                        reading_repository,
+                        user_repository,
+                        path,
+                        headers,
+                    )
+                }
+            }),
+        )
+        .route(
+            "/api/tokens",
+            get({
+                let repository = Arc::clone(&api_token_repository);
+
+                move |session| {
+                    get_api_tokens(
+                        repository,
+                        LoggedInSessionRepository::new(TowerSessionRepository::new(session)),
+                    )
+                }
+            })
+            .post({
+                let repository = Arc::clone(&api_token_repository);
+
+                move |session, body| {
+                    add_api_token(
+                        repository,
+                        LoggedInSessionRepository::new(TowerSessionRepository::new(session)),
+                        body,
+                    )
+                }
+            }),
+        )
+        .route(
+            "/api/tokens/{id}",
+            delete({
+                let repository = Arc::clone(&api_token_repository);
+
+                move |session, path| {
+                    delete_api_token(
+                        repository,
+                        LoggedInSessionRepository::new(TowerSessionRepository::new(session)),
+                        path,
+                    )
+                }
+            }),
+        )
+        .route_layer(middleware::from_fn({
+            let repository = Arc::clone(&api_token_repository);
+
+            move |session, request, next| {
+                auth_middleware(Arc::clone(&repository), session, request, next)
+            }
+        }))
         .fallback_service(serve_dir)
         .layer(session_layer);
 
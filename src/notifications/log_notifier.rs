@@ -0,0 +1,57 @@
+use std::env;
+
+use tokio::{fs::OpenOptions, io::AsyncWriteExt};
+
+use crate::notifications::notifier::{Notification, Notifier, NotifyError};
+
+/**
+ * A stand-in for SMTP during development. Notifications are appended to the file in NOTIFICATION_LOG_PATH, or
+ * printed if it isn't set.
+ */
+pub struct LogNotifier {
+    path: Option<String>,
+}
+
+impl LogNotifier {
+    pub fn from_env() -> LogNotifier {
+        LogNotifier {
+            path: env::var("NOTIFICATION_LOG_PATH").ok(),
+        }
+    }
+}
+
+fn format_notification(notification: &Notification) -> String {
+    format!(
+        "To: {}\nSubject: {}\n\n{}\n---\n",
+        notification.to, notification.subject, notification.body
+    )
+}
+
+impl Notifier for LogNotifier {
+    async fn notify(&self, notification: Notification) -> Result<(), NotifyError> {
+        let formatted = format_notification(&notification);
+
+        match &self.path {
+            None => {
+                println!("{}", formatted);
+                Ok(())
+            }
+            Some(path) => {
+                let result = async {
+                    let mut file = OpenOptions::new()
+                        .create(true)
+                        .append(true)
+                        .open(path)
+                        .await?;
+
+                    file.write_all(formatted.as_bytes()).await
+                }
+                .await;
+
+                result.map_err(|error| NotifyError::DeliveryError {
+                    description: error.to_string(),
+                })
+            }
+        }
+    }
+}
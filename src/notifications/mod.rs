@@ -0,0 +1,4 @@
+pub(crate) mod log_notifier;
+pub(crate) mod notification_queue;
+pub(crate) mod notifier;
+pub(crate) mod smtp_notifier;
@@ -0,0 +1,47 @@
+use std::sync::Arc;
+
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+
+use crate::notifications::notifier::{Notification, Notifier};
+
+/**
+ * Notifications pushed onto the queue are sent by the notification worker, so that a slow or unreachable mail server
+ * doesn't hold up the request that raised them
+ */
+pub struct NotificationQueue {
+    sender: UnboundedSender<Notification>,
+}
+
+impl NotificationQueue {
+    /**
+     * Returns the queue along with the receiver that should be given to the notification worker
+     */
+    pub fn new() -> (NotificationQueue, UnboundedReceiver<Notification>) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+
+        (NotificationQueue { sender }, receiver)
+    }
+
+    pub fn push(&self, notification: Notification) {
+        if let Err(error) = self.sender.send(notification) {
+            println!(
+                "Could not queue notification to {}, the notification worker has stopped",
+                error.0.to
+            );
+        }
+    }
+}
+
+pub async fn run_notification_worker<N: Notifier>(
+    notifier: Arc<N>,
+    mut receiver: UnboundedReceiver<Notification>,
+) {
+    while let Some(notification) = receiver.recv().await {
+        let to = notification.to.clone();
+        let subject = notification.subject.clone();
+
+        if let Err(error) = notifier.notify(notification).await {
+            println!("Could not send '{}' to {}: {}", subject, to, error);
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use std::{env, fmt};
+
+use crate::notifications::{log_notifier::LogNotifier, smtp_notifier::SmtpNotifier};
+
+pub struct Notification {
+    /**
+     * The email address of the recipient
+     */
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+#[derive(Debug)]
+pub enum NotifyError {
+    InvalidAddress { description: String },
+    DeliveryError { description: String },
+}
+
+impl fmt::Display for NotifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NotifyError::InvalidAddress { description } => write!(f, "{}", description),
+            NotifyError::DeliveryError { description } => write!(f, "{}", description),
+        }
+    }
+}
+
+pub trait Notifier {
+    async fn notify(&self, notification: Notification) -> Result<(), NotifyError>;
+}
+
+/**
+ * The notifier chosen at startup. SMTP is used when SMTP_HOST is set, otherwise notifications are only logged.
+ */
+pub enum ConfiguredNotifier {
+    Smtp(SmtpNotifier),
+    Log(LogNotifier),
+}
+
+impl Notifier for ConfiguredNotifier {
+    async fn notify(&self, notification: Notification) -> Result<(), NotifyError> {
+        match self {
+            ConfiguredNotifier::Smtp(notifier) => notifier.notify(notification).await,
+            ConfiguredNotifier::Log(notifier) => notifier.notify(notification).await,
+        }
+    }
+}
+
+pub fn get_notifier() -> Result<ConfiguredNotifier, String> {
+    match env::var("SMTP_HOST") {
+        Ok(_) => Ok(ConfiguredNotifier::Smtp(SmtpNotifier::from_env()?)),
+        Err(_) => Ok(ConfiguredNotifier::Log(LogNotifier::from_env())),
+    }
+}
@@ -0,0 +1,102 @@
+use std::{env, time::Duration};
+
+use lettre::{
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+    message::{Mailbox, header::ContentType},
+    transport::smtp::authentication::Credentials,
+};
+
+use crate::notifications::notifier::{Notification, Notifier, NotifyError};
+
+const DEFAULT_SMTP_PORT: u16 = 587;
+const SMTP_TIMEOUT_SECONDS: u64 = 10;
+
+struct SmtpSettings {
+    host: String,
+    port: u16,
+    username: Option<String>,
+    password: Option<String>,
+    from: String,
+}
+
+fn get_smtp_settings() -> Result<SmtpSettings, String> {
+    let host = env::var("SMTP_HOST").map_err(|_| "SMTP_HOST environment variable missing")?;
+    let port = match env::var("SMTP_PORT") {
+        Ok(port) => port
+            .parse()
+            .map_err(|_| "SMTP_PORT environment variable must be a port number")?,
+        Err(_) => DEFAULT_SMTP_PORT,
+    };
+    let from = env::var("SMTP_FROM").map_err(|_| "SMTP_FROM environment variable missing")?;
+
+    Ok(SmtpSettings {
+        host,
+        port,
+        username: env::var("SMTP_USERNAME").ok(),
+        password: env::var("SMTP_PASSWORD").ok(),
+        from,
+    })
+}
+
+/**
+ * Sends notifications as plain text emails through an SMTP relay using STARTTLS
+ */
+pub struct SmtpNotifier {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl SmtpNotifier {
+    pub fn from_env() -> Result<SmtpNotifier, String> {
+        let settings = get_smtp_settings()?;
+
+        let from = settings
+            .from
+            .parse()
+            .map_err(|_| "SMTP_FROM environment variable must be an email address")?;
+
+        let mut builder = AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&settings.host)
+            .map_err(|_| "Could not construct SMTP transport")?
+            .port(settings.port)
+            .timeout(Some(Duration::from_secs(SMTP_TIMEOUT_SECONDS)));
+
+        if let (Some(username), Some(password)) = (settings.username, settings.password) {
+            builder = builder.credentials(Credentials::new(username, password));
+        }
+
+        Ok(SmtpNotifier {
+            transport: builder.build(),
+            from,
+        })
+    }
+}
+
+impl Notifier for SmtpNotifier {
+    async fn notify(&self, notification: Notification) -> Result<(), NotifyError> {
+        let to: Mailbox = notification
+            .to
+            .parse()
+            .map_err(|_| NotifyError::InvalidAddress {
+                description: format!("Could not parse recipient {}", notification.to),
+            })?;
+
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(to)
+            .subject(notification.subject)
+            .header(ContentType::TEXT_PLAIN)
+            .body(notification.body)
+            .map_err(|error| NotifyError::DeliveryError {
+                description: error.to_string(),
+            })?;
+
+        self.transport
+            .send(message)
+            .await
+            .map_err(|error| NotifyError::DeliveryError {
+                description: error.to_string(),
+            })?;
+
+        Ok(())
+    }
+}
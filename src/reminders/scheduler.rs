@@ -0,0 +1,289 @@
+use std::{sync::Arc, time::Duration};
+
+use chrono::{DateTime, NaiveDate, NaiveTime, TimeDelta, TimeZone, Utc};
+use chrono_tz::Tz;
+
+use crate::{
+    notifications::{notification_queue::NotificationQueue, notifier::Notification},
+    repositories::{
+        blood_pressure_readings_repository::BloodPressureReadingRepository,
+        reminder_repository::{ReminderEntity, ReminderRepository},
+        user_repository::UserRepository,
+    },
+};
+
+const CHECK_INTERVAL_SECONDS: u64 = 60;
+
+// Reminders that became due longer ago than this, for example while the server was down, are skipped
+const MAX_LATENESS_MINUTES: i64 = 30;
+
+/**
+ * The most recent time at or before `now` that the reminder was due in the user's timezone. If the time of day
+ * doesn't exist today because of a daylight saving change, yesterday's occurrence is used instead. None if it doesn't
+ * exist on either day.
+ */
+pub fn latest_occurrence(
+    time_of_day: NaiveTime,
+    timezone: Tz,
+    now: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    let occurrence_on = |date: NaiveDate| {
+        timezone
+            .from_local_datetime(&date.and_time(time_of_day))
+            .earliest()
+            .map(|occurrence| occurrence.with_timezone(&Utc))
+    };
+
+    let today = now.with_timezone(&timezone).date_naive();
+
+    match occurrence_on(today) {
+        Some(occurrence) if occurrence <= now => Some(occurrence),
+        _ => occurrence_on(today.pred_opt()?),
+    }
+}
+
+fn is_due(reminder: &ReminderEntity, occurrence: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+    let already_checked = reminder
+        .last_triggered
+        .is_some_and(|last_triggered| last_triggered >= occurrence);
+
+    !already_checked && now - occurrence <= TimeDelta::minutes(MAX_LATENESS_MINUTES)
+}
+
+async fn check_reminder<
+    R: ReminderRepository,
+    T: BloodPressureReadingRepository,
+    V: UserRepository,
+>(
+    reminder_repository: &Arc<R>,
+    reading_repository: &Arc<T>,
+    user_repository: &Arc<V>,
+    notification_queue: &NotificationQueue,
+    reminder: ReminderEntity,
+    now: DateTime<Utc>,
+) -> Result<(), String> {
+    let profile = user_repository
+        .get_profile(reminder.user_id.clone())
+        .await
+        .map_err(|error| format!("{:?}", error))?;
+
+    let Some(occurrence) = latest_occurrence(reminder.time_of_day, profile.timezone, now) else {
+        return Ok(());
+    };
+
+    if !is_due(&reminder, occurrence, now) {
+        return Ok(());
+    }
+
+    let window_start = occurrence - TimeDelta::minutes(reminder.window_minutes.into());
+
+    let readings = reading_repository
+        .list(reminder.user_id.clone(), window_start, now, None)
+        .await
+        .map_err(|error| format!("{:?}", error))?;
+
+    // Marked whether or not an email is needed, so that the occurrence isn't checked again every minute
+    reminder_repository
+        .mark_triggered(reminder.reminder_id.clone(), occurrence)
+        .await
+        .map_err(|error| format!("{:?}", error))?;
+
+    if !readings.is_empty() {
+        return Ok(());
+    }
+
+    let Some(email) = profile.email else {
+        return Ok(());
+    };
+
+    let notification = Notification {
+        to: email,
+        subject: "Time to measure your blood pressure".to_string(),
+        body: format!(
+            "You haven't recorded a blood pressure reading since {}. Take a reading now to keep your history up to date.",
+            window_start
+                .with_timezone(&profile.timezone)
+                .format("%H:%M on %e %B")
+        ),
+    };
+
+    notification_queue.push(notification);
+
+    Ok(())
+}
+
+async fn check_reminders<
+    R: ReminderRepository,
+    T: BloodPressureReadingRepository,
+    V: UserRepository,
+>(
+    reminder_repository: &Arc<R>,
+    reading_repository: &Arc<T>,
+    user_repository: &Arc<V>,
+    notification_queue: &NotificationQueue,
+) {
+    let reminders = match reminder_repository.list_enabled().await {
+        Ok(reminders) => reminders,
+        Err(error) => {
+            println!("Could not retrieve reminders: {:?}", error);
+            return;
+        }
+    };
+
+    let now = Utc::now();
+
+    for reminder in reminders {
+        let reminder_id = reminder.reminder_id.clone();
+
+        let result = check_reminder(
+            reminder_repository,
+            reading_repository,
+            user_repository,
+            notification_queue,
+            reminder,
+            now,
+        )
+        .await;
+
+        if let Err(error) = result {
+            println!("Could not check reminder {}: {}", reminder_id, error);
+        }
+    }
+}
+
+/**
+ * Checks every minute for reminders that have become due and queues them for users who haven't taken a reading in
+ * the reminder's window. Runs until the server stops.
+ */
+pub async fn run_reminder_scheduler<
+    R: ReminderRepository,
+    T: BloodPressureReadingRepository,
+    V: UserRepository,
+>(
+    reminder_repository: Arc<R>,
+    reading_repository: Arc<T>,
+    user_repository: Arc<V>,
+    notification_queue: Arc<NotificationQueue>,
+) {
+    let mut interval = tokio::time::interval(Duration::from_secs(CHECK_INTERVAL_SECONDS));
+
+    loop {
+        interval.tick().await;
+
+        check_reminders(
+            &reminder_repository,
+            &reading_repository,
+            &user_repository,
+            &notification_queue,
+        )
+        .await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveTime, TimeDelta, TimeZone, Utc};
+    use chrono_tz::Europe::London;
+
+    use super::*;
+
+    fn time(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    fn reminder(last_triggered: Option<DateTime<Utc>>) -> ReminderEntity {
+        ReminderEntity {
+            reminder_id: "reminder".to_string(),
+            user_id: "user".to_string(),
+            time_of_day: time(7, 0),
+            window_minutes: 60,
+            enabled: true,
+            last_triggered,
+        }
+    }
+
+    #[test]
+    fn time_earlier_than_now_is_today() {
+        // 09:00 BST
+        let now = Utc.with_ymd_and_hms(2025, 6, 1, 8, 0, 0).unwrap();
+
+        assert_eq!(
+            latest_occurrence(time(7, 0), London, now),
+            Some(Utc.with_ymd_and_hms(2025, 6, 1, 6, 0, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn time_later_than_now_is_yesterday() {
+        // 09:00 BST
+        let now = Utc.with_ymd_and_hms(2025, 6, 1, 8, 0, 0).unwrap();
+
+        assert_eq!(
+            latest_occurrence(time(20, 0), London, now),
+            Some(Utc.with_ymd_and_hms(2025, 5, 31, 19, 0, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn time_equal_to_now_is_today() {
+        let now = Utc.with_ymd_and_hms(2025, 6, 1, 6, 0, 0).unwrap();
+
+        assert_eq!(latest_occurrence(time(7, 0), London, now), Some(now));
+    }
+
+    #[test]
+    fn time_skipped_by_the_clocks_going_forward_uses_yesterday() {
+        // The clocks went from 01:00 GMT to 02:00 BST on 30 March 2025, so 01:30 didn't happen that day
+        let now = Utc.with_ymd_and_hms(2025, 3, 30, 3, 0, 0).unwrap();
+
+        assert_eq!(
+            latest_occurrence(time(1, 30), London, now),
+            Some(Utc.with_ymd_and_hms(2025, 3, 29, 1, 30, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn time_repeated_by_the_clocks_going_back_uses_the_first() {
+        // The clocks went from 02:00 BST back to 01:00 GMT on 26 October 2025, so 01:30 happened twice
+        let first = Utc.with_ymd_and_hms(2025, 10, 26, 0, 30, 0).unwrap();
+
+        assert_eq!(
+            latest_occurrence(time(1, 30), London, first + TimeDelta::minutes(10)),
+            Some(first)
+        );
+        // Still the first once the second has passed, so the reminder isn't due twice
+        assert_eq!(
+            latest_occurrence(time(1, 30), London, first + TimeDelta::hours(2)),
+            Some(first)
+        );
+    }
+
+    #[test]
+    fn occurrence_within_the_lateness_limit_is_due() {
+        let occurrence = Utc.with_ymd_and_hms(2025, 6, 1, 6, 0, 0).unwrap();
+        let now = occurrence + TimeDelta::minutes(MAX_LATENESS_MINUTES);
+
+        assert!(is_due(&reminder(None), occurrence, now));
+    }
+
+    #[test]
+    fn occurrence_older_than_the_lateness_limit_is_not_due() {
+        let occurrence = Utc.with_ymd_and_hms(2025, 6, 1, 6, 0, 0).unwrap();
+        let now = occurrence + TimeDelta::minutes(MAX_LATENESS_MINUTES + 1);
+
+        assert!(!is_due(&reminder(None), occurrence, now));
+    }
+
+    #[test]
+    fn occurrence_already_triggered_is_not_due() {
+        let occurrence = Utc.with_ymd_and_hms(2025, 6, 1, 6, 0, 0).unwrap();
+        let now = occurrence + TimeDelta::minutes(1);
+
+        assert!(!is_due(&reminder(Some(occurrence)), occurrence, now));
+        assert!(is_due(
+            &reminder(Some(occurrence - TimeDelta::days(1))),
+            occurrence,
+            now
+        ));
+    }
+}
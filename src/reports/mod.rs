@@ -0,0 +1,3 @@
+pub(crate) mod pdf_document;
+pub(crate) mod reading_report;
+pub(crate) mod share_page;
@@ -0,0 +1,322 @@
+use std::fmt::Write;
+
+// A4 in points
+pub const PAGE_WIDTH: f64 = 595.28;
+pub const PAGE_HEIGHT: f64 = 841.89;
+
+#[derive(Clone, Copy)]
+pub enum Font {
+    Regular,
+    Bold,
+}
+
+#[derive(Clone, Copy)]
+pub struct Colour {
+    pub red: f64,
+    pub green: f64,
+    pub blue: f64,
+}
+
+impl Colour {
+    pub const fn rgb(red: u8, green: u8, blue: u8) -> Colour {
+        Colour {
+            red: red as f64 / 255.0,
+            green: green as f64 / 255.0,
+            blue: blue as f64 / 255.0,
+        }
+    }
+}
+
+/**
+ * The position in WinAnsiEncoding of the characters outside Latin-1 that the standard fonts can draw
+ */
+fn win_ansi_code(character: char) -> Option<u32> {
+    match character {
+        '\u{a0}'..='\u{ff}' => Some(character as u32),
+        '€' => Some(0x80),
+        '‘' => Some(0x91),
+        '’' => Some(0x92),
+        '“' => Some(0x93),
+        '”' => Some(0x94),
+        '•' => Some(0x95),
+        '–' => Some(0x96),
+        '—' => Some(0x97),
+        _ => None,
+    }
+}
+
+/**
+ * Escapes text for a PDF string literal. Characters outside ASCII are written as octal escapes of their
+ * WinAnsiEncoding position, and those the standard fonts can't draw are replaced.
+ */
+fn escape_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for character in text.chars() {
+        match character {
+            '(' | ')' | '\\' => {
+                escaped.push('\\');
+                escaped.push(character);
+            }
+            ' '..='~' => escaped.push(character),
+            _ => match win_ansi_code(character) {
+                Some(code) => {
+                    let _ = write!(escaped, "\\{:03o}", code);
+                }
+                None => escaped.push('?'),
+            },
+        }
+    }
+
+    escaped
+}
+
+/**
+ * The drawing operations for one page. Coordinates have their origin at the top left of the page and grow
+ * downwards, and are flipped into PDF's bottom-left origin as they are written.
+ */
+#[derive(Default)]
+pub struct PdfPage {
+    content: String,
+}
+
+impl PdfPage {
+    pub fn text(&mut self, x: f64, y: f64, size: f64, font: Font, text: &str) {
+        let font_name = match font {
+            Font::Regular => "F1",
+            Font::Bold => "F2",
+        };
+
+        let _ = writeln!(
+            self.content,
+            "BT /{} {:.1} Tf {:.2} {:.2} Td ({}) Tj ET",
+            font_name,
+            size,
+            x,
+            PAGE_HEIGHT - y,
+            escape_text(text)
+        );
+    }
+
+    pub fn line(&mut self, from: (f64, f64), to: (f64, f64), width: f64, colour: Colour) {
+        self.polyline(&[from, to], width, colour);
+    }
+
+    pub fn polyline(&mut self, points: &[(f64, f64)], width: f64, colour: Colour) {
+        let Some(((first_x, first_y), rest)) = points.split_first() else {
+            return;
+        };
+
+        let _ = write!(
+            self.content,
+            "{:.3} {:.3} {:.3} RG {:.2} w {:.2} {:.2} m",
+            colour.red,
+            colour.green,
+            colour.blue,
+            width,
+            first_x,
+            PAGE_HEIGHT - first_y
+        );
+
+        for (x, y) in rest {
+            let _ = write!(self.content, " {:.2} {:.2} l", x, PAGE_HEIGHT - y);
+        }
+
+        self.content.push_str(" S\n");
+    }
+
+    pub fn fill_rect(&mut self, x: f64, y: f64, width: f64, height: f64, colour: Colour) {
+        let _ = writeln!(
+            self.content,
+            "{:.3} {:.3} {:.3} rg {:.2} {:.2} {:.2} {:.2} re f",
+            colour.red,
+            colour.green,
+            colour.blue,
+            x,
+            PAGE_HEIGHT - y - height,
+            width,
+            height
+        );
+    }
+}
+
+/**
+ * A minimal PDF writer for text and line drawings using the built in Helvetica fonts, so nothing needs to be
+ * embedded and no external renderer is needed
+ */
+pub struct PdfDocument {
+    title: String,
+    pages: Vec<PdfPage>,
+}
+
+impl PdfDocument {
+    pub fn new(title: &str) -> PdfDocument {
+        PdfDocument {
+            title: title.to_string(),
+            pages: vec![],
+        }
+    }
+
+    pub fn add_page(&mut self, page: PdfPage) {
+        self.pages.push(page);
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        // Objects 1 to 5 are fixed, then each page takes two: the page itself and its content stream
+        let page_object_ids: Vec<usize> =
+            (0..self.pages.len()).map(|index| 6 + index * 2).collect();
+
+        let kids: Vec<String> = page_object_ids
+            .iter()
+            .map(|id| format!("{} 0 R", id))
+            .collect();
+
+        let mut objects = vec![
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            format!(
+                "<< /Type /Pages /Kids [{}] /Count {} >>",
+                kids.join(" "),
+                self.pages.len()
+            ),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>"
+                .to_string(),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>"
+                .to_string(),
+            format!(
+                "<< /Title ({}) /Producer (Blood Pressure Tracker) >>",
+                escape_text(&self.title)
+            ),
+        ];
+
+        for (page, page_object_id) in self.pages.iter().zip(&page_object_ids) {
+            objects.push(format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {:.2} {:.2}] \
+                /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+                PAGE_WIDTH,
+                PAGE_HEIGHT,
+                page_object_id + 1
+            ));
+            objects.push(format!(
+                "<< /Length {} >>\nstream\n{}endstream",
+                page.content.len(),
+                page.content
+            ));
+        }
+
+        let mut output = String::from("%PDF-1.4\n");
+        let mut offsets = Vec::with_capacity(objects.len());
+
+        for (index, object) in objects.iter().enumerate() {
+            offsets.push(output.len());
+            let _ = write!(output, "{} 0 obj\n{}\nendobj\n", index + 1, object);
+        }
+
+        let xref_offset = output.len();
+        let _ = write!(
+            output,
+            "xref\n0 {}\n0000000000 65535 f \n",
+            objects.len() + 1
+        );
+
+        for offset in offsets {
+            let _ = writeln!(output, "{:010} 00000 n ", offset);
+        }
+
+        let _ = write!(
+            output,
+            "trailer\n<< /Size {} /Root 1 0 R /Info 5 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            xref_offset
+        );
+
+        output.into_bytes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document_with_pages(page_count: usize) -> String {
+        let mut document = PdfDocument::new("Report (draft)");
+
+        for index in 0..page_count {
+            let mut page = PdfPage::default();
+            page.text(50.0, 50.0, 12.0, Font::Bold, &format!("Page {}", index + 1));
+            page.line((50.0, 60.0), (200.0, 60.0), 1.0, Colour::rgb(0, 0, 0));
+            document.add_page(page);
+        }
+
+        String::from_utf8(document.to_bytes()).unwrap()
+    }
+
+    #[test]
+    fn xref_offsets_point_at_their_objects() {
+        let output = document_with_pages(3);
+
+        let startxref = output.rfind("startxref\n").unwrap();
+        let xref_offset: usize = output[startxref + "startxref\n".len()..]
+            .lines()
+            .next()
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!(output[xref_offset..].starts_with("xref\n"));
+
+        let mut lines = output[xref_offset..].lines().skip(1);
+        let object_count: usize = lines
+            .next()
+            .unwrap()
+            .strip_prefix("0 ")
+            .unwrap()
+            .parse()
+            .unwrap();
+        // The catalog, page tree, two fonts and info, then a page and its content stream for each page
+        assert_eq!(object_count, 1 + 5 + 3 * 2);
+        assert_eq!(lines.next(), Some("0000000000 65535 f "));
+
+        for id in 1..object_count {
+            let entry = lines.next().unwrap();
+            let offset: usize = entry[..10].parse().unwrap();
+
+            assert!(entry.ends_with(" 00000 n "));
+            assert!(
+                output[offset..].starts_with(&format!("{} 0 obj\n", id)),
+                "Object {} is not at offset {}",
+                id,
+                offset
+            );
+        }
+    }
+
+    #[test]
+    fn stream_lengths_match_their_content() {
+        let output = document_with_pages(2);
+
+        for (index, _) in output.match_indices("/Length ") {
+            let (length, content) = output[index + "/Length ".len()..]
+                .split_once(" >>\nstream\n")
+                .unwrap();
+            let length: usize = length.parse().unwrap();
+
+            assert!(content[length..].starts_with("endstream"));
+        }
+    }
+
+    #[test]
+    fn string_delimiters_and_backslashes_are_escaped() {
+        assert_eq!(escape_text(r"(a) \ b"), r"\(a\) \\ b");
+    }
+
+    #[test]
+    fn characters_outside_ascii_use_their_win_ansi_position() {
+        assert_eq!(escape_text("£5"), r"\2435");
+        assert_eq!(escape_text("€5"), r"\2005");
+        assert_eq!(escape_text("120–130 °"), r"120\226130 \260");
+    }
+
+    #[test]
+    fn characters_without_a_win_ansi_position_are_replaced() {
+        assert_eq!(escape_text("→ 血圧 😀"), "? ?? ?");
+    }
+}
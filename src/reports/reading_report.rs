@@ -0,0 +1,626 @@
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+
+use crate::{
+    aggregation::time_of_day::{MorningEveningSplit, WindowAverages},
+    charts::trend_chart::{ChartArea, ChartMetric, ChartPoint, TrendChart, build_trend_chart},
+    classification::{
+        blood_pressure_category::{
+            BloodPressureCategory, CategoryCounts, ClassificationGuideline, classify,
+        },
+        target_range::{TargetRange, TimeInRange},
+    },
+    reports::pdf_document::{Colour, Font, PAGE_HEIGHT, PAGE_WIDTH, PdfDocument, PdfPage},
+    repositories::blood_pressure_readings_repository::{
+        BloodPressureReadingEntity, MetricSummaryEntity, ReadingSummaryEntity,
+    },
+};
+
+const MARGIN: f64 = 50.0;
+const LINE_HEIGHT: f64 = 15.0;
+const TABLE_ROW_HEIGHT: f64 = 14.0;
+const CHART_HEIGHT: f64 = 200.0;
+const MAX_NOTE_CHARACTERS: usize = 40;
+
+const TEXT_COLOUR: Colour = Colour::rgb(0, 0, 0);
+const GRID_COLOUR: Colour = Colour::rgb(210, 210, 210);
+const TARGET_COLOUR: Colour = Colour::rgb(46, 139, 87);
+// The diastolic band sits inside the systolic one, so it is drawn over it in a deeper shade
+const SYSTOLIC_TARGET_BAND_COLOUR: Colour = Colour::rgb(236, 246, 239);
+const DIASTOLIC_TARGET_BAND_COLOUR: Colour = Colour::rgb(214, 236, 221);
+const SYSTOLIC_COLOUR: Colour = Colour::rgb(200, 40, 40);
+const DIASTOLIC_COLOUR: Colour = Colour::rgb(40, 80, 200);
+const PULSE_COLOUR: Colour = Colour::rgb(120, 120, 120);
+
+pub const CHART_METRICS: [ChartMetric; 2] = [ChartMetric::Systolic, ChartMetric::Diastolic];
+
+const SUMMARY_COLUMN_OFFSETS: [f64; 5] = [0.0, 100.0, 170.0, 240.0, 310.0];
+
+const READING_TABLE_COLUMNS: [(&str, f64); 6] = [
+    ("Taken", 0.0),
+    ("Systolic", 115.0),
+    ("Diastolic", 165.0),
+    ("Pulse", 220.0),
+    ("Category", 260.0),
+    ("Note", 370.0),
+];
+
+pub struct ReadingReport {
+    pub patient_name: String,
+    pub timezone: Tz,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub classification_guideline: ClassificationGuideline,
+    pub target_range: TargetRange,
+    pub summary: ReadingSummaryEntity,
+    pub morning_evening: MorningEveningSplit,
+    pub category_counts: CategoryCounts,
+    pub time_in_range: TimeInRange,
+    pub readings: Vec<BloodPressureReadingEntity>,
+}
+
+pub fn category_label(category: BloodPressureCategory) -> &'static str {
+    match category {
+        BloodPressureCategory::Normal => "Normal",
+        BloodPressureCategory::Elevated => "Elevated",
+        BloodPressureCategory::Stage1Hypertension => "Stage 1 hypertension",
+        BloodPressureCategory::Stage2Hypertension => "Stage 2 hypertension",
+        BloodPressureCategory::HypertensiveCrisis => "Hypertensive crisis",
+    }
+}
+
+pub fn guideline_label(guideline: ClassificationGuideline) -> &'static str {
+    match guideline {
+        ClassificationGuideline::AccAha2017 => "ACC/AHA 2017",
+        ClassificationGuideline::Esh2023 => "ESH 2023",
+    }
+}
+
+pub fn format_optional(value: Option<f64>) -> String {
+    value
+        .map(|value| format!("{:.1}", value))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+fn truncate(text: &str, max_characters: usize) -> String {
+    match text.chars().count() > max_characters {
+        true => format!(
+            "{}...",
+            text.chars().take(max_characters - 3).collect::<String>()
+        ),
+        false => text.to_string(),
+    }
+}
+
+/**
+ * Lays out content from the top of the page down, starting a new page when the next block won't fit
+ */
+struct ReportWriter {
+    document: PdfDocument,
+    page: PdfPage,
+    y: f64,
+}
+
+impl ReportWriter {
+    fn new(title: &str) -> ReportWriter {
+        ReportWriter {
+            document: PdfDocument::new(title),
+            page: PdfPage::default(),
+            y: MARGIN,
+        }
+    }
+
+    fn new_page(&mut self) {
+        let page = std::mem::take(&mut self.page);
+        self.document.add_page(page);
+        self.y = MARGIN;
+    }
+
+    fn ensure_space(&mut self, height: f64) {
+        if self.y + height > PAGE_HEIGHT - MARGIN {
+            self.new_page();
+        }
+    }
+
+    fn heading(&mut self, text: &str) {
+        self.ensure_space(LINE_HEIGHT * 3.0);
+        self.y += LINE_HEIGHT;
+        self.page.text(MARGIN, self.y, 13.0, Font::Bold, text);
+        self.y += LINE_HEIGHT * 1.2;
+    }
+
+    fn columns(&mut self, columns: &[(String, f64)], font: Font) {
+        self.ensure_space(TABLE_ROW_HEIGHT);
+
+        for (text, offset) in columns {
+            self.page.text(MARGIN + offset, self.y, 9.5, font, text);
+        }
+
+        self.y += TABLE_ROW_HEIGHT;
+    }
+
+    fn line_of_text(&mut self, text: &str) {
+        self.columns(&[(text.to_string(), 0.0)], Font::Regular);
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        self.new_page();
+        self.document.to_bytes()
+    }
+}
+
+fn metric_row(label: &str, metric: &Option<MetricSummaryEntity>) -> Vec<(String, f64)> {
+    let values = match metric {
+        None => vec!["-".to_string(); 4],
+        Some(metric) => vec![
+            format!("{:.1}", metric.mean),
+            metric.min.to_string(),
+            metric.max.to_string(),
+            format_optional(metric.standard_deviation),
+        ],
+    };
+
+    std::iter::once(label.to_string())
+        .chain(values)
+        .zip(SUMMARY_COLUMN_OFFSETS)
+        .collect()
+}
+
+fn window_row(label: &str, averages: &WindowAverages) -> Vec<(String, f64)> {
+    [
+        label.to_string(),
+        averages.count.to_string(),
+        format_optional(averages.systolic_mean),
+        format_optional(averages.diastolic_mean),
+        format_optional(averages.pulse_mean),
+    ]
+    .into_iter()
+    .zip(SUMMARY_COLUMN_OFFSETS)
+    .collect()
+}
+
+fn write_summary(report: &ReadingReport, writer: &mut ReportWriter) {
+    writer.heading("Summary");
+
+    writer.line_of_text(&format!("Readings: {}", report.summary.count));
+    writer.line_of_text(&format!(
+        "Within target (below {}/{}): {} of {} ({}%)",
+        report.target_range.systolic,
+        report.target_range.diastolic,
+        report.time_in_range.within_target,
+        report.time_in_range.within_target + report.time_in_range.outside_target,
+        format_optional(report.time_in_range.percentage_within_target)
+    ));
+    writer.line_of_text(&format!(
+        "Mean pulse pressure: {}    Mean arterial pressure: {}",
+        format_optional(report.summary.mean_pulse_pressure),
+        format_optional(report.summary.mean_arterial_pressure)
+    ));
+
+    writer.y += TABLE_ROW_HEIGHT / 2.0;
+
+    let header: Vec<(String, f64)> = ["", "Mean", "Min", "Max", "Std dev"]
+        .into_iter()
+        .map(str::to_string)
+        .zip(SUMMARY_COLUMN_OFFSETS)
+        .collect();
+
+    writer.columns(&header, Font::Bold);
+    writer.columns(
+        &metric_row("Systolic", &report.summary.systolic),
+        Font::Regular,
+    );
+    writer.columns(
+        &metric_row("Diastolic", &report.summary.diastolic),
+        Font::Regular,
+    );
+    writer.columns(&metric_row("Pulse", &report.summary.pulse), Font::Regular);
+}
+
+fn write_morning_evening(report: &ReadingReport, writer: &mut ReportWriter) {
+    writer.heading("Morning and evening averages");
+
+    let header: Vec<(String, f64)> = ["", "Readings", "Systolic", "Diastolic", "Pulse"]
+        .into_iter()
+        .map(str::to_string)
+        .zip(SUMMARY_COLUMN_OFFSETS)
+        .collect();
+
+    writer.columns(&header, Font::Bold);
+    writer.columns(
+        &window_row("Morning", &report.morning_evening.morning),
+        Font::Regular,
+    );
+    writer.columns(
+        &window_row("Evening", &report.morning_evening.evening),
+        Font::Regular,
+    );
+
+    if report.morning_evening.diverges {
+        writer.line_of_text(&format!(
+            "Morning and evening averages differ by {}/{} mmHg",
+            format_optional(report.morning_evening.systolic_difference),
+            format_optional(report.morning_evening.diastolic_difference)
+        ));
+    }
+}
+
+fn write_category_breakdown(report: &ReadingReport, writer: &mut ReportWriter) {
+    writer.heading(&format!(
+        "Categories ({})",
+        guideline_label(report.classification_guideline)
+    ));
+
+    let counts = &report.category_counts;
+    let total = report.summary.count.max(1) as f64;
+
+    for (category, count) in [
+        (BloodPressureCategory::Normal, counts.normal),
+        (BloodPressureCategory::Elevated, counts.elevated),
+        (
+            BloodPressureCategory::Stage1Hypertension,
+            counts.stage1_hypertension,
+        ),
+        (
+            BloodPressureCategory::Stage2Hypertension,
+            counts.stage2_hypertension,
+        ),
+        (
+            BloodPressureCategory::HypertensiveCrisis,
+            counts.hypertensive_crisis,
+        ),
+    ] {
+        writer.columns(
+            &[
+                (category_label(category).to_string(), 0.0),
+                (count.to_string(), SUMMARY_COLUMN_OFFSETS[2]),
+                (
+                    format!("{:.0}%", count as f64 * 100.0 / total),
+                    SUMMARY_COLUMN_OFFSETS[3],
+                ),
+            ],
+            Font::Regular,
+        );
+    }
+}
+
+fn to_tuples(points: &[ChartPoint]) -> Vec<(f64, f64)> {
+    points.iter().map(|point| (point.x, point.y)).collect()
+}
+
+fn series_colour(metric: ChartMetric) -> Colour {
+    match metric {
+        ChartMetric::Systolic => SYSTOLIC_COLOUR,
+        ChartMetric::Diastolic => DIASTOLIC_COLOUR,
+        ChartMetric::Pulse => PULSE_COLOUR,
+    }
+}
+
+fn target_band_colour(metric: ChartMetric) -> Colour {
+    match metric {
+        ChartMetric::Diastolic => DIASTOLIC_TARGET_BAND_COLOUR,
+        ChartMetric::Systolic | ChartMetric::Pulse => SYSTOLIC_TARGET_BAND_COLOUR,
+    }
+}
+
+fn draw_chart(page: &mut PdfPage, chart: &TrendChart) {
+    let area = chart.area;
+
+    // Shapes are drawn opaque, so the bands go first to leave the grid and readings visible on top of them
+    for band in &chart.target_bands {
+        page.fill_rect(
+            area.x,
+            band.top,
+            area.width,
+            band.bottom - band.top,
+            target_band_colour(band.metric),
+        );
+    }
+
+    for tick in &chart.y_ticks {
+        page.line(
+            (area.x, tick.position),
+            (area.x + area.width, tick.position),
+            0.5,
+            GRID_COLOUR,
+        );
+        page.text(
+            area.x - 22.0,
+            tick.position + 3.0,
+            8.0,
+            Font::Regular,
+            &tick.label,
+        );
+    }
+
+    for tick in &chart.x_ticks {
+        page.text(
+            tick.position - 12.0,
+            area.y + area.height + 12.0,
+            8.0,
+            Font::Regular,
+            &tick.label,
+        );
+    }
+
+    for band in &chart.target_bands {
+        page.line(
+            (area.x, band.top),
+            (area.x + area.width, band.top),
+            0.75,
+            TARGET_COLOUR,
+        );
+        page.text(
+            area.x + 4.0,
+            band.top - 3.0,
+            7.0,
+            Font::Regular,
+            &band.label,
+        );
+    }
+
+    for series in &chart.series {
+        let colour = series_colour(series.metric);
+
+        page.polyline(&to_tuples(&series.points), 1.0, colour);
+
+        for point in &series.points {
+            page.fill_rect(point.x - 1.5, point.y - 1.5, 3.0, 3.0, colour);
+        }
+    }
+
+    page.line(
+        (area.x, area.y + area.height),
+        (area.x + area.width, area.y + area.height),
+        0.75,
+        TEXT_COLOUR,
+    );
+}
+
+fn write_trend_chart(report: &ReadingReport, writer: &mut ReportWriter) {
+    writer.heading("Trend");
+
+    if report.readings.is_empty() {
+        writer.line_of_text("No readings in this period");
+        return;
+    }
+
+    writer.ensure_space(CHART_HEIGHT + LINE_HEIGHT * 3.0);
+
+    let area = ChartArea {
+        x: MARGIN + 25.0,
+        y: writer.y,
+        width: PAGE_WIDTH - MARGIN * 2.0 - 25.0,
+        height: CHART_HEIGHT,
+    };
+
+    let chart = build_trend_chart(
+        &report.readings,
+        &CHART_METRICS,
+        report.timezone,
+        Some(report.target_range),
+        area,
+    );
+
+    draw_chart(&mut writer.page, &chart);
+    writer.y += CHART_HEIGHT + LINE_HEIGHT * 1.5;
+
+    writer
+        .page
+        .fill_rect(MARGIN, writer.y - 6.0, 8.0, 3.0, SYSTOLIC_COLOUR);
+    writer
+        .page
+        .text(MARGIN + 12.0, writer.y, 8.0, Font::Regular, "Systolic");
+    writer
+        .page
+        .fill_rect(MARGIN + 70.0, writer.y - 6.0, 8.0, 3.0, DIASTOLIC_COLOUR);
+    writer
+        .page
+        .text(MARGIN + 82.0, writer.y, 8.0, Font::Regular, "Diastolic");
+    writer.y += LINE_HEIGHT;
+}
+
+fn reading_table_header() -> Vec<(String, f64)> {
+    READING_TABLE_COLUMNS
+        .iter()
+        .map(|(label, offset)| (label.to_string(), *offset))
+        .collect()
+}
+
+fn write_reading_table(report: &ReadingReport, writer: &mut ReportWriter) {
+    writer.heading("Readings");
+    writer.columns(&reading_table_header(), Font::Bold);
+
+    let mut readings: Vec<&BloodPressureReadingEntity> = report.readings.iter().collect();
+    readings.sort_by_key(|reading| reading.taken);
+
+    for reading in readings {
+        // The header is repeated at the top of each page the table continues onto
+        if writer.y + TABLE_ROW_HEIGHT > PAGE_HEIGHT - MARGIN {
+            writer.new_page();
+            writer.columns(&reading_table_header(), Font::Bold);
+        }
+
+        let category = classify(
+            reading.systolic,
+            reading.diastolic,
+            report.classification_guideline,
+        );
+
+        let values = [
+            reading
+                .taken
+                .with_timezone(&report.timezone)
+                .format("%Y-%m-%d %H:%M")
+                .to_string(),
+            reading.systolic.to_string(),
+            reading.diastolic.to_string(),
+            reading.pulse.to_string(),
+            category_label(category).to_string(),
+            truncate(reading.note.as_deref().unwrap_or(""), MAX_NOTE_CHARACTERS),
+        ];
+
+        let row: Vec<(String, f64)> = values
+            .into_iter()
+            .zip(READING_TABLE_COLUMNS.iter().map(|(_, offset)| *offset))
+            .collect();
+
+        writer.columns(&row, Font::Regular);
+    }
+}
+
+/**
+ * Renders the report as a PDF for printing or sending to a clinician
+ */
+pub fn render_reading_report(report: ReadingReport) -> Vec<u8> {
+    let mut writer = ReportWriter::new("Blood pressure report");
+
+    writer.page.text(
+        MARGIN,
+        writer.y + 10.0,
+        18.0,
+        Font::Bold,
+        "Blood pressure report",
+    );
+    writer.y += LINE_HEIGHT * 2.0;
+
+    writer.line_of_text(&format!("Patient: {}", report.patient_name));
+    writer.line_of_text(&format!(
+        "Period: {} to {} ({})",
+        report
+            .from
+            .with_timezone(&report.timezone)
+            .format("%e %B %Y"),
+        report.to.with_timezone(&report.timezone).format("%e %B %Y"),
+        report.timezone.name()
+    ));
+    writer.line_of_text(&format!(
+        "Generated: {}",
+        Utc::now()
+            .with_timezone(&report.timezone)
+            .format("%e %B %Y %H:%M")
+    ));
+
+    write_summary(&report, &mut writer);
+    write_morning_evening(&report, &mut writer);
+    write_category_breakdown(&report, &mut writer);
+    write_trend_chart(&report, &mut writer);
+    write_reading_table(&report, &mut writer);
+
+    writer.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeDelta, TimeZone, Utc};
+    use chrono_tz::Tz;
+
+    use super::*;
+
+    fn report_with_readings(count: i64) -> ReadingReport {
+        let start = Utc.with_ymd_and_hms(2025, 1, 1, 8, 0, 0).unwrap();
+        let no_readings = || WindowAverages {
+            count: 0,
+            systolic_mean: None,
+            diastolic_mean: None,
+            pulse_mean: None,
+        };
+
+        ReadingReport {
+            patient_name: "Test".to_string(),
+            timezone: Tz::UTC,
+            from: start,
+            to: start + TimeDelta::hours(count),
+            classification_guideline: ClassificationGuideline::AccAha2017,
+            target_range: TargetRange {
+                systolic: 135,
+                diastolic: 85,
+                pulse_min: None,
+                pulse_max: None,
+            },
+            summary: ReadingSummaryEntity {
+                count,
+                systolic: None,
+                diastolic: None,
+                pulse: None,
+                mean_pulse_pressure: None,
+                mean_arterial_pressure: None,
+            },
+            morning_evening: MorningEveningSplit {
+                morning: no_readings(),
+                evening: no_readings(),
+                systolic_difference: None,
+                diastolic_difference: None,
+                diverges: false,
+            },
+            category_counts: CategoryCounts::default(),
+            time_in_range: TimeInRange::default(),
+            readings: (0..count)
+                .map(|index| BloodPressureReadingEntity {
+                    reading_id: format!("reading-{}", index),
+                    user_id: "user".to_string(),
+                    systolic: 120,
+                    diastolic: 80,
+                    pulse: 60,
+                    weight_kilograms: None,
+                    taken: start + TimeDelta::hours(index),
+                    measurement_session_id: None,
+                    note: None,
+                    arm: None,
+                    body_position: None,
+                    cuff_size: None,
+                    tags: vec![],
+                })
+                .collect(),
+        }
+    }
+
+    /**
+     * The content stream of each page, in order
+     */
+    fn page_contents(pdf: &[u8]) -> Vec<String> {
+        let pdf = String::from_utf8(pdf.to_vec()).unwrap();
+
+        pdf.split(">>\nstream\n")
+            .skip(1)
+            .filter_map(|part| part.split_once("endstream").map(|(content, _)| content))
+            .map(str::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn reading_table_repeats_its_header_on_each_page() {
+        let report = report_with_readings(120);
+        let mut writer = ReportWriter::new("Test");
+
+        write_reading_table(&report, &mut writer);
+        let pages = page_contents(&writer.finish());
+
+        assert!(pages.len() >= 2);
+
+        for page in &pages {
+            assert_eq!(page.matches("(Taken) Tj").count(), 1);
+        }
+
+        // The header is the first thing on each page after the first, which starts with the table's heading
+        for page in &pages[1..] {
+            assert!(page.lines().next().unwrap().contains("(Taken) Tj"));
+        }
+    }
+
+    #[test]
+    fn every_reading_is_written_once() {
+        let report = report_with_readings(120);
+        let mut writer = ReportWriter::new("Test");
+
+        write_reading_table(&report, &mut writer);
+        let pages = page_contents(&writer.finish());
+
+        let rows: usize = pages
+            .iter()
+            .map(|page| page.matches("(120) Tj").count())
+            .sum();
+
+        assert_eq!(rows, 120);
+    }
+}
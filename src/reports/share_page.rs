@@ -0,0 +1,214 @@
+use std::fmt::Write;
+
+use chrono::{DateTime, Utc};
+
+use crate::{
+    charts::{
+        svg_chart::{SVG_CHART_AREA, escape_xml, render_svg_chart},
+        trend_chart::build_trend_chart,
+    },
+    classification::blood_pressure_category::classify,
+    reports::reading_report::{
+        CHART_METRICS, ReadingReport, category_label, format_optional, guideline_label,
+    },
+    repositories::blood_pressure_readings_repository::{
+        BloodPressureReadingEntity, MetricSummaryEntity,
+    },
+};
+
+const STYLE: &str = "body { font-family: Helvetica, Arial, sans-serif; margin: 2em auto; max-width: 860px; \
+    padding: 0 1em; color: #000; } table { border-collapse: collapse; margin-bottom: 1.5em; } \
+    th, td { text-align: left; padding: 0.25em 1em 0.25em 0; border-bottom: 1px solid #ddd; } \
+    .details { color: #555; } svg { max-width: 100%; height: auto; }";
+
+fn start_page(html: &mut String, title: &str) {
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+        <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+        <meta name=\"robots\" content=\"noindex\">\n<title>{0}</title>\n<style>{1}</style>\n</head>\n<body>\n\
+        <h1>{0}</h1>\n",
+        escape_xml(title),
+        STYLE
+    );
+}
+
+fn end_page(html: &mut String) {
+    html.push_str("</body>\n</html>\n");
+}
+
+fn write_row(html: &mut String, cell: &str, values: &[String]) {
+    html.push_str("<tr>");
+
+    for value in values {
+        let _ = write!(html, "<{0}>{1}</{0}>", cell, escape_xml(value));
+    }
+
+    html.push_str("</tr>\n");
+}
+
+fn metric_row(label: &str, metric: &Option<MetricSummaryEntity>) -> Vec<String> {
+    let values = match metric {
+        None => vec!["-".to_string(); 4],
+        Some(metric) => vec![
+            format!("{:.1}", metric.mean),
+            metric.min.to_string(),
+            metric.max.to_string(),
+            format_optional(metric.standard_deviation),
+        ],
+    };
+
+    std::iter::once(label.to_string()).chain(values).collect()
+}
+
+fn write_summary(html: &mut String, report: &ReadingReport) {
+    let _ = write!(
+        html,
+        "<h2>Summary</h2>\n<p>Readings: {}<br>Within target (below {}/{}): {} of {} ({}%)<br>\
+        Mean pulse pressure: {}, mean arterial pressure: {}</p>\n",
+        report.summary.count,
+        report.target_range.systolic,
+        report.target_range.diastolic,
+        report.time_in_range.within_target,
+        report.time_in_range.within_target + report.time_in_range.outside_target,
+        format_optional(report.time_in_range.percentage_within_target),
+        format_optional(report.summary.mean_pulse_pressure),
+        format_optional(report.summary.mean_arterial_pressure)
+    );
+
+    html.push_str("<table>\n");
+    write_row(
+        html,
+        "th",
+        &["", "Mean", "Min", "Max", "Std dev"].map(str::to_string),
+    );
+    write_row(
+        html,
+        "td",
+        &metric_row("Systolic", &report.summary.systolic),
+    );
+    write_row(
+        html,
+        "td",
+        &metric_row("Diastolic", &report.summary.diastolic),
+    );
+    write_row(html, "td", &metric_row("Pulse", &report.summary.pulse));
+    html.push_str("</table>\n");
+}
+
+fn write_reading_table(html: &mut String, report: &ReadingReport) {
+    html.push_str("<h2>Readings</h2>\n");
+
+    if report.readings.is_empty() {
+        html.push_str("<p>No readings in this period</p>\n");
+        return;
+    }
+
+    let mut readings: Vec<&BloodPressureReadingEntity> = report.readings.iter().collect();
+    readings.sort_by_key(|reading| reading.taken);
+
+    html.push_str("<table>\n");
+    write_row(
+        html,
+        "th",
+        &[
+            "Taken",
+            "Systolic",
+            "Diastolic",
+            "Pulse",
+            "Category",
+            "Note",
+        ]
+        .map(str::to_string),
+    );
+
+    for reading in readings {
+        let category = classify(
+            reading.systolic,
+            reading.diastolic,
+            report.classification_guideline,
+        );
+
+        write_row(
+            html,
+            "td",
+            &[
+                reading
+                    .taken
+                    .with_timezone(&report.timezone)
+                    .format("%Y-%m-%d %H:%M")
+                    .to_string(),
+                reading.systolic.to_string(),
+                reading.diastolic.to_string(),
+                reading.pulse.to_string(),
+                category_label(category).to_string(),
+                reading.note.clone().unwrap_or_default(),
+            ],
+        );
+    }
+
+    html.push_str("</table>\n");
+}
+
+/**
+ * Renders the readings behind a share link as a self-contained HTML page, for someone who isn't logged in
+ */
+pub fn render_share_page(report: &ReadingReport, expires: DateTime<Utc>) -> String {
+    let mut html = String::new();
+
+    start_page(&mut html, "Blood pressure readings");
+
+    let _ = writeln!(
+        html,
+        "<p class=\"details\">Patient: {}<br>Period: {} to {} ({})<br>Categories: {}<br>\
+        This link expires on {}</p>",
+        escape_xml(&report.patient_name),
+        report
+            .from
+            .with_timezone(&report.timezone)
+            .format("%e %B %Y"),
+        report.to.with_timezone(&report.timezone).format("%e %B %Y"),
+        report.timezone.name(),
+        guideline_label(report.classification_guideline),
+        expires
+            .with_timezone(&report.timezone)
+            .format("%e %B %Y at %H:%M")
+    );
+
+    write_summary(&mut html, report);
+
+    if !report.readings.is_empty() {
+        let chart = build_trend_chart(
+            &report.readings,
+            &CHART_METRICS,
+            report.timezone,
+            Some(report.target_range),
+            SVG_CHART_AREA,
+        );
+
+        html.push_str("<h2>Trend</h2>\n");
+        html.push_str(&render_svg_chart(&chart));
+    }
+
+    write_reading_table(&mut html, report);
+
+    end_page(&mut html);
+
+    html
+}
+
+/**
+ * Shown for links that don't exist, have expired or have been revoked. It doesn't say which, so that it gives nothing
+ * away about links that were never issued.
+ */
+pub fn render_unavailable_share_page() -> String {
+    let mut html = String::new();
+
+    start_page(&mut html, "Link unavailable");
+    html.push_str(
+        "<p>This link has expired or is no longer available. Please ask for a new one.</p>\n",
+    );
+    end_page(&mut html);
+
+    html
+}
@@ -0,0 +1,123 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::repositories::{
+    blood_pressure_readings_repository::{RetrieveError, SaveError},
+    webhook_repository::WebhookEventEntity,
+};
+
+/**
+ * Alerts when a single reading is above either limit
+ */
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct AbsoluteThresholdRule {
+    pub systolic: i32,
+    pub diastolic: i32,
+}
+
+/**
+ * Alerts when the latest `count` readings are all at or above either limit
+ */
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct ConsecutiveHighRule {
+    pub count: u32,
+    pub systolic: i32,
+    pub diastolic: i32,
+}
+
+/**
+ * Alerts when a reading is at least this much higher than the average of the `window` readings before it
+ */
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct SuddenJumpRule {
+    pub window: u32,
+    pub systolic_increase: i32,
+    pub diastolic_increase: i32,
+}
+
+/**
+ * The alert rules for a user. A rule that is None is switched off.
+ */
+pub struct AlertRulesEntity {
+    pub user_id: String,
+    pub absolute_threshold: Option<AbsoluteThresholdRule>,
+    pub consecutive_high: Option<ConsecutiveHighRule>,
+    pub sudden_jump: Option<SuddenJumpRule>,
+}
+
+impl AlertRulesEntity {
+    /**
+     * The absolute threshold defaults to the hypertensive crisis limits, which are the same under both guidelines
+     */
+    pub fn defaults_for(user_id: String) -> AlertRulesEntity {
+        AlertRulesEntity {
+            user_id,
+            absolute_threshold: Some(AbsoluteThresholdRule {
+                systolic: 180,
+                diastolic: 120,
+            }),
+            consecutive_high: Some(ConsecutiveHighRule {
+                count: 3,
+                systolic: 140,
+                diastolic: 90,
+            }),
+            sudden_jump: Some(SuddenJumpRule {
+                window: 5,
+                systolic_increase: 30,
+                diastolic_increase: 20,
+            }),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertKind {
+    AbsoluteThreshold,
+    ConsecutiveHigh,
+    SuddenJump,
+}
+
+#[derive(Clone)]
+pub struct AlertEntity {
+    pub alert_id: String,
+    pub user_id: String,
+    /**
+     * The reading that triggered the alert
+     */
+    pub reading_id: String,
+    pub kind: AlertKind,
+    pub message: String,
+    pub triggered: DateTime<Utc>,
+}
+
+pub trait AlertRepository {
+    /**
+     * Retrieves the user's alert rules, or the default rules if the user has never changed them
+     */
+    async fn get_rules(&self, user_id: String) -> Result<AlertRulesEntity, RetrieveError>;
+
+    /**
+     * Creates or replaces the user's alert rules
+     */
+    async fn save_rules(&self, entity: AlertRulesEntity) -> Result<(), SaveError>;
+
+    /**
+     * Saves the alerts triggered by a reading along with the webhook events they raised
+     */
+    async fn save_alerts(
+        &self,
+        alerts: Vec<AlertEntity>,
+        webhook_events: Vec<WebhookEventEntity>,
+    ) -> Result<(), SaveError>;
+
+    /**
+     * Retrieves the alerts triggered for the user between the given times (inclusive), most recent first
+     */
+    async fn list_alerts(
+        &self,
+        user_id: String,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<AlertEntity>, RetrieveError>;
+}
@@ -0,0 +1,50 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::repositories::blood_pressure_readings_repository::{
+    DeleteError, RetrieveError, SaveError,
+};
+
+/**
+ * Limits what a personal access token can be used for. A token without any scopes can do anything the user can,
+ * except manage tokens.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ApiTokenScope {
+    #[serde(rename = "readings:read")]
+    ReadingsRead,
+    #[serde(rename = "readings:write")]
+    ReadingsWrite,
+}
+
+#[derive(Clone)]
+pub struct ApiTokenEntity {
+    pub token_id: String,
+    pub user_id: String,
+    /**
+     * What the user called the token, so they can tell their scripts and devices apart
+     */
+    pub name: String,
+    /**
+     * The SHA-256 of the token. The token itself is only shown to the user when it is created.
+     */
+    pub token_hash: String,
+    pub scopes: Vec<ApiTokenScope>,
+    pub created: DateTime<Utc>,
+    pub last_used: Option<DateTime<Utc>>,
+}
+
+pub trait ApiTokenRepository {
+    async fn save(&self, entity: ApiTokenEntity) -> Result<(), SaveError>;
+
+    async fn list(&self, user_id: String) -> Result<Vec<ApiTokenEntity>, RetrieveError>;
+
+    async fn get_by_token_hash(
+        &self,
+        token_hash: String,
+    ) -> Result<Option<ApiTokenEntity>, RetrieveError>;
+
+    async fn mark_used(&self, token_id: String, used: DateTime<Utc>) -> Result<(), SaveError>;
+
+    async fn delete(&self, user_id: String, token_id: String) -> Result<(), DeleteError>;
+}
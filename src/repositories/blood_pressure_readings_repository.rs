@@ -1,5 +1,36 @@
+use std::collections::HashMap;
+
 use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::aggregation::time_buckets::TimeBucket;
+use crate::repositories::webhook_repository::WebhookEventEntity;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Arm {
+    Left,
+    Right,
+}
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BodyPosition {
+    Sitting,
+    Standing,
+    LyingDown,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CuffSize {
+    Small,
+    Standard,
+    Large,
+    ExtraLarge,
+}
+
+#[derive(Clone)]
 pub struct BloodPressureReadingEntity {
     pub reading_id: String,
     pub user_id: String,
@@ -8,11 +39,124 @@ pub struct BloodPressureReadingEntity {
     pub pulse: i32,
     pub weight_kilograms: Option<f64>,
     pub taken: DateTime<Utc>,
+    /**
+     * Readings taken together in one sitting share a measurement session ID. Readings saved before sessions existed
+     * have none and are treated as a session of their own.
+     */
+    pub measurement_session_id: Option<String>,
+    pub note: Option<String>,
+    pub arm: Option<Arm>,
+    pub body_position: Option<BodyPosition>,
+    pub cuff_size: Option<CuffSize>,
+    pub tags: Vec<String>,
+}
+
+/**
+ * The position of the last reading on a page. The next page starts at the reading taken immediately before it, with
+ * the reading ID breaking ties between readings taken at the same time.
+ */
+#[derive(Clone)]
+pub struct ReadingCursor {
+    pub taken: DateTime<Utc>,
+    pub reading_id: String,
+}
+
+pub struct ReadingPageQuery {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub tag: Option<String>,
+    pub after: Option<ReadingCursor>,
+    pub limit: u32,
+}
+
+pub struct MetricSummaryEntity {
+    pub mean: f64,
+    pub min: i32,
+    pub max: i32,
+    /**
+     * The sample standard deviation. None when there are fewer than two readings
+     */
+    pub standard_deviation: Option<f64>,
+}
+
+pub struct ReadingSummaryEntity {
+    pub count: i64,
+    pub systolic: Option<MetricSummaryEntity>,
+    pub diastolic: Option<MetricSummaryEntity>,
+    pub pulse: Option<MetricSummaryEntity>,
+    /**
+     * The mean of systolic minus diastolic over each reading
+     */
+    pub mean_pulse_pressure: Option<f64>,
+    /**
+     * The mean of (diastolic + pulse pressure / 3) over each reading
+     */
+    pub mean_arterial_pressure: Option<f64>,
+}
+
+pub struct BucketAggregateEntity {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub count: i64,
+    pub systolic_mean: Option<f64>,
+    pub diastolic_mean: Option<f64>,
+    pub pulse_mean: Option<f64>,
+    pub weight_kilograms_mean: Option<f64>,
+    /**
+     * The number of readings in the bucket that had a weight recorded alongside them
+     */
+    pub weight_count: i64,
+}
+
+pub struct MeasurementSessionEntity {
+    pub measurement_session_id: String,
+    pub started: DateTime<Utc>,
+    pub ended: DateTime<Utc>,
+    pub reading_count: i64,
+    /**
+     * The number of readings that went into the averages, which excludes the first reading if it was discarded
+     */
+    pub averaged_count: i64,
+    pub systolic_mean: f64,
+    pub diastolic_mean: f64,
+    pub pulse_mean: f64,
+}
+
+/**
+ * A reading in a batch being saved, with the key the client chose so that it can safely send the batch again
+ */
+pub struct BatchReadingEntity {
+    pub reading: BloodPressureReadingEntity,
+    pub idempotency_key: Option<String>,
+    /**
+     * Only saved if the reading is, so a reading sent again doesn't raise its events twice
+     */
+    pub webhook_events: Vec<WebhookEventEntity>,
+}
+
+pub enum BatchSaveOutcome {
+    Saved,
+    /**
+     * The user has already saved a reading with the same idempotency key, so this one was not saved again
+     */
+    AlreadySaved {
+        reading_id: String,
+    },
 }
 
 #[derive(Debug)]
 pub enum SaveError {
     LowLevelError { description: String },
+    /**
+     * The reading being updated does not exist for the user
+     */
+    NotFound,
+}
+
+#[derive(Debug)]
+pub enum DeleteError {
+    LowLevelError { description: String },
+    NotFound,
 }
 
 #[derive(Debug)]
@@ -22,20 +166,117 @@ pub enum RetrieveError {
 }
 
 pub trait BloodPressureReadingRepository {
-    async fn save(&self, entity: BloodPressureReadingEntity) -> Result<(), SaveError>;
+    /**
+     * Saves the reading along with the webhook events it raised
+     */
+    async fn save(
+        &self,
+        entity: BloodPressureReadingEntity,
+        webhook_events: Vec<WebhookEventEntity>,
+    ) -> Result<(), SaveError>;
 
     /**
-     * Retrieves the list of readings from the user in descending order of the date and time they were taken
+     * Saves the readings in a single transaction, so that either all of them are saved or none are. Returns the
+     * outcome for each reading in the order they were given.
+     */
+    async fn save_batch(
+        &self,
+        entities: Vec<BatchReadingEntity>,
+    ) -> Result<Vec<BatchSaveOutcome>, SaveError>;
+
+    /**
+     * Replaces the values of an existing reading and returns the updated reading. Only readings belonging to the
+     * entity's user_id are updated. The measurement session is left as it is if the entity doesn't have one.
+     */
+    async fn update(
+        &self,
+        entity: BloodPressureReadingEntity,
+    ) -> Result<BloodPressureReadingEntity, SaveError>;
+
+    /**
+     * Deletes the reading with the given ID if it belongs to the user
+     */
+    async fn delete(&self, user_id: String, reading_id: String) -> Result<(), DeleteError>;
+
+    /**
+     * Retrieves the list of readings from the user in descending order of the date and time they were taken. If a
+     * tag is given only readings with that tag are included.
      */
     async fn list(
         &self,
         user_id: String,
         from: DateTime<Utc>,
         to: DateTime<Utc>,
+        tag: Option<String>,
     ) -> Result<Vec<BloodPressureReadingEntity>, RetrieveError>;
 
     /**
-     * Retrieves the latest weight reading supplied by the user if it exists
+     * Retrieves at most `limit` readings from the user that come after the cursor, in descending order of the date and
+     * time they were taken and then of reading ID. The from and to bounds are inclusive and are ignored if missing.
+     */
+    async fn list_page(
+        &self,
+        user_id: String,
+        query: ReadingPageQuery,
+    ) -> Result<Vec<BloodPressureReadingEntity>, RetrieveError>;
+
+    /**
+     * Retrieves every distinct tag the user has put on a reading, in alphabetical order
+     */
+    async fn list_tags(&self, user_id: String) -> Result<Vec<String>, RetrieveError>;
+
+    /**
+     * Retrieves the IDs of the user's readings that were saved with any of the given idempotency keys, by key
+     */
+    async fn find_by_idempotency_keys(
+        &self,
+        user_id: String,
+        idempotency_keys: Vec<String>,
+    ) -> Result<HashMap<String, String>, RetrieveError>;
+
+    /**
+     * Retrieves the measurement session of the user's most recent reading taken within the range, if any
      */
-    async fn get_latest_weight(&self, user_id: String) -> Result<Option<f64>, RetrieveError>;
+    async fn find_measurement_session(
+        &self,
+        user_id: String,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Option<String>, RetrieveError>;
+
+    /**
+     * Averages the user's readings taken within the range by measurement session, in descending order of when the
+     * session started. If 'discard_first' is set the first reading of each session with more than one reading is
+     * left out of the averages, since the first reading of a sitting tends to read high.
+     */
+    async fn list_measurement_sessions(
+        &self,
+        user_id: String,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        discard_first: bool,
+    ) -> Result<Vec<MeasurementSessionEntity>, RetrieveError>;
+
+    /**
+     * Calculates summary statistics over the user's readings taken within the given range
+     */
+    async fn summarise(
+        &self,
+        user_id: String,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<ReadingSummaryEntity, RetrieveError>;
+
+    /**
+     * Calculates the averages of the user's readings within each of the given buckets. Every bucket is returned, in
+     * ascending order, including those without any readings. Only readings between from and to (inclusive) are
+     * counted, as the first and last buckets can extend beyond the range.
+     */
+    async fn aggregate_by_bucket(
+        &self,
+        user_id: String,
+        buckets: &[TimeBucket],
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<BucketAggregateEntity>, RetrieveError>;
 }
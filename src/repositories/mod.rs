@@ -1,3 +1,10 @@
+pub(crate) mod alert_repository;
+pub(crate) mod api_token_repository;
 pub(crate) mod blood_pressure_readings_repository;
+pub(crate) mod reminder_repository;
 pub(crate) mod session_repository;
+pub(crate) mod share_repository;
 pub(crate) mod sql_lite;
+pub(crate) mod user_repository;
+pub(crate) mod webhook_repository;
+pub(crate) mod weight_repository;
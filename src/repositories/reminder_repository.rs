@@ -0,0 +1,52 @@
+use chrono::{DateTime, NaiveTime, Utc};
+
+use crate::repositories::blood_pressure_readings_repository::{
+    DeleteError, RetrieveError, SaveError,
+};
+
+#[derive(Clone)]
+pub struct ReminderEntity {
+    pub reminder_id: String,
+    pub user_id: String,
+    /**
+     * The time of day in the user's timezone at which the reminder is due
+     */
+    pub time_of_day: NaiveTime,
+    /**
+     * A reading taken up to this many minutes before the reminder is due means no reminder is needed
+     */
+    pub window_minutes: u32,
+    pub enabled: bool,
+    /**
+     * The most recent time the reminder was due and checked, whether or not a reminder was sent
+     */
+    pub last_triggered: Option<DateTime<Utc>>,
+}
+
+pub trait ReminderRepository {
+    async fn save(&self, entity: ReminderEntity) -> Result<(), SaveError>;
+
+    /**
+     * Replaces the schedule of an existing reminder and returns the updated reminder. Only reminders belonging to
+     * the user in the entity can be updated.
+     */
+    async fn update(&self, entity: ReminderEntity) -> Result<ReminderEntity, SaveError>;
+
+    async fn delete(&self, user_id: String, reminder_id: String) -> Result<(), DeleteError>;
+
+    /**
+     * Retrieves the user's reminders in order of the time of day they are due
+     */
+    async fn list(&self, user_id: String) -> Result<Vec<ReminderEntity>, RetrieveError>;
+
+    /**
+     * Retrieves the enabled reminders of every user, for the scheduler
+     */
+    async fn list_enabled(&self) -> Result<Vec<ReminderEntity>, RetrieveError>;
+
+    async fn mark_triggered(
+        &self,
+        reminder_id: String,
+        triggered: DateTime<Utc>,
+    ) -> Result<(), SaveError>;
+}
@@ -2,6 +2,7 @@ const SUBJECT_SESSION_KEY: &str = "OIDC_SUBJECT_KEY";
 const OIDC_CSRF_STATE_KEY: &str = "OIDC_CSRF_STATE_KEY";
 const OIDC_NONCE_KEY: &str = "OIDC_NONCE_KEY";
 const OIDC_PKCE_VERIFIER_KEY: &str = "OIDC_PKCE_VERIFIER_KEY";
+use axum::{extract::FromRequestParts, http::request::Parts};
 use openidconnect::{Nonce, PkceCodeVerifier};
 use tokio::try_join;
 use tower_sessions::Session;
@@ -39,13 +40,49 @@ pub trait SessionRepository {
     ) -> Result<(), SessionRepositoryError>;
 }
 
+/**
+ * The user a personal access token belongs to. auth_middleware adds this to the request once it has checked the
+ * token.
+ */
+#[derive(Clone)]
+pub struct ApiTokenSubject(pub String);
+
+/**
+ * The session for a request, along with the user of the personal access token the request was made with, if any
+ */
+pub struct RequestSession {
+    session: Session,
+    api_token_subject: Option<String>,
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for RequestSession {
+    type Rejection = <Session as FromRequestParts<S>>::Rejection;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let session = Session::from_request_parts(parts, state).await?;
+        let api_token_subject = parts
+            .extensions
+            .get::<ApiTokenSubject>()
+            .map(|subject| subject.0.clone());
+
+        Ok(RequestSession {
+            session,
+            api_token_subject,
+        })
+    }
+}
+
 pub struct TowerSessionRepository {
     session: Session,
+    api_token_subject: Option<String>,
 }
 
 impl TowerSessionRepository {
-    pub fn new(session: Session) -> TowerSessionRepository {
-        TowerSessionRepository { session }
+    pub fn new(session: RequestSession) -> TowerSessionRepository {
+        TowerSessionRepository {
+            session: session.session,
+            api_token_subject: session.api_token_subject,
+        }
     }
 }
 
@@ -78,6 +115,11 @@ impl<T: SessionRepository> LoggedInSessionRepository<T> {
 
 impl SessionRepository for TowerSessionRepository {
     async fn get_oidc_user_subject(&self) -> Result<Option<String>, SessionRepositoryError> {
+        // Requests made with a personal access token act as the token's owner, whatever the session holds
+        if let Some(subject) = &self.api_token_subject {
+            return Ok(Some(subject.clone()));
+        }
+
         let test = self.session.get::<String>(SUBJECT_SESSION_KEY).await?;
         Ok(test)
     }
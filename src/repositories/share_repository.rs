@@ -0,0 +1,68 @@
+use chrono::{DateTime, Utc};
+
+use crate::repositories::blood_pressure_readings_repository::{RetrieveError, SaveError};
+
+#[derive(Clone)]
+pub struct ShareLinkEntity {
+    pub share_id: String,
+    pub user_id: String,
+    /**
+     * The SHA-256 of the token in the link. The token itself is only known to whoever was given the link.
+     */
+    pub token_hash: String,
+    pub from_inclusive: DateTime<Utc>,
+    pub to_inclusive: DateTime<Utc>,
+    pub expires: DateTime<Utc>,
+    pub created: DateTime<Utc>,
+    pub revoked: Option<DateTime<Utc>>,
+}
+
+impl ShareLinkEntity {
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.revoked.is_none() && now < self.expires
+    }
+}
+
+pub struct ShareAccessEntity {
+    pub access_id: String,
+    pub share_id: String,
+    pub accessed: DateTime<Utc>,
+    pub user_agent: Option<String>,
+}
+
+pub trait ShareRepository {
+    async fn save(&self, entity: ShareLinkEntity) -> Result<(), SaveError>;
+
+    async fn list(&self, user_id: String) -> Result<Vec<ShareLinkEntity>, RetrieveError>;
+
+    async fn get(
+        &self,
+        user_id: String,
+        share_id: String,
+    ) -> Result<Option<ShareLinkEntity>, RetrieveError>;
+
+    async fn get_by_token_hash(
+        &self,
+        token_hash: String,
+    ) -> Result<Option<ShareLinkEntity>, RetrieveError>;
+
+    /**
+     * Stops the link from working. Revoked links are kept so their access log can still be viewed.
+     */
+    async fn revoke(
+        &self,
+        user_id: String,
+        share_id: String,
+        revoked: DateTime<Utc>,
+    ) -> Result<(), SaveError>;
+
+    async fn record_access(&self, entity: ShareAccessEntity) -> Result<(), SaveError>;
+
+    /**
+     * Retrieves the accesses to a link, most recent first
+     */
+    async fn list_accesses(
+        &self,
+        share_id: String,
+    ) -> Result<Vec<ShareAccessEntity>, RetrieveError>;
+}
@@ -0,0 +1,25 @@
+CREATE TABLE alert_rules (
+    user_id TEXT NOT NULL,
+    absolute_systolic INTEGER,
+    absolute_diastolic INTEGER,
+    consecutive_high_count INTEGER,
+    consecutive_high_systolic INTEGER,
+    consecutive_high_diastolic INTEGER,
+    sudden_jump_window INTEGER,
+    sudden_jump_systolic INTEGER,
+    sudden_jump_diastolic INTEGER,
+    PRIMARY KEY (user_id)
+);
+
+CREATE TABLE alerts (
+    alert_id TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    reading_id TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    message TEXT NOT NULL,
+    triggered TEXT NOT NULL,
+    PRIMARY KEY (alert_id, user_id)
+);
+
+CREATE INDEX idx_alerts_user_triggered
+ON alerts (user_id, triggered);
@@ -0,0 +1,12 @@
+CREATE TABLE reminders (
+    reminder_id TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    time_of_day TEXT NOT NULL,
+    window_minutes INTEGER NOT NULL,
+    enabled INTEGER NOT NULL DEFAULT 1,
+    last_triggered TEXT,
+    PRIMARY KEY (reminder_id, user_id)
+);
+
+CREATE INDEX idx_reminders_user
+ON reminders (user_id);
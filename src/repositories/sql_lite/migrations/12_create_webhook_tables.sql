@@ -0,0 +1,26 @@
+CREATE TABLE webhooks (
+    webhook_id TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    url TEXT NOT NULL,
+    secret TEXT NOT NULL,
+    created TEXT NOT NULL,
+    PRIMARY KEY (webhook_id, user_id)
+);
+
+CREATE INDEX idx_webhooks_user
+ON webhooks (user_id);
+
+CREATE TABLE webhook_outbox (
+    delivery_id TEXT NOT NULL,
+    webhook_id TEXT NOT NULL,
+    event TEXT NOT NULL,
+    payload TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending',
+    attempts INTEGER NOT NULL DEFAULT 0,
+    next_attempt TEXT NOT NULL,
+    last_error TEXT,
+    PRIMARY KEY (delivery_id)
+);
+
+CREATE INDEX idx_webhook_outbox_status_next_attempt
+ON webhook_outbox (status, next_attempt);
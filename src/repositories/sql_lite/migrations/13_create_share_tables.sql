@@ -0,0 +1,28 @@
+CREATE TABLE share_links (
+    share_id TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    token_hash TEXT NOT NULL,
+    from_inclusive TEXT NOT NULL,
+    to_inclusive TEXT NOT NULL,
+    expires TEXT NOT NULL,
+    created TEXT NOT NULL,
+    revoked TEXT,
+    PRIMARY KEY (share_id, user_id)
+);
+
+CREATE UNIQUE INDEX idx_share_links_token_hash
+ON share_links (token_hash);
+
+CREATE INDEX idx_share_links_user
+ON share_links (user_id);
+
+CREATE TABLE share_access_log (
+    access_id TEXT NOT NULL,
+    share_id TEXT NOT NULL,
+    accessed TEXT NOT NULL,
+    user_agent TEXT,
+    PRIMARY KEY (access_id)
+);
+
+CREATE INDEX idx_share_access_log_share_accessed
+ON share_access_log (share_id, accessed);
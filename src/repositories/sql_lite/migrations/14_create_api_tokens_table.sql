@@ -0,0 +1,16 @@
+CREATE TABLE api_tokens (
+    token_id TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    token_hash TEXT NOT NULL,
+    scopes TEXT NOT NULL DEFAULT '',
+    created TEXT NOT NULL,
+    last_used TEXT,
+    PRIMARY KEY (token_id, user_id)
+);
+
+CREATE UNIQUE INDEX idx_api_tokens_token_hash
+ON api_tokens (token_hash);
+
+CREATE INDEX idx_api_tokens_user
+ON api_tokens (user_id);
@@ -0,0 +1,5 @@
+ALTER TABLE reading
+ADD COLUMN idempotency_key TEXT NULL;
+
+CREATE UNIQUE INDEX idx_reading_user_idempotency_key
+ON reading (user_id, idempotency_key);
@@ -0,0 +1,5 @@
+CREATE TABLE users (
+    user_id TEXT NOT NULL,
+    classification_guideline TEXT NOT NULL DEFAULT 'acc_aha_2017',
+    PRIMARY KEY (user_id)
+);
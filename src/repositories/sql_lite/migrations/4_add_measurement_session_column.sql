@@ -0,0 +1,5 @@
+ALTER TABLE reading
+ADD COLUMN measurement_session_id TEXT NULL;
+
+CREATE INDEX idx_blood_pressure_reading_entity_user_measurement_session
+ON reading (user_id, measurement_session_id);
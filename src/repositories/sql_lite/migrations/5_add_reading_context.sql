@@ -0,0 +1,21 @@
+ALTER TABLE reading
+ADD COLUMN note TEXT NULL;
+
+ALTER TABLE reading
+ADD COLUMN arm TEXT NULL;
+
+ALTER TABLE reading
+ADD COLUMN body_position TEXT NULL;
+
+ALTER TABLE reading
+ADD COLUMN cuff_size TEXT NULL;
+
+CREATE TABLE reading_tag (
+    reading_id TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    tag TEXT NOT NULL,
+    PRIMARY KEY (reading_id, user_id, tag)
+);
+
+CREATE INDEX idx_reading_tag_user_tag
+ON reading_tag (user_id, tag);
@@ -0,0 +1,12 @@
+CREATE TABLE weight_measurement (
+    weight_measurement_id TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    weight_kilograms REAL NOT NULL,
+    taken TEXT NOT NULL,
+    PRIMARY KEY (weight_measurement_id, user_id)
+);
+
+CREATE INDEX idx_weight_measurement_user_taken
+ON weight_measurement (user_id, taken);
+
+ALTER TABLE users ADD COLUMN height_centimetres REAL;
@@ -0,0 +1 @@
+ALTER TABLE users ADD COLUMN weight_unit TEXT NOT NULL DEFAULT 'kilograms';
@@ -0,0 +1,5 @@
+ALTER TABLE users ADD COLUMN display_name TEXT;
+ALTER TABLE users ADD COLUMN email TEXT;
+ALTER TABLE users ADD COLUMN timezone TEXT NOT NULL DEFAULT 'UTC';
+ALTER TABLE users ADD COLUMN target_systolic INTEGER NOT NULL DEFAULT 130;
+ALTER TABLE users ADD COLUMN target_diastolic INTEGER NOT NULL DEFAULT 80;
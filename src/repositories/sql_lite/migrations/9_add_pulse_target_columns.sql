@@ -0,0 +1,2 @@
+ALTER TABLE users ADD COLUMN target_pulse_min INTEGER;
+ALTER TABLE users ADD COLUMN target_pulse_max INTEGER;
@@ -1 +1,16 @@
+use crate::repositories::blood_pressure_readings_repository::RetrieveError;
+
+pub(crate) mod sql_lite_alert_repository;
+pub(crate) mod sql_lite_api_token_repository;
 pub(crate) mod sql_lite_blood_pressure_reading_repository;
+pub(crate) mod sql_lite_reminder_repository;
+pub(crate) mod sql_lite_share_repository;
+pub(crate) mod sql_lite_user_repository;
+pub(crate) mod sql_lite_webhook_repository;
+pub(crate) mod sql_lite_weight_repository;
+
+pub fn to_column_parse_error(column_name: &str) -> RetrieveError {
+    RetrieveError::DeserializationError {
+        description: format!("Could not deserialize {} column", column_name),
+    }
+}
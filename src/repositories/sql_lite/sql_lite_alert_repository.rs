@@ -0,0 +1,255 @@
+use chrono::{DateTime, Utc};
+use sqlx::{
+    Row,
+    sqlite::{SqlitePool, SqliteRow},
+};
+
+use crate::repositories::{
+    alert_repository::{
+        AbsoluteThresholdRule, AlertEntity, AlertKind, AlertRepository, AlertRulesEntity,
+        ConsecutiveHighRule, SuddenJumpRule,
+    },
+    blood_pressure_readings_repository::{RetrieveError, SaveError},
+    sql_lite::{sql_lite_webhook_repository::insert_webhook_deliveries, to_column_parse_error},
+    webhook_repository::WebhookEventEntity,
+};
+
+pub struct SqlLiteAlertRepository {
+    connection_pool: SqlitePool,
+}
+
+impl SqlLiteAlertRepository {
+    pub fn from_pool(pool: SqlitePool) -> SqlLiteAlertRepository {
+        SqlLiteAlertRepository {
+            connection_pool: pool,
+        }
+    }
+}
+
+fn kind_to_column(kind: AlertKind) -> &'static str {
+    match kind {
+        AlertKind::AbsoluteThreshold => "absolute_threshold",
+        AlertKind::ConsecutiveHigh => "consecutive_high",
+        AlertKind::SuddenJump => "sudden_jump",
+    }
+}
+
+fn kind_from_column(value: &str) -> Result<AlertKind, RetrieveError> {
+    match value {
+        "absolute_threshold" => Ok(AlertKind::AbsoluteThreshold),
+        "consecutive_high" => Ok(AlertKind::ConsecutiveHigh),
+        "sudden_jump" => Ok(AlertKind::SuddenJump),
+        _ => Err(to_column_parse_error("kind")),
+    }
+}
+
+fn get_optional<T>(row: &SqliteRow, column_name: &str) -> Result<Option<T>, RetrieveError>
+where
+    T: for<'r> sqlx::Decode<'r, sqlx::Sqlite> + sqlx::Type<sqlx::Sqlite>,
+{
+    row.try_get(column_name)
+        .map_err(|_| to_column_parse_error(column_name))
+}
+
+/**
+ * Each rule is stored as a group of nullable columns, and is switched off if any of them are NULL
+ */
+fn deserialize_rules(row: SqliteRow) -> Result<AlertRulesEntity, RetrieveError> {
+    let user_id: String = row
+        .try_get("user_id")
+        .map_err(|_| to_column_parse_error("user_id"))?;
+
+    let absolute_threshold = match (
+        get_optional::<i32>(&row, "absolute_systolic")?,
+        get_optional::<i32>(&row, "absolute_diastolic")?,
+    ) {
+        (Some(systolic), Some(diastolic)) => Some(AbsoluteThresholdRule {
+            systolic,
+            diastolic,
+        }),
+        _ => None,
+    };
+
+    let consecutive_high = match (
+        get_optional::<u32>(&row, "consecutive_high_count")?,
+        get_optional::<i32>(&row, "consecutive_high_systolic")?,
+        get_optional::<i32>(&row, "consecutive_high_diastolic")?,
+    ) {
+        (Some(count), Some(systolic), Some(diastolic)) => Some(ConsecutiveHighRule {
+            count,
+            systolic,
+            diastolic,
+        }),
+        _ => None,
+    };
+
+    let sudden_jump = match (
+        get_optional::<u32>(&row, "sudden_jump_window")?,
+        get_optional::<i32>(&row, "sudden_jump_systolic")?,
+        get_optional::<i32>(&row, "sudden_jump_diastolic")?,
+    ) {
+        (Some(window), Some(systolic_increase), Some(diastolic_increase)) => {
+            Some(SuddenJumpRule {
+                window,
+                systolic_increase,
+                diastolic_increase,
+            })
+        }
+        _ => None,
+    };
+
+    Ok(AlertRulesEntity {
+        user_id,
+        absolute_threshold,
+        consecutive_high,
+        sudden_jump,
+    })
+}
+
+fn deserialize_alert(row: SqliteRow) -> Result<AlertEntity, RetrieveError> {
+    let alert_id: String = row
+        .try_get("alert_id")
+        .map_err(|_| to_column_parse_error("alert_id"))?;
+
+    let user_id: String = row
+        .try_get("user_id")
+        .map_err(|_| to_column_parse_error("user_id"))?;
+
+    let reading_id: String = row
+        .try_get("reading_id")
+        .map_err(|_| to_column_parse_error("reading_id"))?;
+
+    let kind_raw: String = row
+        .try_get("kind")
+        .map_err(|_| to_column_parse_error("kind"))?;
+
+    let message: String = row
+        .try_get("message")
+        .map_err(|_| to_column_parse_error("message"))?;
+
+    let triggered_raw: String = row
+        .try_get("triggered")
+        .map_err(|_| to_column_parse_error("triggered"))?;
+
+    let triggered = DateTime::parse_from_rfc3339(&triggered_raw)
+        .map_err(|_| to_column_parse_error("triggered"))?
+        .with_timezone(&Utc);
+
+    Ok(AlertEntity {
+        alert_id,
+        user_id,
+        reading_id,
+        kind: kind_from_column(&kind_raw)?,
+        message,
+        triggered,
+    })
+}
+
+impl AlertRepository for SqlLiteAlertRepository {
+    async fn get_rules(&self, user_id: String) -> Result<AlertRulesEntity, RetrieveError> {
+        let row = sqlx::query("select * from alert_rules WHERE user_id = ?")
+            .bind(&user_id)
+            .fetch_optional(&self.connection_pool)
+            .await
+            .map_err(|error| RetrieveError::LowLevelError {
+                description: error.to_string(),
+            })?;
+
+        match row {
+            None => Ok(AlertRulesEntity::defaults_for(user_id)),
+            Some(row) => deserialize_rules(row),
+        }
+    }
+
+    async fn save_rules(&self, entity: AlertRulesEntity) -> Result<(), SaveError> {
+        let result = sqlx::query(
+            "INSERT INTO alert_rules (user_id, absolute_systolic, absolute_diastolic, consecutive_high_count, \
+            consecutive_high_systolic, consecutive_high_diastolic, sudden_jump_window, sudden_jump_systolic, \
+            sudden_jump_diastolic) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?) \
+            ON CONFLICT (user_id) DO UPDATE SET absolute_systolic = excluded.absolute_systolic, \
+            absolute_diastolic = excluded.absolute_diastolic, consecutive_high_count = excluded.consecutive_high_count, \
+            consecutive_high_systolic = excluded.consecutive_high_systolic, \
+            consecutive_high_diastolic = excluded.consecutive_high_diastolic, \
+            sudden_jump_window = excluded.sudden_jump_window, sudden_jump_systolic = excluded.sudden_jump_systolic, \
+            sudden_jump_diastolic = excluded.sudden_jump_diastolic",
+        )
+        .bind(entity.user_id)
+        .bind(entity.absolute_threshold.map(|rule| rule.systolic))
+        .bind(entity.absolute_threshold.map(|rule| rule.diastolic))
+        .bind(entity.consecutive_high.map(|rule| rule.count))
+        .bind(entity.consecutive_high.map(|rule| rule.systolic))
+        .bind(entity.consecutive_high.map(|rule| rule.diastolic))
+        .bind(entity.sudden_jump.map(|rule| rule.window))
+        .bind(entity.sudden_jump.map(|rule| rule.systolic_increase))
+        .bind(entity.sudden_jump.map(|rule| rule.diastolic_increase))
+        .execute(&self.connection_pool)
+        .await;
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(error) => Err(SaveError::LowLevelError {
+                description: error.to_string(),
+            }),
+        }
+    }
+
+    async fn save_alerts(
+        &self,
+        alerts: Vec<AlertEntity>,
+        webhook_events: Vec<WebhookEventEntity>,
+    ) -> Result<(), SaveError> {
+        let result: Result<(), sqlx::Error> = async {
+            let mut transaction = self.connection_pool.begin().await?;
+
+            if let Some(alert) = alerts.first() {
+                insert_webhook_deliveries(&mut transaction, &alert.user_id, &webhook_events)
+                    .await?;
+            }
+
+            for alert in alerts {
+                sqlx::query(
+                    "INSERT into alerts (alert_id, user_id, reading_id, kind, message, triggered) VALUES(?,?,?,?,?,?)",
+                )
+                .bind(alert.alert_id)
+                .bind(alert.user_id)
+                .bind(alert.reading_id)
+                .bind(kind_to_column(alert.kind))
+                .bind(alert.message)
+                .bind(alert.triggered.to_rfc3339())
+                .execute(&mut *transaction)
+                .await?;
+            }
+
+            transaction.commit().await
+        }
+        .await;
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(error) => Err(SaveError::LowLevelError {
+                description: error.to_string(),
+            }),
+        }
+    }
+
+    async fn list_alerts(
+        &self,
+        user_id: String,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<AlertEntity>, RetrieveError> {
+        let query_result = sqlx::query(
+            "select * from alerts WHERE user_id = ? AND triggered >= ? AND triggered <= ? ORDER BY triggered DESC",
+        )
+        .bind(user_id)
+        .bind(from.to_rfc3339())
+        .bind(to.to_rfc3339())
+        .fetch_all(&self.connection_pool)
+        .await
+        .map_err(|error| RetrieveError::LowLevelError {
+            description: error.to_string(),
+        })?;
+
+        query_result.into_iter().map(deserialize_alert).collect()
+    }
+}
@@ -0,0 +1,190 @@
+use chrono::{DateTime, Utc};
+use sqlx::{
+    Row,
+    sqlite::{SqlitePool, SqliteRow},
+};
+
+use crate::repositories::{
+    api_token_repository::{ApiTokenEntity, ApiTokenRepository, ApiTokenScope},
+    blood_pressure_readings_repository::{DeleteError, RetrieveError, SaveError},
+    sql_lite::to_column_parse_error,
+};
+
+pub struct SqlLiteApiTokenRepository {
+    connection_pool: SqlitePool,
+}
+
+impl SqlLiteApiTokenRepository {
+    pub fn from_pool(pool: SqlitePool) -> SqlLiteApiTokenRepository {
+        SqlLiteApiTokenRepository {
+            connection_pool: pool,
+        }
+    }
+}
+
+fn scope_to_column(scope: ApiTokenScope) -> &'static str {
+    match scope {
+        ApiTokenScope::ReadingsRead => "readings:read",
+        ApiTokenScope::ReadingsWrite => "readings:write",
+    }
+}
+
+fn scope_from_column(value: &str) -> Result<ApiTokenScope, RetrieveError> {
+    match value {
+        "readings:read" => Ok(ApiTokenScope::ReadingsRead),
+        "readings:write" => Ok(ApiTokenScope::ReadingsWrite),
+        _ => Err(to_column_parse_error("scopes")),
+    }
+}
+
+/**
+ * Scopes are stored as a ',' separated list, with an empty string for a token without scopes
+ */
+fn scopes_to_column(scopes: &[ApiTokenScope]) -> String {
+    scopes
+        .iter()
+        .map(|scope| scope_to_column(*scope))
+        .collect::<Vec<&str>>()
+        .join(",")
+}
+
+fn scopes_from_column(value: &str) -> Result<Vec<ApiTokenScope>, RetrieveError> {
+    value
+        .split(',')
+        .filter(|scope| !scope.is_empty())
+        .map(scope_from_column)
+        .collect()
+}
+
+fn deserialize_row(row: SqliteRow) -> Result<ApiTokenEntity, RetrieveError> {
+    let token_id: String = row
+        .try_get("token_id")
+        .map_err(|_| to_column_parse_error("token_id"))?;
+
+    let user_id: String = row
+        .try_get("user_id")
+        .map_err(|_| to_column_parse_error("user_id"))?;
+
+    let name: String = row
+        .try_get("name")
+        .map_err(|_| to_column_parse_error("name"))?;
+
+    let token_hash: String = row
+        .try_get("token_hash")
+        .map_err(|_| to_column_parse_error("token_hash"))?;
+
+    let scopes_raw: String = row
+        .try_get("scopes")
+        .map_err(|_| to_column_parse_error("scopes"))?;
+
+    let created_raw: String = row
+        .try_get("created")
+        .map_err(|_| to_column_parse_error("created"))?;
+
+    let created = DateTime::parse_from_rfc3339(&created_raw)
+        .map_err(|_| to_column_parse_error("created"))?
+        .with_timezone(&Utc);
+
+    let last_used_raw: Option<String> = row
+        .try_get("last_used")
+        .map_err(|_| to_column_parse_error("last_used"))?;
+
+    let last_used = last_used_raw
+        .map(|raw| DateTime::parse_from_rfc3339(&raw).map(|date| date.with_timezone(&Utc)))
+        .transpose()
+        .map_err(|_| to_column_parse_error("last_used"))?;
+
+    Ok(ApiTokenEntity {
+        token_id,
+        user_id,
+        name,
+        token_hash,
+        scopes: scopes_from_column(&scopes_raw)?,
+        created,
+        last_used,
+    })
+}
+
+impl ApiTokenRepository for SqlLiteApiTokenRepository {
+    async fn save(&self, entity: ApiTokenEntity) -> Result<(), SaveError> {
+        let result = sqlx::query(
+            "INSERT into api_tokens (token_id, user_id, name, token_hash, scopes, created, last_used) \
+            VALUES(?,?,?,?,?,?,?)",
+        )
+        .bind(entity.token_id)
+        .bind(entity.user_id)
+        .bind(entity.name)
+        .bind(entity.token_hash)
+        .bind(scopes_to_column(&entity.scopes))
+        .bind(entity.created.to_rfc3339())
+        .bind(entity.last_used.map(|date| date.to_rfc3339()))
+        .execute(&self.connection_pool)
+        .await;
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(error) => Err(SaveError::LowLevelError {
+                description: error.to_string(),
+            }),
+        }
+    }
+
+    async fn list(&self, user_id: String) -> Result<Vec<ApiTokenEntity>, RetrieveError> {
+        let query_result =
+            sqlx::query("select * from api_tokens WHERE user_id = ? ORDER BY created ASC")
+                .bind(user_id)
+                .fetch_all(&self.connection_pool)
+                .await
+                .map_err(|error| RetrieveError::LowLevelError {
+                    description: error.to_string(),
+                })?;
+
+        query_result.into_iter().map(deserialize_row).collect()
+    }
+
+    async fn get_by_token_hash(
+        &self,
+        token_hash: String,
+    ) -> Result<Option<ApiTokenEntity>, RetrieveError> {
+        let row = sqlx::query("select * from api_tokens WHERE token_hash = ?")
+            .bind(token_hash)
+            .fetch_optional(&self.connection_pool)
+            .await
+            .map_err(|error| RetrieveError::LowLevelError {
+                description: error.to_string(),
+            })?;
+
+        row.map(deserialize_row).transpose()
+    }
+
+    async fn mark_used(&self, token_id: String, used: DateTime<Utc>) -> Result<(), SaveError> {
+        let result = sqlx::query("UPDATE api_tokens SET last_used = ? WHERE token_id = ?")
+            .bind(used.to_rfc3339())
+            .bind(token_id)
+            .execute(&self.connection_pool)
+            .await;
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(error) => Err(SaveError::LowLevelError {
+                description: error.to_string(),
+            }),
+        }
+    }
+
+    async fn delete(&self, user_id: String, token_id: String) -> Result<(), DeleteError> {
+        let result = sqlx::query("DELETE FROM api_tokens WHERE token_id = ? AND user_id = ?")
+            .bind(token_id)
+            .bind(user_id)
+            .execute(&self.connection_pool)
+            .await
+            .map_err(|error| DeleteError::LowLevelError {
+                description: error.to_string(),
+            })?;
+
+        match result.rows_affected() {
+            0 => Err(DeleteError::NotFound),
+            _ => Ok(()),
+        }
+    }
+}
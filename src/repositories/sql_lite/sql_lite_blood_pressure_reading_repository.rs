@@ -1,12 +1,27 @@
+use std::collections::HashMap;
+
+use crate::aggregation::time_buckets::TimeBucket;
 use crate::repositories::blood_pressure_readings_repository::{
-    BloodPressureReadingEntity, BloodPressureReadingRepository, RetrieveError, SaveError,
+    Arm, BatchReadingEntity, BatchSaveOutcome, BloodPressureReadingEntity,
+    BloodPressureReadingRepository, BodyPosition, BucketAggregateEntity, CuffSize, DeleteError,
+    MeasurementSessionEntity, MetricSummaryEntity, ReadingPageQuery, ReadingSummaryEntity,
+    RetrieveError, SaveError,
 };
-use chrono::DateTime;
+use crate::repositories::sql_lite::sql_lite_webhook_repository::insert_webhook_deliveries;
+use crate::repositories::sql_lite::to_column_parse_error;
+use crate::repositories::webhook_repository::WebhookEventEntity;
+use chrono::{DateTime, Utc};
 use sqlx::{
-    Row,
+    Row, SqliteConnection,
     sqlite::{SqlitePool, SqliteRow},
 };
 
+// Tags live in their own table, so they're gathered into a JSON array alongside each reading
+const SELECT_READING_WITH_TAGS: &str = "select reading.*, \
+    (select json_group_array(reading_tag.tag) from reading_tag \
+    WHERE reading_tag.reading_id = reading.reading_id AND reading_tag.user_id = reading.user_id) AS tags \
+    from reading";
+
 pub struct SqlLiteBloodPressureReadingRepository {
     connection_pool: SqlitePool,
 }
@@ -19,10 +34,136 @@ impl SqlLiteBloodPressureReadingRepository {
     }
 }
 
-fn to_column_parse_error(column_name: &str) -> RetrieveError {
-    RetrieveError::DeserializationError {
-        description: format!("Could not deserialize {} column", column_name),
+fn arm_to_column(arm: Arm) -> &'static str {
+    match arm {
+        Arm::Left => "left",
+        Arm::Right => "right",
+    }
+}
+
+fn arm_from_column(value: &str) -> Result<Arm, RetrieveError> {
+    match value {
+        "left" => Ok(Arm::Left),
+        "right" => Ok(Arm::Right),
+        _ => Err(to_column_parse_error("arm")),
+    }
+}
+
+fn body_position_to_column(body_position: BodyPosition) -> &'static str {
+    match body_position {
+        BodyPosition::Sitting => "sitting",
+        BodyPosition::Standing => "standing",
+        BodyPosition::LyingDown => "lying_down",
+    }
+}
+
+fn body_position_from_column(value: &str) -> Result<BodyPosition, RetrieveError> {
+    match value {
+        "sitting" => Ok(BodyPosition::Sitting),
+        "standing" => Ok(BodyPosition::Standing),
+        "lying_down" => Ok(BodyPosition::LyingDown),
+        _ => Err(to_column_parse_error("body_position")),
+    }
+}
+
+fn cuff_size_to_column(cuff_size: CuffSize) -> &'static str {
+    match cuff_size {
+        CuffSize::Small => "small",
+        CuffSize::Standard => "standard",
+        CuffSize::Large => "large",
+        CuffSize::ExtraLarge => "extra_large",
+    }
+}
+
+fn cuff_size_from_column(value: &str) -> Result<CuffSize, RetrieveError> {
+    match value {
+        "small" => Ok(CuffSize::Small),
+        "standard" => Ok(CuffSize::Standard),
+        "large" => Ok(CuffSize::Large),
+        "extra_large" => Ok(CuffSize::ExtraLarge),
+        _ => Err(to_column_parse_error("cuff_size")),
+    }
+}
+
+fn deserialize_optional_column<T>(
+    row: &SqliteRow,
+    column_name: &str,
+    from_column: fn(&str) -> Result<T, RetrieveError>,
+) -> Result<Option<T>, RetrieveError> {
+    let raw: Option<String> = row
+        .try_get(column_name)
+        .map_err(|_| to_column_parse_error(column_name))?;
+
+    raw.map(|value| from_column(&value)).transpose()
+}
+
+/**
+ * Rows that weren't selected with SELECT_READING_WITH_TAGS have no tags column and are given no tags
+ */
+fn deserialize_tags(row: &SqliteRow) -> Result<Vec<String>, RetrieveError> {
+    let raw: Option<String> = row.try_get("tags").ok().flatten();
+
+    match raw {
+        None => Ok(vec![]),
+        Some(json) => serde_json::from_str(&json).map_err(|_| to_column_parse_error("tags")),
+    }
+}
+
+async fn insert_tags(
+    connection: &mut SqliteConnection,
+    reading_id: &str,
+    user_id: &str,
+    tags: &[String],
+) -> Result<(), sqlx::Error> {
+    for tag in tags {
+        sqlx::query("INSERT OR IGNORE into reading_tag (reading_id, user_id, tag) VALUES(?,?,?)")
+            .bind(reading_id)
+            .bind(user_id)
+            .bind(tag)
+            .execute(&mut *connection)
+            .await?;
+    }
+
+    Ok(())
+}
+
+/**
+ * Returns false without saving anything if the user already has a reading with the idempotency key. Readings without
+ * a key are always saved.
+ */
+async fn insert_reading(
+    connection: &mut SqliteConnection,
+    entity: &BloodPressureReadingEntity,
+    idempotency_key: Option<&str>,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        "INSERT into reading (reading_id, user_id, systolic, diastolic, pulse, weight_kilograms, taken, \
+        measurement_session_id, note, arm, body_position, cuff_size, idempotency_key) \
+        VALUES(?,?,?,?,?,?,?,?,?,?,?,?,?) ON CONFLICT (user_id, idempotency_key) DO NOTHING",
+    )
+    .bind(&entity.reading_id)
+    .bind(&entity.user_id)
+    .bind(entity.systolic)
+    .bind(entity.diastolic)
+    .bind(entity.pulse)
+    .bind(entity.weight_kilograms)
+    .bind(entity.taken.to_rfc3339())
+    .bind(&entity.measurement_session_id)
+    .bind(&entity.note)
+    .bind(entity.arm.map(arm_to_column))
+    .bind(entity.body_position.map(body_position_to_column))
+    .bind(entity.cuff_size.map(cuff_size_to_column))
+    .bind(idempotency_key)
+    .execute(&mut *connection)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Ok(false);
     }
+
+    insert_tags(connection, &entity.reading_id, &entity.user_id, &entity.tags).await?;
+
+    Ok(true)
 }
 
 fn deserialize_row(
@@ -59,7 +200,23 @@ fn deserialize_row(
         .map(|date| date.to_utc())
         .map_err(|_| to_column_parse_error("taken"))?;
 
+    let measurement_session_id: Option<String> = row
+        .try_get("measurement_session_id")
+        .map_err(|_| to_column_parse_error("measurement_session_id"))?;
+
+    let note: Option<String> = row
+        .try_get("note")
+        .map_err(|_| to_column_parse_error("note"))?;
+
     let result: BloodPressureReadingEntity = BloodPressureReadingEntity {
+        arm: deserialize_optional_column(&row, "arm", arm_from_column)?,
+        body_position: deserialize_optional_column(
+            &row,
+            "body_position",
+            body_position_from_column,
+        )?,
+        cuff_size: deserialize_optional_column(&row, "cuff_size", cuff_size_from_column)?,
+        tags: deserialize_tags(&row)?,
         reading_id,
         user_id,
         systolic,
@@ -67,27 +224,152 @@ fn deserialize_row(
         pulse,
         weight_kilograms,
         taken: taken,
+        measurement_session_id,
+        note,
+    };
+
+    Ok(result)
+}
+
+/**
+ * The aggregate columns for one metric. SQLite has no built in standard deviation function, so the sample variance
+ * is calculated from the sums and the square root is taken once the row has been read.
+ */
+fn summary_columns(column_name: &str) -> String {
+    format!(
+        "AVG({column}) AS {column}_mean, MIN({column}) AS {column}_min, MAX({column}) AS {column}_max, \
+        (SUM({column} * {column}) - SUM({column}) * SUM({column}) * 1.0 / COUNT(*)) / (COUNT(*) - 1) AS {column}_variance",
+        column = column_name
+    )
+}
+
+fn deserialize_metric_summary(
+    row: &SqliteRow,
+    column_name: &str,
+) -> Result<Option<MetricSummaryEntity>, RetrieveError> {
+    let mean_column = format!("{}_mean", column_name);
+    let min_column = format!("{}_min", column_name);
+    let max_column = format!("{}_max", column_name);
+    let variance_column = format!("{}_variance", column_name);
+
+    let mean: Option<f64> = row
+        .try_get(mean_column.as_str())
+        .map_err(|_| to_column_parse_error(&mean_column))?;
+    let min: Option<i32> = row
+        .try_get(min_column.as_str())
+        .map_err(|_| to_column_parse_error(&min_column))?;
+    let max: Option<i32> = row
+        .try_get(max_column.as_str())
+        .map_err(|_| to_column_parse_error(&max_column))?;
+    let variance: Option<f64> = row
+        .try_get(variance_column.as_str())
+        .map_err(|_| to_column_parse_error(&variance_column))?;
+
+    let result = match (mean, min, max) {
+        (Some(mean), Some(min), Some(max)) => Some(MetricSummaryEntity {
+            mean,
+            min,
+            max,
+            // Floating point error can make the variance of identical values very slightly negative
+            standard_deviation: variance.map(|variance| variance.max(0.0).sqrt()),
+        }),
+        _ => None,
     };
 
     Ok(result)
 }
 
+fn deserialize_timestamp(row: &SqliteRow, column_name: &str) -> Result<DateTime<Utc>, RetrieveError> {
+    let raw: String = row
+        .try_get(column_name)
+        .map_err(|_| to_column_parse_error(column_name))?;
+
+    DateTime::parse_from_rfc3339(&raw)
+        .map(|date| date.to_utc())
+        .map_err(|_| to_column_parse_error(column_name))
+}
+
+fn deserialize_bucket_aggregate(row: SqliteRow) -> Result<BucketAggregateEntity, RetrieveError> {
+    let count: i64 = row
+        .try_get("reading_count")
+        .map_err(|_| to_column_parse_error("reading_count"))?;
+    let systolic_mean: Option<f64> = row
+        .try_get("systolic_mean")
+        .map_err(|_| to_column_parse_error("systolic_mean"))?;
+    let diastolic_mean: Option<f64> = row
+        .try_get("diastolic_mean")
+        .map_err(|_| to_column_parse_error("diastolic_mean"))?;
+    let pulse_mean: Option<f64> = row
+        .try_get("pulse_mean")
+        .map_err(|_| to_column_parse_error("pulse_mean"))?;
+    let weight_kilograms_mean: Option<f64> = row
+        .try_get("weight_kilograms_mean")
+        .map_err(|_| to_column_parse_error("weight_kilograms_mean"))?;
+    let weight_count: i64 = row
+        .try_get("weight_count")
+        .map_err(|_| to_column_parse_error("weight_count"))?;
+
+    Ok(BucketAggregateEntity {
+        start: deserialize_timestamp(&row, "bucket_start")?,
+        end: deserialize_timestamp(&row, "bucket_end")?,
+        count,
+        systolic_mean,
+        diastolic_mean,
+        pulse_mean,
+        weight_kilograms_mean,
+        weight_count,
+    })
+}
+
+fn deserialize_measurement_session(
+    row: SqliteRow,
+) -> Result<MeasurementSessionEntity, RetrieveError> {
+    let measurement_session_id: String = row
+        .try_get("session_id")
+        .map_err(|_| to_column_parse_error("session_id"))?;
+    let reading_count: i64 = row
+        .try_get("reading_count")
+        .map_err(|_| to_column_parse_error("reading_count"))?;
+    let averaged_count: i64 = row
+        .try_get("averaged_count")
+        .map_err(|_| to_column_parse_error("averaged_count"))?;
+    let systolic_mean: f64 = row
+        .try_get("systolic_mean")
+        .map_err(|_| to_column_parse_error("systolic_mean"))?;
+    let diastolic_mean: f64 = row
+        .try_get("diastolic_mean")
+        .map_err(|_| to_column_parse_error("diastolic_mean"))?;
+    let pulse_mean: f64 = row
+        .try_get("pulse_mean")
+        .map_err(|_| to_column_parse_error("pulse_mean"))?;
+
+    Ok(MeasurementSessionEntity {
+        measurement_session_id,
+        started: deserialize_timestamp(&row, "started")?,
+        ended: deserialize_timestamp(&row, "ended")?,
+        reading_count,
+        averaged_count,
+        systolic_mean,
+        diastolic_mean,
+        pulse_mean,
+    })
+}
+
 impl BloodPressureReadingRepository for SqlLiteBloodPressureReadingRepository {
     async fn save(
         &self,
         entity: crate::repositories::blood_pressure_readings_repository::BloodPressureReadingEntity,
+        webhook_events: Vec<WebhookEventEntity>,
     ) -> Result<(), crate::repositories::blood_pressure_readings_repository::SaveError> {
-        let result = sqlx::query(
-            "INSERT into reading (reading_id, user_id, systolic, diastolic, pulse, weight_kilograms, taken) VALUES(?,?,?,?,?,?,?)"
-        )
-            .bind(entity.reading_id)
-            .bind(entity.user_id)
-            .bind(entity.systolic)
-            .bind(entity.diastolic)
-            .bind(entity.pulse)
-            .bind(entity.weight_kilograms)
-            .bind(entity.taken.to_rfc3339())
-            .execute(&self.connection_pool).await;
+        let result: Result<(), sqlx::Error> = async {
+            let mut transaction = self.connection_pool.begin().await?;
+
+            insert_reading(&mut transaction, &entity, None).await?;
+            insert_webhook_deliveries(&mut transaction, &entity.user_id, &webhook_events).await?;
+
+            transaction.commit().await
+        }
+        .await;
 
         match result {
             Ok(_) => Ok(()),
@@ -97,20 +379,182 @@ impl BloodPressureReadingRepository for SqlLiteBloodPressureReadingRepository {
         }
     }
 
+    async fn save_batch(
+        &self,
+        entities: Vec<BatchReadingEntity>,
+    ) -> Result<Vec<BatchSaveOutcome>, SaveError> {
+        let result: Result<Vec<BatchSaveOutcome>, sqlx::Error> = async {
+            let mut transaction = self.connection_pool.begin().await?;
+            let mut outcomes = Vec::with_capacity(entities.len());
+
+            for entity in entities {
+                // Inserting first and only looking up the existing reading when nothing was inserted means two
+                // batches sent at the same time can't both save a reading with the same key. A key repeated within
+                // the batch is caught the same way.
+                let inserted = insert_reading(
+                    &mut transaction,
+                    &entity.reading,
+                    entity.idempotency_key.as_deref(),
+                )
+                .await?;
+
+                let outcome = match inserted {
+                    true => {
+                        insert_webhook_deliveries(
+                            &mut transaction,
+                            &entity.reading.user_id,
+                            &entity.webhook_events,
+                        )
+                        .await?;
+
+                        BatchSaveOutcome::Saved
+                    }
+                    false => {
+                        let reading_id: String = sqlx::query_scalar(
+                            "select reading_id from reading WHERE user_id = ? AND idempotency_key = ?",
+                        )
+                        .bind(&entity.reading.user_id)
+                        .bind(&entity.idempotency_key)
+                        .fetch_one(&mut *transaction)
+                        .await?;
+
+                        BatchSaveOutcome::AlreadySaved { reading_id }
+                    }
+                };
+
+                outcomes.push(outcome);
+            }
+
+            transaction.commit().await?;
+
+            Ok(outcomes)
+        }
+        .await;
+
+        result.map_err(|error| SaveError::LowLevelError {
+            description: error.to_string(),
+        })
+    }
+
+    async fn update(
+        &self,
+        entity: BloodPressureReadingEntity,
+    ) -> Result<BloodPressureReadingEntity, SaveError> {
+        let result: Result<Option<SqliteRow>, sqlx::Error> = async {
+            let mut transaction = self.connection_pool.begin().await?;
+
+            let row = sqlx::query(
+                "UPDATE reading SET systolic = ?, diastolic = ?, pulse = ?, weight_kilograms = ?, taken = ?, \
+                measurement_session_id = COALESCE(?, measurement_session_id), note = ?, arm = ?, body_position = ?, \
+                cuff_size = ? WHERE reading_id = ? AND user_id = ? RETURNING *"
+            )
+                .bind(entity.systolic)
+                .bind(entity.diastolic)
+                .bind(entity.pulse)
+                .bind(entity.weight_kilograms)
+                .bind(entity.taken.to_rfc3339())
+                .bind(&entity.measurement_session_id)
+                .bind(&entity.note)
+                .bind(entity.arm.map(arm_to_column))
+                .bind(entity.body_position.map(body_position_to_column))
+                .bind(entity.cuff_size.map(cuff_size_to_column))
+                .bind(&entity.reading_id)
+                .bind(&entity.user_id)
+                .fetch_optional(&mut *transaction)
+                .await?;
+
+            if row.is_some() {
+                sqlx::query("DELETE FROM reading_tag WHERE reading_id = ? AND user_id = ?")
+                    .bind(&entity.reading_id)
+                    .bind(&entity.user_id)
+                    .execute(&mut *transaction)
+                    .await?;
+
+                insert_tags(&mut transaction, &entity.reading_id, &entity.user_id, &entity.tags)
+                    .await?;
+            }
+
+            transaction.commit().await?;
+
+            Ok(row)
+        }
+        .await;
+
+        let row = result.map_err(|error| SaveError::LowLevelError {
+            description: error.to_string(),
+        })?;
+
+        match row {
+            None => Err(SaveError::NotFound),
+            Some(row) => {
+                let updated = deserialize_row(row).map_err(|_| SaveError::LowLevelError {
+                    description: "Could not deserialize updated reading".to_string(),
+                })?;
+
+                Ok(BloodPressureReadingEntity {
+                    tags: entity.tags,
+                    ..updated
+                })
+            }
+        }
+    }
+
+    async fn delete(&self, user_id: String, reading_id: String) -> Result<(), DeleteError> {
+        let result: Result<u64, sqlx::Error> = async {
+            let mut transaction = self.connection_pool.begin().await?;
+
+            sqlx::query("DELETE FROM reading_tag WHERE reading_id = ? AND user_id = ?")
+                .bind(&reading_id)
+                .bind(&user_id)
+                .execute(&mut *transaction)
+                .await?;
+
+            let deleted = sqlx::query("DELETE FROM reading WHERE reading_id = ? AND user_id = ?")
+                .bind(&reading_id)
+                .bind(&user_id)
+                .execute(&mut *transaction)
+                .await?;
+
+            transaction.commit().await?;
+
+            Ok(deleted.rows_affected())
+        }
+        .await;
+
+        let rows_affected = result.map_err(|error| DeleteError::LowLevelError {
+            description: error.to_string(),
+        })?;
+
+        match rows_affected {
+            0 => Err(DeleteError::NotFound),
+            _ => Ok(()),
+        }
+    }
+
     async fn list(
         &self,
         user_id: String,
         from: chrono::DateTime<chrono::Utc>,
         to: chrono::DateTime<chrono::Utc>,
+        tag: Option<String>,
     ) -> Result<
         Vec<crate::repositories::blood_pressure_readings_repository::BloodPressureReadingEntity>,
         crate::repositories::blood_pressure_readings_repository::RetrieveError,
     > {
+        let query = format!(
+            "{} WHERE user_id = ? AND taken >= ? AND taken <= ? AND (? IS NULL OR EXISTS ( \
+            select 1 from reading_tag WHERE reading_tag.reading_id = reading.reading_id \
+            AND reading_tag.user_id = reading.user_id AND reading_tag.tag = ?)) ORDER BY taken DESC",
+            SELECT_READING_WITH_TAGS
+        );
+
         let query_result =
-            sqlx::query("select * from reading WHERE user_id = ? AND taken >= ? AND taken <= ? ORDER BY taken DESC")
+            sqlx::query(&query)
                 .bind(user_id)
                 .bind(from.to_rfc3339())
                 .bind(to.to_rfc3339())
+                .bind(&tag)
+                .bind(&tag)
                 .fetch_all(&self.connection_pool)
                 .await
                 .map_err(|error| RetrieveError::LowLevelError {
@@ -128,20 +572,267 @@ impl BloodPressureReadingRepository for SqlLiteBloodPressureReadingRepository {
         return result;
     }
 
-    async fn get_latest_weight(&self, user_id: String) -> Result<Option<f64>, RetrieveError> {
-        let query = "select weight_kilograms from reading WHERE user_id = ? weight_kilograms IS NOT NULL ORDER BY taken DESC LIMIT 1";
+    async fn list_page(
+        &self,
+        user_id: String,
+        query: ReadingPageQuery,
+    ) -> Result<Vec<BloodPressureReadingEntity>, RetrieveError> {
+        let sql = format!(
+            "{} WHERE user_id = ? AND (? IS NULL OR taken >= ?) AND (? IS NULL OR taken <= ?) \
+            AND (? IS NULL OR taken < ? OR (taken = ? AND reading_id < ?)) \
+            AND (? IS NULL OR EXISTS ( \
+            select 1 from reading_tag WHERE reading_tag.reading_id = reading.reading_id \
+            AND reading_tag.user_id = reading.user_id AND reading_tag.tag = ?)) \
+            ORDER BY taken DESC, reading_id DESC LIMIT ?",
+            SELECT_READING_WITH_TAGS
+        );
+
+        let from = query.from.map(|from| from.to_rfc3339());
+        let to = query.to.map(|to| to.to_rfc3339());
+        let after_taken = query.after.as_ref().map(|cursor| cursor.taken.to_rfc3339());
+        let after_reading_id = query.after.map(|cursor| cursor.reading_id);
+
+        let query_result = sqlx::query(&sql)
+            .bind(user_id)
+            .bind(&from)
+            .bind(&from)
+            .bind(&to)
+            .bind(&to)
+            .bind(&after_taken)
+            .bind(&after_taken)
+            .bind(&after_taken)
+            .bind(&after_reading_id)
+            .bind(&query.tag)
+            .bind(&query.tag)
+            .bind(query.limit)
+            .fetch_all(&self.connection_pool)
+            .await
+            .map_err(|error| RetrieveError::LowLevelError {
+                description: error.to_string(),
+            })?;
+
+        query_result.into_iter().map(deserialize_row).collect()
+    }
+
+    async fn list_tags(&self, user_id: String) -> Result<Vec<String>, RetrieveError> {
+        let query_result =
+            sqlx::query("select DISTINCT tag from reading_tag WHERE user_id = ? ORDER BY tag ASC")
+                .bind(user_id)
+                .fetch_all(&self.connection_pool)
+                .await
+                .map_err(|error| RetrieveError::LowLevelError {
+                    description: error.to_string(),
+                })?;
+
+        query_result
+            .into_iter()
+            .map(|row| row.try_get("tag").map_err(|_| to_column_parse_error("tag")))
+            .collect()
+    }
+
+    async fn find_by_idempotency_keys(
+        &self,
+        user_id: String,
+        idempotency_keys: Vec<String>,
+    ) -> Result<HashMap<String, String>, RetrieveError> {
+        if idempotency_keys.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        // The keys are passed as a JSON array so that any number of them can be bound to a single parameter
+        let keys_json = serde_json::to_string(&idempotency_keys).map_err(|error| {
+            RetrieveError::LowLevelError {
+                description: error.to_string(),
+            }
+        })?;
+
+        let query_result = sqlx::query(
+            "select idempotency_key, reading_id from reading WHERE user_id = ? \
+            AND idempotency_key IN (select value from json_each(?))",
+        )
+        .bind(user_id)
+        .bind(keys_json)
+        .fetch_all(&self.connection_pool)
+        .await
+        .map_err(|error| RetrieveError::LowLevelError {
+            description: error.to_string(),
+        })?;
+
+        query_result
+            .into_iter()
+            .map(|row| {
+                let idempotency_key: String = row
+                    .try_get("idempotency_key")
+                    .map_err(|_| to_column_parse_error("idempotency_key"))?;
+                let reading_id: String = row
+                    .try_get("reading_id")
+                    .map_err(|_| to_column_parse_error("reading_id"))?;
+
+                Ok((idempotency_key, reading_id))
+            })
+            .collect()
+    }
+
+    async fn find_measurement_session(
+        &self,
+        user_id: String,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Option<String>, RetrieveError> {
+        let query_result = sqlx::query(
+            "select measurement_session_id from reading WHERE user_id = ? AND taken >= ? AND taken <= ? \
+            AND measurement_session_id IS NOT NULL ORDER BY taken DESC LIMIT 1",
+        )
+        .bind(user_id)
+        .bind(from.to_rfc3339())
+        .bind(to.to_rfc3339())
+        .fetch_optional(&self.connection_pool)
+        .await
+        .map_err(|error| RetrieveError::LowLevelError {
+            description: error.to_string(),
+        })?;
+
+        match query_result {
+            None => Ok(None),
+            Some(row) => row
+                .try_get("measurement_session_id")
+                .map_err(|_| to_column_parse_error("measurement_session_id")),
+        }
+    }
+
+    async fn list_measurement_sessions(
+        &self,
+        user_id: String,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        discard_first: bool,
+    ) -> Result<Vec<MeasurementSessionEntity>, RetrieveError> {
+        // Readings without a session are treated as a session of one identified by the reading's own ID
+        let query = "WITH session_reading AS ( \
+                select COALESCE(measurement_session_id, reading_id) AS session_id, taken, systolic, diastolic, pulse, \
+                ROW_NUMBER() OVER (PARTITION BY COALESCE(measurement_session_id, reading_id) ORDER BY taken ASC) AS position, \
+                COUNT(*) OVER (PARTITION BY COALESCE(measurement_session_id, reading_id)) AS session_size \
+                from reading WHERE user_id = ? AND taken >= ? AND taken <= ? \
+            ), averaged_reading AS ( \
+                select *, (? = 0 OR position > 1 OR session_size = 1) AS averaged from session_reading \
+            ) \
+            select session_id, MIN(taken) AS started, MAX(taken) AS ended, COUNT(*) AS reading_count, \
+            SUM(averaged) AS averaged_count, AVG(CASE WHEN averaged THEN systolic END) AS systolic_mean, \
+            AVG(CASE WHEN averaged THEN diastolic END) AS diastolic_mean, AVG(CASE WHEN averaged THEN pulse END) AS pulse_mean \
+            from averaged_reading GROUP BY session_id ORDER BY started DESC";
+
         let query_result = sqlx::query(query)
             .bind(user_id)
-            .fetch_optional(&self.connection_pool)
+            .bind(from.to_rfc3339())
+            .bind(to.to_rfc3339())
+            .bind(discard_first)
+            .fetch_all(&self.connection_pool)
             .await
             .map_err(|error| RetrieveError::LowLevelError {
                 description: error.to_string(),
             })?;
 
-        let result: Option<f64> = query_result
-            .map(|row: SqliteRow| row.try_get("weight_kilograms").ok())
-            .flatten();
+        query_result
+            .into_iter()
+            .map(deserialize_measurement_session)
+            .collect()
+    }
 
-        Ok(result)
+    async fn summarise(
+        &self,
+        user_id: String,
+        from: chrono::DateTime<chrono::Utc>,
+        to: chrono::DateTime<chrono::Utc>,
+    ) -> Result<ReadingSummaryEntity, RetrieveError> {
+        let query = format!(
+            "select COUNT(*) AS reading_count, {}, {}, {}, \
+            AVG(systolic - diastolic) AS pulse_pressure_mean, \
+            AVG(diastolic + (systolic - diastolic) / 3.0) AS mean_arterial_pressure_mean \
+            from reading WHERE user_id = ? AND taken >= ? AND taken <= ?",
+            summary_columns("systolic"),
+            summary_columns("diastolic"),
+            summary_columns("pulse")
+        );
+
+        let row = sqlx::query(&query)
+            .bind(user_id)
+            .bind(from.to_rfc3339())
+            .bind(to.to_rfc3339())
+            .fetch_one(&self.connection_pool)
+            .await
+            .map_err(|error| RetrieveError::LowLevelError {
+                description: error.to_string(),
+            })?;
+
+        let count: i64 = row
+            .try_get("reading_count")
+            .map_err(|_| to_column_parse_error("reading_count"))?;
+        let mean_pulse_pressure: Option<f64> = row
+            .try_get("pulse_pressure_mean")
+            .map_err(|_| to_column_parse_error("pulse_pressure_mean"))?;
+        let mean_arterial_pressure: Option<f64> = row
+            .try_get("mean_arterial_pressure_mean")
+            .map_err(|_| to_column_parse_error("mean_arterial_pressure_mean"))?;
+
+        Ok(ReadingSummaryEntity {
+            count,
+            systolic: deserialize_metric_summary(&row, "systolic")?,
+            diastolic: deserialize_metric_summary(&row, "diastolic")?,
+            pulse: deserialize_metric_summary(&row, "pulse")?,
+            mean_pulse_pressure,
+            mean_arterial_pressure,
+        })
+    }
+
+    async fn aggregate_by_bucket(
+        &self,
+        user_id: String,
+        buckets: &[TimeBucket],
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<BucketAggregateEntity>, RetrieveError> {
+        if buckets.is_empty() {
+            return Ok(vec![]);
+        }
+
+        // The bucket boundaries are worked out in the user's timezone beforehand and passed in as a table, since
+        // SQLite can only do date arithmetic in UTC or the server's local time
+        let bucket_values = vec!["(?, ?)"; buckets.len()].join(", ");
+
+        let query = format!(
+            "WITH buckets (bucket_start, bucket_end) AS (VALUES {}) \
+            select buckets.bucket_start AS bucket_start, buckets.bucket_end AS bucket_end, \
+            COUNT(reading.reading_id) AS reading_count, AVG(reading.systolic) AS systolic_mean, \
+            AVG(reading.diastolic) AS diastolic_mean, AVG(reading.pulse) AS pulse_mean, \
+            AVG(reading.weight_kilograms) AS weight_kilograms_mean, COUNT(reading.weight_kilograms) AS weight_count \
+            from buckets LEFT JOIN reading ON reading.user_id = ? \
+            AND reading.taken >= buckets.bucket_start AND reading.taken < buckets.bucket_end \
+            AND reading.taken >= ? AND reading.taken <= ? \
+            GROUP BY buckets.bucket_start, buckets.bucket_end ORDER BY buckets.bucket_start ASC",
+            bucket_values
+        );
+
+        let mut sql_query = sqlx::query(&query);
+
+        for bucket in buckets {
+            sql_query = sql_query
+                .bind(bucket.start.to_rfc3339())
+                .bind(bucket.end.to_rfc3339());
+        }
+
+        let query_result = sql_query
+            .bind(user_id)
+            .bind(from.to_rfc3339())
+            .bind(to.to_rfc3339())
+            .fetch_all(&self.connection_pool)
+            .await
+            .map_err(|error| RetrieveError::LowLevelError {
+                description: error.to_string(),
+            })?;
+
+        query_result
+            .into_iter()
+            .map(deserialize_bucket_aggregate)
+            .collect()
     }
 }
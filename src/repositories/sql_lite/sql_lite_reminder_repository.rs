@@ -0,0 +1,179 @@
+use chrono::{DateTime, NaiveTime, Utc};
+use sqlx::{
+    Row,
+    sqlite::{SqlitePool, SqliteRow},
+};
+
+use crate::repositories::{
+    blood_pressure_readings_repository::{DeleteError, RetrieveError, SaveError},
+    reminder_repository::{ReminderEntity, ReminderRepository},
+    sql_lite::to_column_parse_error,
+};
+
+const TIME_OF_DAY_FORMAT: &str = "%H:%M";
+
+pub struct SqlLiteReminderRepository {
+    connection_pool: SqlitePool,
+}
+
+impl SqlLiteReminderRepository {
+    pub fn from_pool(pool: SqlitePool) -> SqlLiteReminderRepository {
+        SqlLiteReminderRepository {
+            connection_pool: pool,
+        }
+    }
+}
+
+fn time_of_day_to_column(time_of_day: NaiveTime) -> String {
+    time_of_day.format(TIME_OF_DAY_FORMAT).to_string()
+}
+
+fn deserialize_row(row: SqliteRow) -> Result<ReminderEntity, RetrieveError> {
+    let reminder_id: String = row
+        .try_get("reminder_id")
+        .map_err(|_| to_column_parse_error("reminder_id"))?;
+
+    let user_id: String = row
+        .try_get("user_id")
+        .map_err(|_| to_column_parse_error("user_id"))?;
+
+    let time_of_day_raw: String = row
+        .try_get("time_of_day")
+        .map_err(|_| to_column_parse_error("time_of_day"))?;
+
+    let time_of_day = NaiveTime::parse_from_str(&time_of_day_raw, TIME_OF_DAY_FORMAT)
+        .map_err(|_| to_column_parse_error("time_of_day"))?;
+
+    let window_minutes: u32 = row
+        .try_get("window_minutes")
+        .map_err(|_| to_column_parse_error("window_minutes"))?;
+
+    let enabled: bool = row
+        .try_get("enabled")
+        .map_err(|_| to_column_parse_error("enabled"))?;
+
+    let last_triggered_raw: Option<String> = row
+        .try_get("last_triggered")
+        .map_err(|_| to_column_parse_error("last_triggered"))?;
+
+    let last_triggered = last_triggered_raw
+        .map(|raw| DateTime::parse_from_rfc3339(&raw).map(|date| date.with_timezone(&Utc)))
+        .transpose()
+        .map_err(|_| to_column_parse_error("last_triggered"))?;
+
+    Ok(ReminderEntity {
+        reminder_id,
+        user_id,
+        time_of_day,
+        window_minutes,
+        enabled,
+        last_triggered,
+    })
+}
+
+impl ReminderRepository for SqlLiteReminderRepository {
+    async fn save(&self, entity: ReminderEntity) -> Result<(), SaveError> {
+        let result = sqlx::query(
+            "INSERT into reminders (reminder_id, user_id, time_of_day, window_minutes, enabled, last_triggered) \
+            VALUES(?,?,?,?,?,?)",
+        )
+        .bind(entity.reminder_id)
+        .bind(entity.user_id)
+        .bind(time_of_day_to_column(entity.time_of_day))
+        .bind(entity.window_minutes)
+        .bind(entity.enabled)
+        .bind(entity.last_triggered.map(|date| date.to_rfc3339()))
+        .execute(&self.connection_pool)
+        .await;
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(error) => Err(SaveError::LowLevelError {
+                description: error.to_string(),
+            }),
+        }
+    }
+
+    async fn update(&self, entity: ReminderEntity) -> Result<ReminderEntity, SaveError> {
+        let row = sqlx::query(
+            "UPDATE reminders SET time_of_day = ?, window_minutes = ?, enabled = ? \
+            WHERE reminder_id = ? AND user_id = ? RETURNING *",
+        )
+        .bind(time_of_day_to_column(entity.time_of_day))
+        .bind(entity.window_minutes)
+        .bind(entity.enabled)
+        .bind(entity.reminder_id)
+        .bind(entity.user_id)
+        .fetch_optional(&self.connection_pool)
+        .await
+        .map_err(|error| SaveError::LowLevelError {
+            description: error.to_string(),
+        })?;
+
+        match row {
+            None => Err(SaveError::NotFound),
+            Some(row) => deserialize_row(row).map_err(|_| SaveError::LowLevelError {
+                description: "Could not deserialize updated reminder".to_string(),
+            }),
+        }
+    }
+
+    async fn delete(&self, user_id: String, reminder_id: String) -> Result<(), DeleteError> {
+        let result = sqlx::query("DELETE FROM reminders WHERE reminder_id = ? AND user_id = ?")
+            .bind(reminder_id)
+            .bind(user_id)
+            .execute(&self.connection_pool)
+            .await
+            .map_err(|error| DeleteError::LowLevelError {
+                description: error.to_string(),
+            })?;
+
+        match result.rows_affected() {
+            0 => Err(DeleteError::NotFound),
+            _ => Ok(()),
+        }
+    }
+
+    async fn list(&self, user_id: String) -> Result<Vec<ReminderEntity>, RetrieveError> {
+        let query_result =
+            sqlx::query("select * from reminders WHERE user_id = ? ORDER BY time_of_day ASC")
+                .bind(user_id)
+                .fetch_all(&self.connection_pool)
+                .await
+                .map_err(|error| RetrieveError::LowLevelError {
+                    description: error.to_string(),
+                })?;
+
+        query_result.into_iter().map(deserialize_row).collect()
+    }
+
+    async fn list_enabled(&self) -> Result<Vec<ReminderEntity>, RetrieveError> {
+        let query_result = sqlx::query("select * from reminders WHERE enabled = 1")
+            .fetch_all(&self.connection_pool)
+            .await
+            .map_err(|error| RetrieveError::LowLevelError {
+                description: error.to_string(),
+            })?;
+
+        query_result.into_iter().map(deserialize_row).collect()
+    }
+
+    async fn mark_triggered(
+        &self,
+        reminder_id: String,
+        triggered: DateTime<Utc>,
+    ) -> Result<(), SaveError> {
+        let result = sqlx::query("UPDATE reminders SET last_triggered = ? WHERE reminder_id = ?")
+            .bind(triggered.to_rfc3339())
+            .bind(reminder_id)
+            .execute(&self.connection_pool)
+            .await;
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(error) => Err(SaveError::LowLevelError {
+                description: error.to_string(),
+            }),
+        }
+    }
+}
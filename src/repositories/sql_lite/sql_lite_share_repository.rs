@@ -0,0 +1,222 @@
+use chrono::{DateTime, Utc};
+use sqlx::{
+    Row,
+    sqlite::{SqlitePool, SqliteRow},
+};
+
+use crate::repositories::{
+    blood_pressure_readings_repository::{RetrieveError, SaveError},
+    share_repository::{ShareAccessEntity, ShareLinkEntity, ShareRepository},
+    sql_lite::to_column_parse_error,
+};
+
+pub struct SqlLiteShareRepository {
+    connection_pool: SqlitePool,
+}
+
+impl SqlLiteShareRepository {
+    pub fn from_pool(pool: SqlitePool) -> SqlLiteShareRepository {
+        SqlLiteShareRepository {
+            connection_pool: pool,
+        }
+    }
+}
+
+fn date_from_column(row: &SqliteRow, column_name: &str) -> Result<DateTime<Utc>, RetrieveError> {
+    let raw: String = row
+        .try_get(column_name)
+        .map_err(|_| to_column_parse_error(column_name))?;
+
+    DateTime::parse_from_rfc3339(&raw)
+        .map(|date| date.with_timezone(&Utc))
+        .map_err(|_| to_column_parse_error(column_name))
+}
+
+fn deserialize_share_link(row: SqliteRow) -> Result<ShareLinkEntity, RetrieveError> {
+    let share_id: String = row
+        .try_get("share_id")
+        .map_err(|_| to_column_parse_error("share_id"))?;
+
+    let user_id: String = row
+        .try_get("user_id")
+        .map_err(|_| to_column_parse_error("user_id"))?;
+
+    let token_hash: String = row
+        .try_get("token_hash")
+        .map_err(|_| to_column_parse_error("token_hash"))?;
+
+    let revoked_raw: Option<String> = row
+        .try_get("revoked")
+        .map_err(|_| to_column_parse_error("revoked"))?;
+
+    let revoked = revoked_raw
+        .map(|raw| DateTime::parse_from_rfc3339(&raw).map(|date| date.with_timezone(&Utc)))
+        .transpose()
+        .map_err(|_| to_column_parse_error("revoked"))?;
+
+    Ok(ShareLinkEntity {
+        share_id,
+        user_id,
+        token_hash,
+        from_inclusive: date_from_column(&row, "from_inclusive")?,
+        to_inclusive: date_from_column(&row, "to_inclusive")?,
+        expires: date_from_column(&row, "expires")?,
+        created: date_from_column(&row, "created")?,
+        revoked,
+    })
+}
+
+fn deserialize_access(row: SqliteRow) -> Result<ShareAccessEntity, RetrieveError> {
+    let access_id: String = row
+        .try_get("access_id")
+        .map_err(|_| to_column_parse_error("access_id"))?;
+
+    let share_id: String = row
+        .try_get("share_id")
+        .map_err(|_| to_column_parse_error("share_id"))?;
+
+    let user_agent: Option<String> = row
+        .try_get("user_agent")
+        .map_err(|_| to_column_parse_error("user_agent"))?;
+
+    Ok(ShareAccessEntity {
+        access_id,
+        share_id,
+        accessed: date_from_column(&row, "accessed")?,
+        user_agent,
+    })
+}
+
+impl ShareRepository for SqlLiteShareRepository {
+    async fn save(&self, entity: ShareLinkEntity) -> Result<(), SaveError> {
+        let result = sqlx::query(
+            "INSERT into share_links (share_id, user_id, token_hash, from_inclusive, to_inclusive, expires, \
+            created, revoked) VALUES(?,?,?,?,?,?,?,?)",
+        )
+        .bind(entity.share_id)
+        .bind(entity.user_id)
+        .bind(entity.token_hash)
+        .bind(entity.from_inclusive.to_rfc3339())
+        .bind(entity.to_inclusive.to_rfc3339())
+        .bind(entity.expires.to_rfc3339())
+        .bind(entity.created.to_rfc3339())
+        .bind(entity.revoked.map(|date| date.to_rfc3339()))
+        .execute(&self.connection_pool)
+        .await;
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(error) => Err(SaveError::LowLevelError {
+                description: error.to_string(),
+            }),
+        }
+    }
+
+    async fn list(&self, user_id: String) -> Result<Vec<ShareLinkEntity>, RetrieveError> {
+        let query_result =
+            sqlx::query("select * from share_links WHERE user_id = ? ORDER BY created DESC")
+                .bind(user_id)
+                .fetch_all(&self.connection_pool)
+                .await
+                .map_err(|error| RetrieveError::LowLevelError {
+                    description: error.to_string(),
+                })?;
+
+        query_result
+            .into_iter()
+            .map(deserialize_share_link)
+            .collect()
+    }
+
+    async fn get(
+        &self,
+        user_id: String,
+        share_id: String,
+    ) -> Result<Option<ShareLinkEntity>, RetrieveError> {
+        let row = sqlx::query("select * from share_links WHERE share_id = ? AND user_id = ?")
+            .bind(share_id)
+            .bind(user_id)
+            .fetch_optional(&self.connection_pool)
+            .await
+            .map_err(|error| RetrieveError::LowLevelError {
+                description: error.to_string(),
+            })?;
+
+        row.map(deserialize_share_link).transpose()
+    }
+
+    async fn get_by_token_hash(
+        &self,
+        token_hash: String,
+    ) -> Result<Option<ShareLinkEntity>, RetrieveError> {
+        let row = sqlx::query("select * from share_links WHERE token_hash = ?")
+            .bind(token_hash)
+            .fetch_optional(&self.connection_pool)
+            .await
+            .map_err(|error| RetrieveError::LowLevelError {
+                description: error.to_string(),
+            })?;
+
+        row.map(deserialize_share_link).transpose()
+    }
+
+    async fn revoke(
+        &self,
+        user_id: String,
+        share_id: String,
+        revoked: DateTime<Utc>,
+    ) -> Result<(), SaveError> {
+        // Revoking an already revoked link keeps the original time
+        let result = sqlx::query(
+            "UPDATE share_links SET revoked = COALESCE(revoked, ?) WHERE share_id = ? AND user_id = ?",
+        )
+        .bind(revoked.to_rfc3339())
+        .bind(share_id)
+        .bind(user_id)
+        .execute(&self.connection_pool)
+        .await
+        .map_err(|error| SaveError::LowLevelError {
+            description: error.to_string(),
+        })?;
+
+        match result.rows_affected() {
+            0 => Err(SaveError::NotFound),
+            _ => Ok(()),
+        }
+    }
+
+    async fn record_access(&self, entity: ShareAccessEntity) -> Result<(), SaveError> {
+        let result = sqlx::query(
+            "INSERT into share_access_log (access_id, share_id, accessed, user_agent) VALUES(?,?,?,?)",
+        )
+        .bind(entity.access_id)
+        .bind(entity.share_id)
+        .bind(entity.accessed.to_rfc3339())
+        .bind(entity.user_agent)
+        .execute(&self.connection_pool)
+        .await;
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(error) => Err(SaveError::LowLevelError {
+                description: error.to_string(),
+            }),
+        }
+    }
+
+    async fn list_accesses(
+        &self,
+        share_id: String,
+    ) -> Result<Vec<ShareAccessEntity>, RetrieveError> {
+        let query_result =
+            sqlx::query("select * from share_access_log WHERE share_id = ? ORDER BY accessed DESC")
+                .bind(share_id)
+                .fetch_all(&self.connection_pool)
+                .await
+                .map_err(|error| RetrieveError::LowLevelError {
+                    description: error.to_string(),
+                })?;
+
+        query_result.into_iter().map(deserialize_access).collect()
+    }
+}
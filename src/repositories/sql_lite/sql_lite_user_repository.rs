@@ -0,0 +1,203 @@
+use chrono_tz::Tz;
+use sqlx::{
+    Row,
+    sqlite::{SqlitePool, SqliteRow},
+};
+
+use crate::{
+    classification::blood_pressure_category::ClassificationGuideline,
+    repositories::{
+        blood_pressure_readings_repository::{RetrieveError, SaveError},
+        sql_lite::to_column_parse_error,
+        user_repository::{UserProfileEntity, UserRepository},
+    },
+    units::weight::WeightUnit,
+};
+
+pub struct SqlLiteUserRepository {
+    connection_pool: SqlitePool,
+}
+
+impl SqlLiteUserRepository {
+    pub fn from_pool(pool: SqlitePool) -> SqlLiteUserRepository {
+        SqlLiteUserRepository {
+            connection_pool: pool,
+        }
+    }
+}
+
+fn guideline_to_column(guideline: ClassificationGuideline) -> &'static str {
+    match guideline {
+        ClassificationGuideline::AccAha2017 => "acc_aha_2017",
+        ClassificationGuideline::Esh2023 => "esh_2023",
+    }
+}
+
+fn guideline_from_column(value: &str) -> Result<ClassificationGuideline, RetrieveError> {
+    match value {
+        "acc_aha_2017" => Ok(ClassificationGuideline::AccAha2017),
+        "esh_2023" => Ok(ClassificationGuideline::Esh2023),
+        _ => Err(to_column_parse_error("classification_guideline")),
+    }
+}
+
+fn weight_unit_to_column(weight_unit: WeightUnit) -> &'static str {
+    match weight_unit {
+        WeightUnit::Kilograms => "kilograms",
+        WeightUnit::Pounds => "pounds",
+        WeightUnit::StonesAndPounds => "stones_and_pounds",
+    }
+}
+
+fn weight_unit_from_column(value: &str) -> Result<WeightUnit, RetrieveError> {
+    match value {
+        "kilograms" => Ok(WeightUnit::Kilograms),
+        "pounds" => Ok(WeightUnit::Pounds),
+        "stones_and_pounds" => Ok(WeightUnit::StonesAndPounds),
+        _ => Err(to_column_parse_error("weight_unit")),
+    }
+}
+
+fn deserialize_row(row: SqliteRow) -> Result<UserProfileEntity, RetrieveError> {
+    let user_id: String = row
+        .try_get("user_id")
+        .map_err(|_| to_column_parse_error("user_id"))?;
+
+    let display_name: Option<String> = row
+        .try_get("display_name")
+        .map_err(|_| to_column_parse_error("display_name"))?;
+
+    let email: Option<String> = row
+        .try_get("email")
+        .map_err(|_| to_column_parse_error("email"))?;
+
+    let timezone_raw: String = row
+        .try_get("timezone")
+        .map_err(|_| to_column_parse_error("timezone"))?;
+
+    let timezone: Tz = timezone_raw
+        .parse()
+        .map_err(|_| to_column_parse_error("timezone"))?;
+
+    let guideline_raw: String = row
+        .try_get("classification_guideline")
+        .map_err(|_| to_column_parse_error("classification_guideline"))?;
+
+    let height_centimetres: Option<f64> = row
+        .try_get("height_centimetres")
+        .map_err(|_| to_column_parse_error("height_centimetres"))?;
+
+    let weight_unit_raw: String = row
+        .try_get("weight_unit")
+        .map_err(|_| to_column_parse_error("weight_unit"))?;
+
+    let target_systolic: i32 = row
+        .try_get("target_systolic")
+        .map_err(|_| to_column_parse_error("target_systolic"))?;
+
+    let target_diastolic: i32 = row
+        .try_get("target_diastolic")
+        .map_err(|_| to_column_parse_error("target_diastolic"))?;
+
+    let target_pulse_min: Option<i32> = row
+        .try_get("target_pulse_min")
+        .map_err(|_| to_column_parse_error("target_pulse_min"))?;
+
+    let target_pulse_max: Option<i32> = row
+        .try_get("target_pulse_max")
+        .map_err(|_| to_column_parse_error("target_pulse_max"))?;
+
+    Ok(UserProfileEntity {
+        user_id,
+        display_name,
+        email,
+        timezone,
+        classification_guideline: guideline_from_column(&guideline_raw)?,
+        height_centimetres,
+        weight_unit: weight_unit_from_column(&weight_unit_raw)?,
+        target_systolic,
+        target_diastolic,
+        target_pulse_min,
+        target_pulse_max,
+    })
+}
+
+impl UserRepository for SqlLiteUserRepository {
+    async fn get_profile(&self, user_id: String) -> Result<UserProfileEntity, RetrieveError> {
+        let row = sqlx::query("select * from users WHERE user_id = ?")
+            .bind(&user_id)
+            .fetch_optional(&self.connection_pool)
+            .await
+            .map_err(|error| RetrieveError::LowLevelError {
+                description: error.to_string(),
+            })?;
+
+        match row {
+            None => Ok(UserProfileEntity::defaults_for(user_id)),
+            Some(row) => deserialize_row(row),
+        }
+    }
+
+    async fn save_profile(&self, entity: UserProfileEntity) -> Result<(), SaveError> {
+        let result = sqlx::query(
+            "INSERT INTO users (user_id, display_name, email, timezone, classification_guideline, height_centimetres, \
+            weight_unit, target_systolic, target_diastolic, target_pulse_min, target_pulse_max) \
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?) \
+            ON CONFLICT (user_id) DO UPDATE SET display_name = excluded.display_name, email = excluded.email, \
+            timezone = excluded.timezone, classification_guideline = excluded.classification_guideline, \
+            height_centimetres = excluded.height_centimetres, weight_unit = excluded.weight_unit, \
+            target_systolic = excluded.target_systolic, target_diastolic = excluded.target_diastolic, \
+            target_pulse_min = excluded.target_pulse_min, target_pulse_max = excluded.target_pulse_max",
+        )
+        .bind(entity.user_id)
+        .bind(entity.display_name)
+        .bind(entity.email)
+        .bind(entity.timezone.name())
+        .bind(guideline_to_column(entity.classification_guideline))
+        .bind(entity.height_centimetres)
+        .bind(weight_unit_to_column(entity.weight_unit))
+        .bind(entity.target_systolic)
+        .bind(entity.target_diastolic)
+        .bind(entity.target_pulse_min)
+        .bind(entity.target_pulse_max)
+        .execute(&self.connection_pool)
+        .await;
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(error) => Err(SaveError::LowLevelError {
+                description: error.to_string(),
+            }),
+        }
+    }
+
+    async fn create_or_refresh_profile(&self, entity: UserProfileEntity) -> Result<(), SaveError> {
+        let result = sqlx::query(
+            "INSERT INTO users (user_id, display_name, email, timezone, classification_guideline, height_centimetres, \
+            weight_unit, target_systolic, target_diastolic, target_pulse_min, target_pulse_max) \
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?) \
+            ON CONFLICT (user_id) DO UPDATE SET email = excluded.email, \
+            display_name = COALESCE(users.display_name, excluded.display_name)",
+        )
+        .bind(entity.user_id)
+        .bind(entity.display_name)
+        .bind(entity.email)
+        .bind(entity.timezone.name())
+        .bind(guideline_to_column(entity.classification_guideline))
+        .bind(entity.height_centimetres)
+        .bind(weight_unit_to_column(entity.weight_unit))
+        .bind(entity.target_systolic)
+        .bind(entity.target_diastolic)
+        .bind(entity.target_pulse_min)
+        .bind(entity.target_pulse_max)
+        .execute(&self.connection_pool)
+        .await;
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(error) => Err(SaveError::LowLevelError {
+                description: error.to_string(),
+            }),
+        }
+    }
+}
@@ -0,0 +1,294 @@
+use chrono::{DateTime, Utc};
+use sqlx::{
+    Row, SqliteConnection,
+    sqlite::{SqlitePool, SqliteRow},
+};
+use uuid::Uuid;
+
+use crate::repositories::{
+    blood_pressure_readings_repository::{DeleteError, RetrieveError, SaveError},
+    sql_lite::to_column_parse_error,
+    webhook_repository::{
+        WebhookDeliveryEntity, WebhookEntity, WebhookEvent, WebhookEventEntity, WebhookRepository,
+    },
+};
+
+pub struct SqlLiteWebhookRepository {
+    connection_pool: SqlitePool,
+}
+
+impl SqlLiteWebhookRepository {
+    pub fn from_pool(pool: SqlitePool) -> SqlLiteWebhookRepository {
+        SqlLiteWebhookRepository {
+            connection_pool: pool,
+        }
+    }
+}
+
+fn event_to_column(event: WebhookEvent) -> &'static str {
+    match event {
+        WebhookEvent::ReadingAdded => "reading_added",
+        WebhookEvent::AlertTriggered => "alert_triggered",
+    }
+}
+
+fn event_from_column(value: &str) -> Result<WebhookEvent, RetrieveError> {
+    match value {
+        "reading_added" => Ok(WebhookEvent::ReadingAdded),
+        "alert_triggered" => Ok(WebhookEvent::AlertTriggered),
+        _ => Err(to_column_parse_error("event")),
+    }
+}
+
+fn deserialize_webhook(row: SqliteRow) -> Result<WebhookEntity, RetrieveError> {
+    let webhook_id: String = row
+        .try_get("webhook_id")
+        .map_err(|_| to_column_parse_error("webhook_id"))?;
+
+    let user_id: String = row
+        .try_get("user_id")
+        .map_err(|_| to_column_parse_error("user_id"))?;
+
+    let url: String = row
+        .try_get("url")
+        .map_err(|_| to_column_parse_error("url"))?;
+
+    let secret: String = row
+        .try_get("secret")
+        .map_err(|_| to_column_parse_error("secret"))?;
+
+    let created_raw: String = row
+        .try_get("created")
+        .map_err(|_| to_column_parse_error("created"))?;
+
+    let created = DateTime::parse_from_rfc3339(&created_raw)
+        .map_err(|_| to_column_parse_error("created"))?
+        .with_timezone(&Utc);
+
+    Ok(WebhookEntity {
+        webhook_id,
+        user_id,
+        url,
+        secret,
+        created,
+    })
+}
+
+fn deserialize_delivery(row: SqliteRow) -> Result<WebhookDeliveryEntity, RetrieveError> {
+    let delivery_id: String = row
+        .try_get("delivery_id")
+        .map_err(|_| to_column_parse_error("delivery_id"))?;
+
+    let webhook_id: String = row
+        .try_get("webhook_id")
+        .map_err(|_| to_column_parse_error("webhook_id"))?;
+
+    let url: String = row
+        .try_get("url")
+        .map_err(|_| to_column_parse_error("url"))?;
+
+    let secret: String = row
+        .try_get("secret")
+        .map_err(|_| to_column_parse_error("secret"))?;
+
+    let event_raw: String = row
+        .try_get("event")
+        .map_err(|_| to_column_parse_error("event"))?;
+
+    let payload: String = row
+        .try_get("payload")
+        .map_err(|_| to_column_parse_error("payload"))?;
+
+    let attempts: u32 = row
+        .try_get("attempts")
+        .map_err(|_| to_column_parse_error("attempts"))?;
+
+    Ok(WebhookDeliveryEntity {
+        delivery_id,
+        webhook_id,
+        url,
+        secret,
+        event: event_from_column(&event_raw)?,
+        payload,
+        attempts,
+    })
+}
+
+/**
+ * Adds a delivery of each event to the outbox for each of the user's webhooks. Takes the connection of the
+ * transaction saving the change that caused the events.
+ */
+pub async fn insert_webhook_deliveries(
+    connection: &mut SqliteConnection,
+    user_id: &str,
+    events: &[WebhookEventEntity],
+) -> Result<(), sqlx::Error> {
+    if events.is_empty() {
+        return Ok(());
+    }
+
+    let webhook_ids: Vec<String> =
+        sqlx::query_scalar("select webhook_id from webhooks WHERE user_id = ?")
+            .bind(user_id)
+            .fetch_all(&mut *connection)
+            .await?;
+
+    let now = Utc::now().to_rfc3339();
+
+    for event in events {
+        for webhook_id in &webhook_ids {
+            sqlx::query(
+                "INSERT into webhook_outbox (delivery_id, webhook_id, event, payload, next_attempt) \
+                VALUES(?,?,?,?,?)",
+            )
+            .bind(Uuid::now_v7().to_string())
+            .bind(webhook_id)
+            .bind(event_to_column(event.event))
+            .bind(&event.payload)
+            .bind(&now)
+            .execute(&mut *connection)
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+impl WebhookRepository for SqlLiteWebhookRepository {
+    async fn save(&self, entity: WebhookEntity) -> Result<(), SaveError> {
+        let result = sqlx::query(
+            "INSERT into webhooks (webhook_id, user_id, url, secret, created) VALUES(?,?,?,?,?)",
+        )
+        .bind(entity.webhook_id)
+        .bind(entity.user_id)
+        .bind(entity.url)
+        .bind(entity.secret)
+        .bind(entity.created.to_rfc3339())
+        .execute(&self.connection_pool)
+        .await;
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(error) => Err(SaveError::LowLevelError {
+                description: error.to_string(),
+            }),
+        }
+    }
+
+    async fn list(&self, user_id: String) -> Result<Vec<WebhookEntity>, RetrieveError> {
+        let query_result =
+            sqlx::query("select * from webhooks WHERE user_id = ? ORDER BY created ASC")
+                .bind(user_id)
+                .fetch_all(&self.connection_pool)
+                .await
+                .map_err(|error| RetrieveError::LowLevelError {
+                    description: error.to_string(),
+                })?;
+
+        query_result.into_iter().map(deserialize_webhook).collect()
+    }
+
+    async fn delete(&self, user_id: String, webhook_id: String) -> Result<(), DeleteError> {
+        let result: Result<u64, sqlx::Error> = async {
+            let mut transaction = self.connection_pool.begin().await?;
+
+            let deleted = sqlx::query("DELETE FROM webhooks WHERE webhook_id = ? AND user_id = ?")
+                .bind(&webhook_id)
+                .bind(&user_id)
+                .execute(&mut *transaction)
+                .await?;
+
+            if deleted.rows_affected() > 0 {
+                sqlx::query("DELETE FROM webhook_outbox WHERE webhook_id = ?")
+                    .bind(&webhook_id)
+                    .execute(&mut *transaction)
+                    .await?;
+            }
+
+            transaction.commit().await?;
+
+            Ok(deleted.rows_affected())
+        }
+        .await;
+
+        let rows_affected = result.map_err(|error| DeleteError::LowLevelError {
+            description: error.to_string(),
+        })?;
+
+        match rows_affected {
+            0 => Err(DeleteError::NotFound),
+            _ => Ok(()),
+        }
+    }
+
+    async fn list_due_deliveries(
+        &self,
+        now: DateTime<Utc>,
+        limit: u32,
+    ) -> Result<Vec<WebhookDeliveryEntity>, RetrieveError> {
+        let query_result = sqlx::query(
+            "select webhook_outbox.*, webhooks.url, webhooks.secret from webhook_outbox \
+            JOIN webhooks ON webhooks.webhook_id = webhook_outbox.webhook_id \
+            WHERE webhook_outbox.status = 'pending' AND webhook_outbox.next_attempt <= ? \
+            ORDER BY webhook_outbox.next_attempt ASC LIMIT ?",
+        )
+        .bind(now.to_rfc3339())
+        .bind(limit)
+        .fetch_all(&self.connection_pool)
+        .await
+        .map_err(|error| RetrieveError::LowLevelError {
+            description: error.to_string(),
+        })?;
+
+        query_result.into_iter().map(deserialize_delivery).collect()
+    }
+
+    async fn mark_delivered(&self, delivery_id: String) -> Result<(), SaveError> {
+        let result = sqlx::query(
+            "UPDATE webhook_outbox SET status = 'delivered', attempts = attempts + 1, last_error = NULL \
+            WHERE delivery_id = ?",
+        )
+        .bind(delivery_id)
+        .execute(&self.connection_pool)
+        .await;
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(error) => Err(SaveError::LowLevelError {
+                description: error.to_string(),
+            }),
+        }
+    }
+
+    async fn mark_attempt_failed(
+        &self,
+        delivery_id: String,
+        attempts: u32,
+        next_attempt: Option<DateTime<Utc>>,
+        error: String,
+    ) -> Result<(), SaveError> {
+        let status = match next_attempt {
+            Some(_) => "pending",
+            None => "failed",
+        };
+
+        let result = sqlx::query(
+            "UPDATE webhook_outbox SET status = ?, attempts = ?, next_attempt = COALESCE(?, next_attempt), \
+            last_error = ? WHERE delivery_id = ?",
+        )
+        .bind(status)
+        .bind(attempts)
+        .bind(next_attempt.map(|date| date.to_rfc3339()))
+        .bind(error)
+        .bind(delivery_id)
+        .execute(&self.connection_pool)
+        .await;
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(error) => Err(SaveError::LowLevelError {
+                description: error.to_string(),
+            }),
+        }
+    }
+}
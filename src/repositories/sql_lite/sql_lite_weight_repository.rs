@@ -0,0 +1,129 @@
+use chrono::{DateTime, Utc};
+use sqlx::{
+    Row,
+    sqlite::{SqlitePool, SqliteRow},
+};
+
+use crate::repositories::{
+    blood_pressure_readings_repository::{RetrieveError, SaveError},
+    sql_lite::to_column_parse_error,
+    weight_repository::{WeightMeasurementEntity, WeightRepository},
+};
+
+// Weights recorded with a blood pressure reading stay on the reading table, so both sources are combined when reading
+const SELECT_ALL_WEIGHTS: &str = "select weight_measurement_id, user_id, weight_kilograms, taken, NULL AS reading_id \
+    from weight_measurement \
+    UNION ALL \
+    select reading_id AS weight_measurement_id, user_id, weight_kilograms, taken, reading_id \
+    from reading WHERE weight_kilograms IS NOT NULL";
+
+pub struct SqlLiteWeightRepository {
+    connection_pool: SqlitePool,
+}
+
+impl SqlLiteWeightRepository {
+    pub fn from_pool(pool: SqlitePool) -> SqlLiteWeightRepository {
+        SqlLiteWeightRepository {
+            connection_pool: pool,
+        }
+    }
+}
+
+fn deserialize_row(row: SqliteRow) -> Result<WeightMeasurementEntity, RetrieveError> {
+    let weight_measurement_id: String = row
+        .try_get("weight_measurement_id")
+        .map_err(|_| to_column_parse_error("weight_measurement_id"))?;
+
+    let user_id: String = row
+        .try_get("user_id")
+        .map_err(|_| to_column_parse_error("user_id"))?;
+
+    let weight_kilograms: f64 = row
+        .try_get("weight_kilograms")
+        .map_err(|_| to_column_parse_error("weight_kilograms"))?;
+
+    let taken_raw: String = row
+        .try_get("taken")
+        .map_err(|_| to_column_parse_error("taken"))?;
+
+    let taken = DateTime::parse_from_rfc3339(&taken_raw)
+        .map_err(|_| to_column_parse_error("taken"))?
+        .with_timezone(&Utc);
+
+    let reading_id: Option<String> = row
+        .try_get("reading_id")
+        .map_err(|_| to_column_parse_error("reading_id"))?;
+
+    Ok(WeightMeasurementEntity {
+        weight_measurement_id,
+        user_id,
+        weight_kilograms,
+        taken,
+        reading_id,
+    })
+}
+
+impl WeightRepository for SqlLiteWeightRepository {
+    async fn save(&self, entity: WeightMeasurementEntity) -> Result<(), SaveError> {
+        let result = sqlx::query(
+            "INSERT into weight_measurement (weight_measurement_id, user_id, weight_kilograms, taken) VALUES(?,?,?,?)",
+        )
+        .bind(entity.weight_measurement_id)
+        .bind(entity.user_id)
+        .bind(entity.weight_kilograms)
+        .bind(entity.taken.to_rfc3339())
+        .execute(&self.connection_pool)
+        .await;
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(error) => Err(SaveError::LowLevelError {
+                description: error.to_string(),
+            }),
+        }
+    }
+
+    async fn list(
+        &self,
+        user_id: String,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<WeightMeasurementEntity>, RetrieveError> {
+        let query = format!(
+            "select * from ({}) WHERE user_id = ? AND taken >= ? AND taken <= ? ORDER BY taken ASC",
+            SELECT_ALL_WEIGHTS
+        );
+
+        let query_result = sqlx::query(&query)
+            .bind(user_id)
+            .bind(from.to_rfc3339())
+            .bind(to.to_rfc3339())
+            .fetch_all(&self.connection_pool)
+            .await
+            .map_err(|error| RetrieveError::LowLevelError {
+                description: error.to_string(),
+            })?;
+
+        query_result.into_iter().map(deserialize_row).collect()
+    }
+
+    async fn get_latest(
+        &self,
+        user_id: String,
+    ) -> Result<Option<WeightMeasurementEntity>, RetrieveError> {
+        let query = format!(
+            "select * from ({}) WHERE user_id = ? ORDER BY taken DESC LIMIT 1",
+            SELECT_ALL_WEIGHTS
+        );
+
+        let query_result = sqlx::query(&query)
+            .bind(user_id)
+            .fetch_optional(&self.connection_pool)
+            .await
+            .map_err(|error| RetrieveError::LowLevelError {
+                description: error.to_string(),
+            })?;
+
+        query_result.map(deserialize_row).transpose()
+    }
+}
@@ -0,0 +1,80 @@
+use chrono_tz::Tz;
+
+use crate::{
+    classification::blood_pressure_category::ClassificationGuideline,
+    repositories::blood_pressure_readings_repository::{RetrieveError, SaveError},
+    units::weight::WeightUnit,
+};
+
+// The usual treatment goal under both supported guidelines
+const DEFAULT_TARGET_SYSTOLIC: i32 = 130;
+const DEFAULT_TARGET_DIASTOLIC: i32 = 80;
+
+/**
+ * A user's profile and preferences, keyed by their OIDC subject
+ */
+pub struct UserProfileEntity {
+    pub user_id: String,
+    pub display_name: Option<String>,
+    /**
+     * Taken from the ID token claims when the user first logs in
+     */
+    pub email: Option<String>,
+    /**
+     * Used when grouping readings by day or time of day if the request doesn't specify a timezone
+     */
+    pub timezone: Tz,
+    pub classification_guideline: ClassificationGuideline,
+    /**
+     * Used to calculate BMI. None if the user hasn't told us their height
+     */
+    pub height_centimetres: Option<f64>,
+    pub weight_unit: WeightUnit,
+    /**
+     * Readings below both of these are within the user's target
+     */
+    pub target_systolic: i32,
+    pub target_diastolic: i32,
+    /**
+     * The pulse range (inclusive) the user should stay within. Either end may be left unset.
+     */
+    pub target_pulse_min: Option<i32>,
+    pub target_pulse_max: Option<i32>,
+}
+
+impl UserProfileEntity {
+    pub fn defaults_for(user_id: String) -> UserProfileEntity {
+        UserProfileEntity {
+            user_id,
+            display_name: None,
+            email: None,
+            timezone: Tz::UTC,
+            classification_guideline: ClassificationGuideline::default(),
+            height_centimetres: None,
+            weight_unit: WeightUnit::default(),
+            target_systolic: DEFAULT_TARGET_SYSTOLIC,
+            target_diastolic: DEFAULT_TARGET_DIASTOLIC,
+            target_pulse_min: None,
+            target_pulse_max: None,
+        }
+    }
+}
+
+pub trait UserRepository {
+    /**
+     * Retrieves the user's profile, or the default profile if the user doesn't have one yet
+     */
+    async fn get_profile(&self, user_id: String) -> Result<UserProfileEntity, RetrieveError>;
+
+    /**
+     * Creates or replaces the user's profile
+     */
+    async fn save_profile(&self, entity: UserProfileEntity) -> Result<(), SaveError>;
+
+    /**
+     * Creates the user's profile if they don't have one. For an existing profile only the details that come from the
+     * identity provider are refreshed, so that changes the user has made aren't overwritten each time they log in. The
+     * display name is only filled in if the user doesn't already have one.
+     */
+    async fn create_or_refresh_profile(&self, entity: UserProfileEntity) -> Result<(), SaveError>;
+}
@@ -0,0 +1,91 @@
+use chrono::{DateTime, Utc};
+
+use crate::repositories::blood_pressure_readings_repository::{
+    DeleteError, RetrieveError, SaveError,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WebhookEvent {
+    ReadingAdded,
+    AlertTriggered,
+}
+
+impl WebhookEvent {
+    /**
+     * The name sent to receivers in the payload and the event header
+     */
+    pub fn name(self) -> &'static str {
+        match self {
+            WebhookEvent::ReadingAdded => "reading.added",
+            WebhookEvent::AlertTriggered => "alert.triggered",
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct WebhookEntity {
+    pub webhook_id: String,
+    pub user_id: String,
+    pub url: String,
+    /**
+     * The key used to sign payloads so the receiver can check they came from us
+     */
+    pub secret: String,
+    pub created: DateTime<Utc>,
+}
+
+/**
+ * An event to add to the outbox for each of the user's webhooks. It is saved in the same transaction as the change
+ * that caused it, so a change is never saved without its event or the other way around.
+ */
+#[derive(Clone)]
+pub struct WebhookEventEntity {
+    pub event: WebhookEvent,
+    pub payload: String,
+}
+
+/**
+ * A payload waiting in the outbox to be sent to a webhook
+ */
+pub struct WebhookDeliveryEntity {
+    pub delivery_id: String,
+    pub webhook_id: String,
+    pub url: String,
+    pub secret: String,
+    pub event: WebhookEvent,
+    pub payload: String,
+    pub attempts: u32,
+}
+
+pub trait WebhookRepository {
+    async fn save(&self, entity: WebhookEntity) -> Result<(), SaveError>;
+
+    async fn list(&self, user_id: String) -> Result<Vec<WebhookEntity>, RetrieveError>;
+
+    /**
+     * Deletes the webhook along with any deliveries still waiting to be sent to it
+     */
+    async fn delete(&self, user_id: String, webhook_id: String) -> Result<(), DeleteError>;
+
+    /**
+     * Retrieves pending deliveries whose next attempt is due, oldest first
+     */
+    async fn list_due_deliveries(
+        &self,
+        now: DateTime<Utc>,
+        limit: u32,
+    ) -> Result<Vec<WebhookDeliveryEntity>, RetrieveError>;
+
+    async fn mark_delivered(&self, delivery_id: String) -> Result<(), SaveError>;
+
+    /**
+     * Records a failed attempt. The delivery is retried at `next_attempt`, or given up on if it is None.
+     */
+    async fn mark_attempt_failed(
+        &self,
+        delivery_id: String,
+        attempts: u32,
+        next_attempt: Option<DateTime<Utc>>,
+        error: String,
+    ) -> Result<(), SaveError>;
+}
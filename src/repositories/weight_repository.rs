@@ -0,0 +1,41 @@
+use chrono::{DateTime, Utc};
+
+use crate::repositories::blood_pressure_readings_repository::{RetrieveError, SaveError};
+
+#[derive(Clone)]
+pub struct WeightMeasurementEntity {
+    pub weight_measurement_id: String,
+    pub user_id: String,
+    pub weight_kilograms: f64,
+    pub taken: DateTime<Utc>,
+    /**
+     * Set when the weight was recorded as part of a blood pressure reading rather than on its own
+     */
+    pub reading_id: Option<String>,
+}
+
+/**
+ * Weight can be recorded on its own or along with a blood pressure reading. Both kinds are returned by the retrieval
+ * methods, but only weights recorded on their own are saved through this repository.
+ */
+pub trait WeightRepository {
+    async fn save(&self, entity: WeightMeasurementEntity) -> Result<(), SaveError>;
+
+    /**
+     * Retrieves the user's weights taken between the given times (inclusive) in ascending order of when they were taken
+     */
+    async fn list(
+        &self,
+        user_id: String,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<WeightMeasurementEntity>, RetrieveError>;
+
+    /**
+     * Retrieves the latest weight supplied by the user if it exists
+     */
+    async fn get_latest(
+        &self,
+        user_id: String,
+    ) -> Result<Option<WeightMeasurementEntity>, RetrieveError>;
+}
@@ -0,0 +1,165 @@
+use serde::{Deserialize, Serialize};
+
+const KILOGRAMS_PER_POUND: f64 = 0.45359237;
+pub const POUNDS_PER_STONE: f64 = 14.0;
+
+/**
+ * The unit a user prefers to see their weight in. Weights are always stored in kilograms.
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WeightUnit {
+    #[default]
+    Kilograms,
+    Pounds,
+    StonesAndPounds,
+}
+
+/**
+ * A weight in one of the supported units, e.g. {"unit": "stones_and_pounds", "stones": 12, "pounds": 3.5}
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "unit", rename_all = "snake_case")]
+pub enum Weight {
+    Kilograms { kilograms: f64 },
+    Pounds { pounds: f64 },
+    StonesAndPounds { stones: u32, pounds: f64 },
+}
+
+fn round_to_one_decimal_place(value: f64) -> f64 {
+    (value * 10.0).round() / 10.0
+}
+
+impl Weight {
+    pub fn to_kilograms(self) -> f64 {
+        match self {
+            Weight::Kilograms { kilograms } => kilograms,
+            Weight::Pounds { pounds } => pounds * KILOGRAMS_PER_POUND,
+            Weight::StonesAndPounds { stones, pounds } => {
+                (stones as f64 * POUNDS_PER_STONE + pounds) * KILOGRAMS_PER_POUND
+            }
+        }
+    }
+
+    /**
+     * Converts a stored weight into the given unit, rounded to one decimal place for display
+     */
+    pub fn from_kilograms(kilograms: f64, unit: WeightUnit) -> Weight {
+        let total_pounds = round_to_one_decimal_place(kilograms / KILOGRAMS_PER_POUND);
+
+        match unit {
+            WeightUnit::Kilograms => Weight::Kilograms {
+                kilograms: round_to_one_decimal_place(kilograms),
+            },
+            WeightUnit::Pounds => Weight::Pounds {
+                pounds: total_pounds,
+            },
+            WeightUnit::StonesAndPounds => {
+                // Rounding happens before splitting so that e.g. 13.96 lb doesn't come out as 0 st 14.0 lb
+                let stones = (total_pounds / POUNDS_PER_STONE).floor();
+
+                Weight::StonesAndPounds {
+                    stones: stones as u32,
+                    pounds: round_to_one_decimal_place(total_pounds - stones * POUNDS_PER_STONE),
+                }
+            }
+        }
+    }
+
+    pub fn display(&self) -> String {
+        match self {
+            Weight::Kilograms { kilograms } => format!("{} kg", kilograms),
+            Weight::Pounds { pounds } => format!("{} lb", pounds),
+            Weight::StonesAndPounds { stones, pounds } => format!("{} st {} lb", stones, pounds),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "{} is not close to {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn kilograms_round_trip() {
+        let weight = Weight::Kilograms { kilograms: 72.5 };
+
+        assert_close(weight.to_kilograms(), 72.5);
+        assert_eq!(
+            Weight::from_kilograms(weight.to_kilograms(), WeightUnit::Kilograms),
+            weight
+        );
+    }
+
+    #[test]
+    fn pounds_round_trip() {
+        let weight = Weight::Pounds { pounds: 160.5 };
+
+        assert_close(weight.to_kilograms(), 160.5 * KILOGRAMS_PER_POUND);
+        assert_eq!(
+            Weight::from_kilograms(weight.to_kilograms(), WeightUnit::Pounds),
+            weight
+        );
+    }
+
+    #[test]
+    fn stones_and_pounds_round_trip() {
+        let weight = Weight::StonesAndPounds {
+            stones: 11,
+            pounds: 6.5,
+        };
+
+        assert_close(weight.to_kilograms(), 160.5 * KILOGRAMS_PER_POUND);
+        assert_eq!(
+            Weight::from_kilograms(weight.to_kilograms(), WeightUnit::StonesAndPounds),
+            weight
+        );
+    }
+
+    #[test]
+    fn pounds_that_round_up_to_a_stone_carry_into_the_stones() {
+        let kilograms = 13.96 * KILOGRAMS_PER_POUND;
+
+        assert_eq!(
+            Weight::from_kilograms(kilograms, WeightUnit::StonesAndPounds),
+            Weight::StonesAndPounds {
+                stones: 1,
+                pounds: 0.0
+            }
+        );
+        assert_eq!(
+            Weight::from_kilograms(kilograms, WeightUnit::Pounds),
+            Weight::Pounds { pounds: 14.0 }
+        );
+    }
+
+    #[test]
+    fn kilograms_are_rounded_to_one_decimal_place() {
+        assert_eq!(
+            Weight::from_kilograms(72.46, WeightUnit::Kilograms),
+            Weight::Kilograms { kilograms: 72.5 }
+        );
+    }
+
+    #[test]
+    fn display_uses_the_unit() {
+        assert_eq!(Weight::Kilograms { kilograms: 72.5 }.display(), "72.5 kg");
+        assert_eq!(Weight::Pounds { pounds: 160.5 }.display(), "160.5 lb");
+        assert_eq!(
+            Weight::StonesAndPounds {
+                stones: 11,
+                pounds: 6.5
+            }
+            .display(),
+            "11 st 6.5 lb"
+        );
+    }
+}
@@ -0,0 +1,333 @@
+use std::{sync::Arc, time::Duration};
+
+use chrono::{TimeDelta, Utc};
+use hmac::{Hmac, Mac};
+use reqwest::Client;
+use sha2::Sha256;
+
+use crate::repositories::webhook_repository::{WebhookDeliveryEntity, WebhookRepository};
+
+const CHECK_INTERVAL_SECONDS: u64 = 10;
+const DELIVERY_BATCH_SIZE: u32 = 50;
+const REQUEST_TIMEOUT_SECONDS: u64 = 10;
+
+// With a 30 second initial backoff that doubles each time, the last attempt is a little over two hours after the first
+const MAX_ATTEMPTS: u32 = 9;
+const INITIAL_BACKOFF_SECONDS: i64 = 30;
+
+const SIGNATURE_HEADER: &str = "X-Webhook-Signature";
+const EVENT_HEADER: &str = "X-Webhook-Event";
+
+/**
+ * The hex encoded HMAC-SHA256 of the payload, keyed with the webhook's secret
+ */
+fn sign_payload(secret: &str, payload: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(payload.as_bytes());
+
+    hex::encode(mac.finalize().into_bytes())
+}
+
+fn backoff(attempts: u32) -> TimeDelta {
+    TimeDelta::seconds(INITIAL_BACKOFF_SECONDS * 2_i64.pow(attempts.saturating_sub(1)))
+}
+
+async fn send(http_client: &Client, delivery: &WebhookDeliveryEntity) -> Result<(), String> {
+    let response = http_client
+        .post(&delivery.url)
+        .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECONDS))
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header(EVENT_HEADER, delivery.event.name())
+        .header(
+            SIGNATURE_HEADER,
+            format!(
+                "sha256={}",
+                sign_payload(&delivery.secret, &delivery.payload)
+            ),
+        )
+        .body(delivery.payload.clone())
+        .send()
+        .await
+        .map_err(|error| error.to_string())?;
+
+    match response.status().is_success() {
+        true => Ok(()),
+        false => Err(format!("Receiver responded with {}", response.status())),
+    }
+}
+
+async fn deliver<W: WebhookRepository>(
+    webhook_repository: &Arc<W>,
+    http_client: &Client,
+    delivery: WebhookDeliveryEntity,
+) {
+    let result = match send(http_client, &delivery).await {
+        Ok(_) => {
+            webhook_repository
+                .mark_delivered(delivery.delivery_id)
+                .await
+        }
+        Err(error) => {
+            let attempts = delivery.attempts + 1;
+            let next_attempt = match attempts < MAX_ATTEMPTS {
+                true => Some(Utc::now() + backoff(attempts)),
+                false => None,
+            };
+
+            println!(
+                "Webhook delivery {} to webhook {} failed on attempt {}: {}",
+                delivery.delivery_id, delivery.webhook_id, attempts, error
+            );
+
+            webhook_repository
+                .mark_attempt_failed(delivery.delivery_id, attempts, next_attempt, error)
+                .await
+        }
+    };
+
+    if let Err(error) = result {
+        println!("Could not record webhook delivery: {:?}", error);
+    }
+}
+
+/**
+ * Sends the deliveries in the webhook outbox, retrying failed ones with exponential backoff. Runs until the server
+ * stops.
+ */
+pub async fn run_webhook_worker<W: WebhookRepository>(
+    webhook_repository: Arc<W>,
+    http_client: Client,
+) {
+    let mut interval = tokio::time::interval(Duration::from_secs(CHECK_INTERVAL_SECONDS));
+
+    loop {
+        interval.tick().await;
+
+        let deliveries = match webhook_repository
+            .list_due_deliveries(Utc::now(), DELIVERY_BATCH_SIZE)
+            .await
+        {
+            Ok(deliveries) => deliveries,
+            Err(error) => {
+                println!("Could not retrieve webhook deliveries: {:?}", error);
+                continue;
+            }
+        };
+
+        for delivery in deliveries {
+            deliver(&webhook_repository, &http_client, delivery).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use axum::{
+        Router,
+        extract::State,
+        http::{HeaderMap, StatusCode},
+        routing::post,
+    };
+    use chrono::DateTime;
+    use tokio::net::TcpListener;
+
+    use super::*;
+    use crate::repositories::{
+        blood_pressure_readings_repository::{DeleteError, RetrieveError, SaveError},
+        webhook_repository::{WebhookEntity, WebhookEvent},
+    };
+
+    const SECRET: &str = "secret";
+    const PAYLOAD: &str = r#"{"event":"reading.added"}"#;
+
+    struct FailedAttempt {
+        delivery_id: String,
+        attempts: u32,
+        next_attempt: Option<DateTime<Utc>>,
+    }
+
+    /**
+     * Records what the worker reports about each delivery
+     */
+    #[derive(Default)]
+    struct RecordingWebhookRepository {
+        delivered: Mutex<Vec<String>>,
+        failed: Mutex<Vec<FailedAttempt>>,
+    }
+
+    impl WebhookRepository for RecordingWebhookRepository {
+        async fn save(&self, _entity: WebhookEntity) -> Result<(), SaveError> {
+            unimplemented!()
+        }
+
+        async fn list(&self, _user_id: String) -> Result<Vec<WebhookEntity>, RetrieveError> {
+            unimplemented!()
+        }
+
+        async fn delete(&self, _user_id: String, _webhook_id: String) -> Result<(), DeleteError> {
+            unimplemented!()
+        }
+
+        async fn list_due_deliveries(
+            &self,
+            _now: DateTime<Utc>,
+            _limit: u32,
+        ) -> Result<Vec<WebhookDeliveryEntity>, RetrieveError> {
+            unimplemented!()
+        }
+
+        async fn mark_delivered(&self, delivery_id: String) -> Result<(), SaveError> {
+            self.delivered.lock().unwrap().push(delivery_id);
+            Ok(())
+        }
+
+        async fn mark_attempt_failed(
+            &self,
+            delivery_id: String,
+            attempts: u32,
+            next_attempt: Option<DateTime<Utc>>,
+            _error: String,
+        ) -> Result<(), SaveError> {
+            self.failed.lock().unwrap().push(FailedAttempt {
+                delivery_id,
+                attempts,
+                next_attempt,
+            });
+            Ok(())
+        }
+    }
+
+    struct ReceivedRequest {
+        headers: HeaderMap,
+        body: String,
+    }
+
+    #[derive(Clone)]
+    struct ReceiverState {
+        status: StatusCode,
+        received: Arc<Mutex<Vec<ReceivedRequest>>>,
+    }
+
+    async fn receive(
+        State(state): State<ReceiverState>,
+        headers: HeaderMap,
+        body: String,
+    ) -> StatusCode {
+        state
+            .received
+            .lock()
+            .unwrap()
+            .push(ReceivedRequest { headers, body });
+
+        state.status
+    }
+
+    /**
+     * Starts a receiver on a free local port that answers every request with the given status. Returns its URL and
+     * the requests it has received.
+     */
+    async fn start_receiver(status: StatusCode) -> (String, Arc<Mutex<Vec<ReceivedRequest>>>) {
+        let received = Arc::new(Mutex::new(vec![]));
+        let router = Router::new()
+            .route("/", post(receive))
+            .with_state(ReceiverState {
+                status,
+                received: Arc::clone(&received),
+            });
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+
+        tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+
+        (format!("http://{}/", address), received)
+    }
+
+    fn delivery(url: String, attempts: u32) -> WebhookDeliveryEntity {
+        WebhookDeliveryEntity {
+            delivery_id: "delivery".to_string(),
+            webhook_id: "webhook".to_string(),
+            url,
+            secret: SECRET.to_string(),
+            event: WebhookEvent::ReadingAdded,
+            payload: PAYLOAD.to_string(),
+            attempts,
+        }
+    }
+
+    fn http_client() -> Client {
+        // Requests to the local receiver must not go through any proxy configured in the environment
+        Client::builder().no_proxy().build().unwrap()
+    }
+
+    #[tokio::test]
+    async fn delivery_is_signed_with_the_webhook_secret() {
+        let (url, received) = start_receiver(StatusCode::OK).await;
+        let repository = Arc::new(RecordingWebhookRepository::default());
+
+        deliver(&repository, &http_client(), delivery(url, 0)).await;
+
+        let mut mac = Hmac::<Sha256>::new_from_slice(SECRET.as_bytes()).unwrap();
+        mac.update(PAYLOAD.as_bytes());
+        let expected_signature = format!("sha256={}", hex::encode(mac.finalize().into_bytes()));
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 1);
+        assert_eq!(received[0].body, PAYLOAD);
+        assert_eq!(
+            received[0].headers[SIGNATURE_HEADER].to_str().unwrap(),
+            expected_signature
+        );
+        assert_eq!(
+            received[0].headers[EVENT_HEADER].to_str().unwrap(),
+            "reading.added"
+        );
+
+        assert_eq!(*repository.delivered.lock().unwrap(), vec!["delivery"]);
+        assert!(repository.failed.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn failed_delivery_is_retried_after_the_backoff() {
+        let (url, _) = start_receiver(StatusCode::INTERNAL_SERVER_ERROR).await;
+        let repository = Arc::new(RecordingWebhookRepository::default());
+
+        let before = Utc::now();
+        deliver(&repository, &http_client(), delivery(url, 2)).await;
+        let after = Utc::now();
+
+        let failed = repository.failed.lock().unwrap();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].delivery_id, "delivery");
+        assert_eq!(failed[0].attempts, 3);
+
+        let next_attempt = failed[0].next_attempt.unwrap();
+        assert!(next_attempt >= before + backoff(3));
+        assert!(next_attempt <= after + backoff(3));
+
+        assert!(repository.delivered.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn delivery_is_given_up_on_after_the_last_attempt() {
+        let (url, _) = start_receiver(StatusCode::INTERNAL_SERVER_ERROR).await;
+        let repository = Arc::new(RecordingWebhookRepository::default());
+
+        deliver(&repository, &http_client(), delivery(url, MAX_ATTEMPTS - 1)).await;
+
+        let failed = repository.failed.lock().unwrap();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].attempts, MAX_ATTEMPTS);
+        assert!(failed[0].next_attempt.is_none());
+    }
+
+    #[test]
+    fn backoff_doubles_with_each_attempt() {
+        assert_eq!(backoff(1), TimeDelta::seconds(30));
+        assert_eq!(backoff(2), TimeDelta::seconds(60));
+        assert_eq!(backoff(MAX_ATTEMPTS), TimeDelta::seconds(30 * 256));
+    }
+}